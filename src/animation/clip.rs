@@ -0,0 +1,119 @@
+use crate::object_traits::{Rotation, Scale, SceneObject, Translation};
+
+use super::{Animatable, Track};
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct AnimationClip {
+    pub(crate) name: Option<String>,
+
+    pub(crate) translation: Option<Track<Translation>>,
+    pub(crate) scale: Option<Track<Scale>>,
+    pub(crate) rotation: Option<Track<Rotation>>,
+}
+
+impl AnimationClip {
+    pub fn empty() -> Self {
+        Self {
+            name: None,
+            translation: None,
+            scale: None,
+            rotation: None,
+        }
+    }
+
+    pub fn with_name(mut self, name: impl Into<String>) -> Self {
+        self.name = Some(name.into());
+        self
+    }
+
+    pub fn with_translation(mut self, track: Track<Translation>) -> Self {
+        self.translation = Some(track);
+        self
+    }
+
+    pub fn with_scale(mut self, track: Track<Scale>) -> Self {
+        self.scale = Some(track);
+        self
+    }
+
+    pub fn with_rotation(mut self, track: Track<Rotation>) -> Self {
+        self.rotation = Some(track);
+        self
+    }
+
+    pub fn name(&self) -> Option<&str> {
+        self.name.as_deref()
+    }
+
+    pub fn translation_track(&self) -> Option<&Track<Translation>> {
+        self.translation.as_ref()
+    }
+
+    pub fn scale_track(&self) -> Option<&Track<Scale>> {
+        self.scale.as_ref()
+    }
+
+    pub fn rotation_track(&self) -> Option<&Track<Rotation>> {
+        self.rotation.as_ref()
+    }
+
+    ///the duration of the longest track
+    pub fn duration(&self) -> f32 {
+        [
+            self.translation.as_ref().map(Track::duration),
+            self.scale.as_ref().map(Track::duration),
+            self.rotation.as_ref().map(Track::duration),
+        ]
+        .into_iter()
+        .flatten()
+        .fold(0., f32::max)
+    }
+
+    pub fn sample(&self, t: f32) -> ClipSample {
+        ClipSample {
+            translation: self.translation.as_ref().and_then(|track| track.sample(t)),
+            scale: self.scale.as_ref().and_then(|track| track.sample(t)),
+            rotation: self.rotation.as_ref().and_then(|track| track.sample(t)),
+        }
+    }
+}
+
+///the state of a clip at a given time, a `None` property is left untouched when applied
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ClipSample {
+    pub translation: Option<Translation>,
+    pub scale: Option<Scale>,
+    pub rotation: Option<Rotation>,
+}
+
+impl ClipSample {
+    ///blend two samples, `weight` being the weight of `other`.
+    ///If a property is only present in one sample it is kept as is.
+    pub fn blend(self, other: Self, weight: f32) -> Self {
+        fn blend_opt<T: Animatable>(a: Option<T>, b: Option<T>, w: f32) -> Option<T> {
+            match (a, b) {
+                (Some(a), Some(b)) => Some(a.lerp(b, w)),
+                (a, None) => a,
+                (None, b) => b,
+            }
+        }
+
+        Self {
+            translation: blend_opt(self.translation, other.translation, weight),
+            scale: blend_opt(self.scale, other.scale, weight),
+            rotation: blend_opt(self.rotation, other.rotation, weight),
+        }
+    }
+
+    pub fn apply_to<O: SceneObject + ?Sized>(&self, object: &mut O) {
+        if let Some(translation) = self.translation {
+            object.set_position(translation);
+        }
+        if let Some(scale) = self.scale {
+            object.set_scale(scale);
+        }
+        if let Some(rotation) = self.rotation {
+            object.set_rotation(rotation);
+        }
+    }
+}
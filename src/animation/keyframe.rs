@@ -0,0 +1,233 @@
+use crate::{
+    object_traits::{Rotation, Scale, Translation},
+    utils::types_util::{Arr3F32, Arr4F32, QuatF32},
+};
+
+/******************\
+*                 *
+*---Animatable----*
+*                 *
+\******************/
+
+///a value that can be stored in a [`Track`] and interpolated between keyframes.
+///Values are interpolated on their raw components, the tangents of cubic spline
+///keyframes are expressed in the same component space.
+pub trait Animatable: Copy {
+    fn to_components(self) -> Arr4F32;
+    fn from_components(components: Arr4F32) -> Self;
+
+    fn lerp(self, other: Self, t: f32) -> Self {
+        let a = self.to_components();
+        let b = other.to_components();
+        Self::from_components(core::array::from_fn(|i| a[i] + (b[i] - a[i]) * t))
+    }
+}
+
+impl Animatable for Translation {
+    #[inline]
+    fn to_components(self) -> Arr4F32 {
+        self.into()
+    }
+
+    #[inline]
+    fn from_components(components: Arr4F32) -> Self {
+        components.into()
+    }
+}
+
+impl Animatable for Scale {
+    #[inline]
+    fn to_components(self) -> Arr4F32 {
+        let [x, y, z]: Arr3F32 = self.into();
+        [x, y, z, 0.]
+    }
+
+    #[inline]
+    fn from_components([x, y, z, _]: Arr4F32) -> Self {
+        [x, y, z].into()
+    }
+}
+
+impl Animatable for Rotation {
+    #[inline]
+    fn to_components(self) -> Arr4F32 {
+        let (w, x, y, z): (f32, f32, f32, f32) = QuatF32::from(self).into();
+        [w, x, y, z]
+    }
+
+    ///the quaternion is normalized by `Rotation::from`
+    #[inline]
+    fn from_components([w, x, y, z]: Arr4F32) -> Self {
+        QuatF32::from((w, x, y, z)).into()
+    }
+
+    ///spherical linear interpolation, always taking the shortest path
+    fn lerp(self, other: Self, t: f32) -> Self {
+        let a = self.to_components();
+        let mut b = other.to_components();
+
+        let mut dot: f32 = a.iter().zip(b.iter()).map(|(a, b)| a * b).sum();
+        if dot < 0. {
+            b = b.map(|c| -c);
+            dot = -dot;
+        }
+
+        if dot > 0.9995 {
+            return Self::from_components(core::array::from_fn(|i| a[i] + (b[i] - a[i]) * t));
+        }
+
+        let theta = dot.clamp(-1., 1.).acos();
+        let sin_theta = theta.sin();
+        let wa = ((1. - t) * theta).sin() / sin_theta;
+        let wb = (t * theta).sin() / sin_theta;
+
+        Self::from_components(core::array::from_fn(|i| a[i] * wa + b[i] * wb))
+    }
+}
+
+/******************\
+*                 *
+*----Keyframes----*
+*                 *
+\******************/
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Interpolation {
+    Step,
+    #[default]
+    Linear,
+    CubicSpline,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Keyframe<T: Animatable> {
+    pub time: f32,
+    pub value: T,
+
+    ///only used by [`Interpolation::CubicSpline`]
+    pub in_tangent: Arr4F32,
+    ///only used by [`Interpolation::CubicSpline`]
+    pub out_tangent: Arr4F32,
+}
+
+impl<T: Animatable> Keyframe<T> {
+    pub fn new(time: f32, value: T) -> Self {
+        Self {
+            time,
+            value,
+            in_tangent: [0.; 4],
+            out_tangent: [0.; 4],
+        }
+    }
+
+    pub fn with_tangents(time: f32, value: T, in_tangent: Arr4F32, out_tangent: Arr4F32) -> Self {
+        Self {
+            time,
+            value,
+            in_tangent,
+            out_tangent,
+        }
+    }
+}
+
+/******************\
+*                 *
+*------Tracks-----*
+*                 *
+\******************/
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Track<T: Animatable> {
+    interpolation: Interpolation,
+    keyframes: Vec<Keyframe<T>>,
+}
+
+impl<T: Animatable> Track<T> {
+    pub fn new(interpolation: Interpolation) -> Self {
+        Self {
+            interpolation,
+            keyframes: vec![],
+        }
+    }
+
+    pub fn from_keyframes(interpolation: Interpolation, mut keyframes: Vec<Keyframe<T>>) -> Self {
+        keyframes.sort_by(|a, b| a.time.total_cmp(&b.time));
+        Self {
+            interpolation,
+            keyframes,
+        }
+    }
+
+    ///insert a keyframe, keeping the keyframes sorted by time
+    pub fn insert(&mut self, keyframe: Keyframe<T>) {
+        let index = self.keyframes.partition_point(|k| k.time <= keyframe.time);
+        self.keyframes.insert(index, keyframe);
+    }
+
+    pub fn keyframes(&self) -> &[Keyframe<T>] {
+        &self.keyframes
+    }
+
+    pub fn interpolation(&self) -> Interpolation {
+        self.interpolation
+    }
+
+    pub fn set_interpolation(&mut self, interpolation: Interpolation) {
+        self.interpolation = interpolation;
+    }
+
+    ///time of the last keyframe
+    pub fn duration(&self) -> f32 {
+        self.keyframes.last().map(|k| k.time).unwrap_or(0.)
+    }
+
+    ///returns the value of the track at the time `t`,
+    ///the value is clamped to the first and last keyframes
+    pub fn sample(&self, t: f32) -> Option<T> {
+        let first = self.keyframes.first()?;
+        let last = self.keyframes.last()?;
+
+        if t <= first.time {
+            return Some(first.value);
+        }
+        if t >= last.time {
+            return Some(last.value);
+        }
+
+        let next_index = self.keyframes.partition_point(|k| k.time <= t);
+        let k0 = &self.keyframes[next_index - 1];
+        let k1 = &self.keyframes[next_index];
+
+        let dt = k1.time - k0.time;
+        if dt <= 0. {
+            return Some(k1.value);
+        }
+        let s = (t - k0.time) / dt;
+
+        Some(match self.interpolation {
+            Interpolation::Step => k0.value,
+            Interpolation::Linear => k0.value.lerp(k1.value, s),
+            Interpolation::CubicSpline => cubic_spline(k0, k1, s, dt),
+        })
+    }
+}
+
+///cubic hermite spline, with the same conventions as glTF
+fn cubic_spline<T: Animatable>(k0: &Keyframe<T>, k1: &Keyframe<T>, s: f32, dt: f32) -> T {
+    let p0 = k0.value.to_components();
+    let p1 = k1.value.to_components();
+    let m0 = k0.out_tangent;
+    let m1 = k1.in_tangent;
+
+    let s2 = s * s;
+    let s3 = s2 * s;
+
+    let h00 = 2. * s3 - 3. * s2 + 1.;
+    let h10 = s3 - 2. * s2 + s;
+    let h01 = -2. * s3 + 3. * s2;
+    let h11 = s3 - s2;
+
+    T::from_components(core::array::from_fn(|i| {
+        h00 * p0[i] + h10 * dt * m0[i] + h01 * p1[i] + h11 * dt * m1[i]
+    }))
+}
@@ -0,0 +1,10 @@
+mod clip;
+mod keyframe;
+mod player;
+
+#[cfg(test)]
+mod test;
+
+pub use clip::*;
+pub use keyframe::*;
+pub use player::*;
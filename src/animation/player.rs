@@ -0,0 +1,224 @@
+use crate::object_traits::SceneObject;
+
+use super::{AnimationClip, ClipSample};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum LoopMode {
+    ///play the clip once and stay on the last frame
+    #[default]
+    Once,
+    Loop,
+    ///play the clip forward then backward
+    PingPong,
+}
+
+impl LoopMode {
+    ///map a playing time to a time inside the clip
+    pub fn local_time(self, time: f32, duration: f32) -> f32 {
+        if duration <= 0. {
+            return 0.;
+        }
+        match self {
+            LoopMode::Once => time.clamp(0., duration),
+            LoopMode::Loop => time.rem_euclid(duration),
+            LoopMode::PingPong => {
+                let t = time.rem_euclid(2. * duration);
+                if t > duration {
+                    2. * duration - t
+                } else {
+                    t
+                }
+            }
+        }
+    }
+}
+
+/******************\
+*                 *
+*------Player-----*
+*                 *
+\******************/
+
+#[derive(Debug, Clone)]
+pub struct AnimationPlayer {
+    clip: AnimationClip,
+    duration: f32,
+
+    time: f32,
+    speed: f32,
+    loop_mode: LoopMode,
+    playing: bool,
+}
+
+impl From<AnimationClip> for AnimationPlayer {
+    fn from(clip: AnimationClip) -> Self {
+        Self::new(clip)
+    }
+}
+
+impl AnimationPlayer {
+    pub fn new(clip: AnimationClip) -> Self {
+        Self {
+            duration: clip.duration(),
+            clip,
+            time: 0.,
+            speed: 1.,
+            loop_mode: LoopMode::Once,
+            playing: true,
+        }
+    }
+
+    pub fn with_loop_mode(mut self, loop_mode: LoopMode) -> Self {
+        self.loop_mode = loop_mode;
+        self
+    }
+
+    pub fn with_speed(mut self, speed: f32) -> Self {
+        self.speed = speed;
+        self
+    }
+
+    pub fn clip(&self) -> &AnimationClip {
+        &self.clip
+    }
+
+    pub fn set_clip(&mut self, clip: AnimationClip) {
+        self.duration = clip.duration();
+        self.clip = clip;
+        self.time = 0.;
+    }
+
+    pub fn play(&mut self) {
+        self.playing = true;
+    }
+
+    pub fn pause(&mut self) {
+        self.playing = false;
+    }
+
+    pub fn stop(&mut self) {
+        self.playing = false;
+        self.time = 0.;
+    }
+
+    pub fn is_playing(&self) -> bool {
+        self.playing
+    }
+
+    ///returns true if a `LoopMode::Once` player reached the end of its clip
+    pub fn is_finished(&self) -> bool {
+        self.loop_mode == LoopMode::Once && self.time >= self.duration
+    }
+
+    pub fn seek(&mut self, time: f32) {
+        self.time = time;
+    }
+
+    pub fn set_speed(&mut self, speed: f32) {
+        self.speed = speed;
+    }
+
+    pub fn set_loop_mode(&mut self, loop_mode: LoopMode) {
+        self.loop_mode = loop_mode;
+    }
+
+    pub fn get_time(&self) -> f32 {
+        self.time
+    }
+
+    pub fn get_speed(&self) -> f32 {
+        self.speed
+    }
+
+    pub fn get_loop_mode(&self) -> LoopMode {
+        self.loop_mode
+    }
+
+    ///the time inside the clip, after applying the loop mode
+    pub fn local_time(&self) -> f32 {
+        self.loop_mode.local_time(self.time, self.duration)
+    }
+
+    ///advance the player by `dt` seconds
+    pub fn advance(&mut self, dt: f32) {
+        if !self.playing {
+            return;
+        }
+        self.time += dt * self.speed;
+        if self.loop_mode == LoopMode::Once {
+            self.time = self.time.clamp(0., self.duration);
+        }
+    }
+
+    pub fn sample(&self) -> ClipSample {
+        self.clip.sample(self.local_time())
+    }
+
+    ///set the position, scale and rotation of `object` to the current state of the clip
+    pub fn apply<O: SceneObject + ?Sized>(&self, object: &mut O) {
+        self.sample().apply_to(object);
+    }
+}
+
+/******************\
+*                 *
+*------Blend------*
+*                 *
+\******************/
+
+///blend two players, each player keeps its own time
+#[derive(Debug, Clone)]
+pub struct AnimationBlend {
+    from: AnimationPlayer,
+    to: AnimationPlayer,
+
+    weight: f32,
+}
+
+impl AnimationBlend {
+    ///`weight` is the weight of `to`, clamped between 0. and 1.
+    pub fn new(from: AnimationPlayer, to: AnimationPlayer, weight: f32) -> Self {
+        Self {
+            from,
+            to,
+            weight: weight.clamp(0., 1.),
+        }
+    }
+
+    pub fn set_weight(&mut self, weight: f32) {
+        self.weight = weight.clamp(0., 1.);
+    }
+
+    pub fn get_weight(&self) -> f32 {
+        self.weight
+    }
+
+    pub fn from_player(&self) -> &AnimationPlayer {
+        &self.from
+    }
+
+    pub fn from_player_mut(&mut self) -> &mut AnimationPlayer {
+        &mut self.from
+    }
+
+    pub fn to_player(&self) -> &AnimationPlayer {
+        &self.to
+    }
+
+    pub fn to_player_mut(&mut self) -> &mut AnimationPlayer {
+        &mut self.to
+    }
+
+    pub fn advance(&mut self, dt: f32) {
+        self.from.advance(dt);
+        self.to.advance(dt);
+    }
+
+    pub fn sample(&self) -> ClipSample {
+        self.from.sample().blend(self.to.sample(), self.weight)
+    }
+
+    pub fn apply<O: SceneObject + ?Sized>(&self, object: &mut O) {
+        self.sample().apply_to(object);
+    }
+}
@@ -0,0 +1,164 @@
+mod test_track {
+    use crate::{
+        animation::{Interpolation, Keyframe, Track},
+        object_traits::{Scale, Translation},
+    };
+
+    fn translation_track(interpolation: Interpolation) -> Track<Translation> {
+        Track::from_keyframes(
+            interpolation,
+            vec![
+                Keyframe::new(1., [2., 0., 0., 1.].into()),
+                Keyframe::new(0., [0., 0., 0., 1.].into()),
+            ],
+        )
+    }
+
+    #[test]
+    fn test_keyframes_are_sorted() {
+        let mut track = translation_track(Interpolation::Linear);
+        track.insert(Keyframe::new(0.5, [5., 0., 0., 1.].into()));
+
+        let times: Vec<f32> = track.keyframes().iter().map(|k| k.time).collect();
+        assert_eq!(times, vec![0., 0.5, 1.]);
+    }
+
+    #[test]
+    fn test_sample_linear() {
+        let track = translation_track(Interpolation::Linear);
+        let expected: Translation = [1., 0., 0., 1.].into();
+        assert_eq!(track.sample(0.5), Some(expected));
+    }
+
+    #[test]
+    fn test_sample_step() {
+        let track = translation_track(Interpolation::Step);
+        let expected: Translation = [0., 0., 0., 1.].into();
+        assert_eq!(track.sample(0.9), Some(expected));
+    }
+
+    #[test]
+    fn test_sample_cubic_without_tangents() {
+        let track = translation_track(Interpolation::CubicSpline);
+        let expected: Translation = [1., 0., 0., 1.].into();
+        assert_eq!(track.sample(0.5), Some(expected));
+    }
+
+    #[test]
+    fn test_sample_is_clamped() {
+        let track = translation_track(Interpolation::Linear);
+        let first: Translation = [0., 0., 0., 1.].into();
+        let last: Translation = [2., 0., 0., 1.].into();
+        assert_eq!(track.sample(-1.), Some(first));
+        assert_eq!(track.sample(10.), Some(last));
+    }
+
+    #[test]
+    fn test_sample_empty_track() {
+        let track: Track<Scale> = Track::new(Interpolation::Linear);
+        assert_eq!(track.sample(0.), None);
+    }
+}
+
+mod test_rotation_slerp {
+    use crate::{animation::Animatable, object_traits::Rotation};
+
+    #[test]
+    fn test_slerp_half_way() {
+        let a = Rotation::zero();
+        let b = Rotation::from_axis(core::f32::consts::FRAC_PI_2, (0., 1., 0.));
+
+        let (angle, axis) = a.lerp(b, 0.5).to_axis();
+        assert!((angle - core::f32::consts::FRAC_PI_4).abs() < 1e-5);
+        assert!((axis.1 - 1.).abs() < 1e-5);
+    }
+}
+
+mod test_player {
+    use crate::{
+        animation::{
+            AnimationBlend, AnimationClip, AnimationPlayer, Interpolation, Keyframe, LoopMode,
+            Track,
+        },
+        object_traits::{Rotation, Scale, SceneObject, Translation},
+    };
+
+    struct Dummy {
+        position: Translation,
+        scale: Scale,
+        rotation: Rotation,
+    }
+
+    impl SceneObject for Dummy {
+        fn translate(&mut self, trans: Translation) {
+            self.position += trans;
+        }
+        fn set_position(&mut self, pos: Translation) {
+            self.position = pos;
+        }
+        fn scale(&mut self, scale: Scale) {
+            self.scale += scale;
+        }
+        fn set_scale(&mut self, scale: Scale) {
+            self.scale = scale;
+        }
+        fn rotate(&mut self, rotation: Rotation) {
+            self.rotation += rotation;
+        }
+        fn set_rotation(&mut self, rotation: Rotation) {
+            self.rotation = rotation;
+        }
+    }
+
+    fn clip(end: f32) -> AnimationClip {
+        AnimationClip::empty().with_translation(Track::from_keyframes(
+            Interpolation::Linear,
+            vec![
+                Keyframe::new(0., [0., 0., 0., 1.].into()),
+                Keyframe::new(2., [end, 0., 0., 1.].into()),
+            ],
+        ))
+    }
+
+    #[test]
+    fn test_loop_modes() {
+        assert_eq!(LoopMode::Once.local_time(3., 2.), 2.);
+        assert_eq!(LoopMode::Loop.local_time(3., 2.), 1.);
+        assert_eq!(LoopMode::PingPong.local_time(3., 2.), 1.);
+        assert_eq!(LoopMode::PingPong.local_time(3.5, 2.), 0.5);
+    }
+
+    #[test]
+    fn test_player_apply() {
+        let mut player = AnimationPlayer::new(clip(4.));
+        let mut object = Dummy {
+            position: Translation::zero(),
+            scale: Scale::zero(),
+            rotation: Rotation::zero(),
+        };
+
+        player.advance(1.);
+        player.apply(&mut object);
+
+        let expected: Translation = [2., 0., 0., 1.].into();
+        assert_eq!(object.position, expected);
+        assert_eq!(object.scale, Scale::zero());
+    }
+
+    #[test]
+    fn test_player_once_finishes() {
+        let mut player = AnimationPlayer::new(clip(4.));
+        player.advance(5.);
+        assert!(player.is_finished());
+        assert_eq!(player.get_time(), 2.);
+    }
+
+    #[test]
+    fn test_blend() {
+        let mut blend = AnimationBlend::new(clip(4.).into(), clip(8.).into(), 0.5);
+        blend.advance(2.);
+
+        let expected: Translation = [6., 0., 0., 1.].into();
+        assert_eq!(blend.sample().translation, Some(expected));
+    }
+}
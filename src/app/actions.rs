@@ -0,0 +1,424 @@
+use std::{collections::HashMap, fmt::Display, fs::read_to_string, path::Path};
+
+use glium::winit::{event::MouseButton, keyboard::KeyCode};
+
+use super::{GamepadAxis, GamepadButton, InputState};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum InputBinding {
+    Key(KeyCode),
+    Mouse(MouseButton),
+    Gamepad(GamepadButton),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum AxisBinding {
+    ///-1. when `negative` is pressed, 1. when `positive` is pressed
+    Buttons {
+        negative: InputBinding,
+        positive: InputBinding,
+    },
+    Gamepad(GamepadAxis),
+    MouseX,
+    MouseY,
+    ScrollX,
+    ScrollY,
+}
+
+///binds named actions and axes to inputs.
+///
+///It can be loaded from a file where each line is either :
+///```text
+///action <name> <input> [<input> ...]
+///axis <name> <axis> [<axis> ...]
+///```
+///with `<input>` one of `key:<KeyCode>`, `mouse:<Left|Right|Middle|Back|Forward|number>`,
+///`gamepad:<GamepadButton>`, and `<axis>` one of `keys:<input>,<input>`, `gamepad:<GamepadAxis>`,
+///`mouse:<x|y>`, `scroll:<x|y>`. Everything after a `#` is a comment.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ActionMap {
+    actions: HashMap<String, Vec<InputBinding>>,
+    axes: HashMap<String, Vec<AxisBinding>>,
+}
+
+impl ActionMap {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn bind_action(&mut self, name: impl Into<String>, input: InputBinding) {
+        self.actions.entry(name.into()).or_default().push(input);
+    }
+
+    pub fn bind_axis(&mut self, name: impl Into<String>, axis: AxisBinding) {
+        self.axes.entry(name.into()).or_default().push(axis);
+    }
+
+    pub fn unbind_action(&mut self, name: &str) {
+        self.actions.remove(name);
+    }
+
+    pub fn unbind_axis(&mut self, name: &str) {
+        self.axes.remove(name);
+    }
+
+    pub fn action_bindings(&self, name: &str) -> &[InputBinding] {
+        self.actions
+            .get(name)
+            .map(Vec::as_slice)
+            .unwrap_or_default()
+    }
+
+    pub fn axis_bindings(&self, name: &str) -> &[AxisBinding] {
+        self.axes.get(name).map(Vec::as_slice).unwrap_or_default()
+    }
+
+    pub fn read_from_file<P: AsRef<Path>>(path: P) -> Result<Self, ActionMapError> {
+        let data = read_to_string(path).map_err(ActionMapError::IOError)?;
+        data.parse()
+    }
+}
+
+impl std::str::FromStr for ActionMap {
+    type Err = ActionMapError;
+
+    fn from_str(data: &str) -> Result<Self, Self::Err> {
+        let mut map = ActionMap::new();
+
+        for line in data.lines() {
+            let line = line.split_once('#').map(|(l, _)| l).unwrap_or(line);
+            let mut words = line.split_whitespace();
+
+            match words.next() {
+                None => (),
+                Some("action") => {
+                    let name = words
+                        .next()
+                        .ok_or(ActionMapError::InvalidLine(line.to_string()))?;
+                    for word in words {
+                        map.bind_action(name, parse_input(word)?);
+                    }
+                }
+                Some("axis") => {
+                    let name = words
+                        .next()
+                        .ok_or(ActionMapError::InvalidLine(line.to_string()))?;
+                    for word in words {
+                        map.bind_axis(name, parse_axis(word)?);
+                    }
+                }
+                Some(_) => return Err(ActionMapError::InvalidLine(line.to_string())),
+            }
+        }
+
+        Ok(map)
+    }
+}
+
+/******************\
+*                 *
+*-----Queries-----*
+*                 *
+\******************/
+
+impl InputBinding {
+    pub fn is_pressed(&self, input: &InputState) -> bool {
+        match *self {
+            InputBinding::Key(key) => input.is_key_pressed(key),
+            InputBinding::Mouse(button) => input.is_mouse_button_pressed(button),
+            InputBinding::Gamepad(button) => input.is_gamepad_button_pressed(button),
+        }
+    }
+
+    pub fn is_just_pressed(&self, input: &InputState) -> bool {
+        match *self {
+            InputBinding::Key(key) => input.is_key_just_pressed(key),
+            InputBinding::Mouse(button) => input.is_mouse_button_just_pressed(button),
+            InputBinding::Gamepad(button) => input.is_gamepad_button_just_pressed(button),
+        }
+    }
+
+    pub fn is_just_released(&self, input: &InputState) -> bool {
+        match *self {
+            InputBinding::Key(key) => input.is_key_just_released(key),
+            InputBinding::Mouse(button) => input.is_mouse_button_just_released(button),
+            InputBinding::Gamepad(button) => input.is_gamepad_button_just_released(button),
+        }
+    }
+}
+
+impl AxisBinding {
+    pub fn value(&self, input: &InputState) -> f32 {
+        match self {
+            AxisBinding::Buttons { negative, positive } => {
+                let mut value = 0.;
+                if negative.is_pressed(input) {
+                    value -= 1.;
+                }
+                if positive.is_pressed(input) {
+                    value += 1.;
+                }
+                value
+            }
+            AxisBinding::Gamepad(axis) => input.gamepad_axis(*axis),
+            AxisBinding::MouseX => input.mouse_motion().0 as f32,
+            AxisBinding::MouseY => input.mouse_motion().1 as f32,
+            AxisBinding::ScrollX => input.scroll_delta().0,
+            AxisBinding::ScrollY => input.scroll_delta().1,
+        }
+    }
+}
+
+impl InputState {
+    pub fn is_action_pressed(&self, action: &str) -> bool {
+        self.actions
+            .action_bindings(action)
+            .iter()
+            .any(|binding| binding.is_pressed(self))
+    }
+
+    pub fn is_action_just_pressed(&self, action: &str) -> bool {
+        self.actions
+            .action_bindings(action)
+            .iter()
+            .any(|binding| binding.is_just_pressed(self))
+    }
+
+    pub fn is_action_just_released(&self, action: &str) -> bool {
+        self.actions
+            .action_bindings(action)
+            .iter()
+            .any(|binding| binding.is_just_released(self))
+    }
+
+    ///the value of the binding with the largest magnitude, 0. if the axis is not bound
+    pub fn axis(&self, axis: &str) -> f32 {
+        self.actions
+            .axis_bindings(axis)
+            .iter()
+            .map(|binding| binding.value(self))
+            .fold(0., |acc, value| {
+                if value.abs() > f32::abs(acc) {
+                    value
+                } else {
+                    acc
+                }
+            })
+    }
+}
+
+/******************\
+*                 *
+*-----Parsing-----*
+*                 *
+\******************/
+
+pub(crate) fn parse_input(word: &str) -> Result<InputBinding, ActionMapError> {
+    let unknown = || ActionMapError::UnknownInput(word.to_string());
+
+    match word.split_once(':') {
+        Some(("key", name)) => parse_key_code(name)
+            .map(InputBinding::Key)
+            .ok_or_else(unknown),
+        Some(("mouse", name)) => parse_mouse_button(name)
+            .map(InputBinding::Mouse)
+            .ok_or_else(unknown),
+        Some(("gamepad", name)) => parse_gamepad_button(name)
+            .map(InputBinding::Gamepad)
+            .ok_or_else(unknown),
+        _ => Err(unknown()),
+    }
+}
+
+pub(crate) fn parse_axis(word: &str) -> Result<AxisBinding, ActionMapError> {
+    let unknown = || ActionMapError::UnknownInput(word.to_string());
+
+    match word.split_once(':') {
+        Some(("keys", inputs)) => {
+            let (negative, positive) = inputs.split_once(',').ok_or_else(unknown)?;
+            let with_prefix = |name: &str| {
+                if name.contains(':') {
+                    parse_input(name)
+                } else {
+                    parse_input(&format!("key:{name}"))
+                }
+            };
+            Ok(AxisBinding::Buttons {
+                negative: with_prefix(negative)?,
+                positive: with_prefix(positive)?,
+            })
+        }
+        Some(("gamepad", name)) => parse_gamepad_axis(name)
+            .map(AxisBinding::Gamepad)
+            .ok_or_else(unknown),
+        Some(("mouse", "x")) => Ok(AxisBinding::MouseX),
+        Some(("mouse", "y")) => Ok(AxisBinding::MouseY),
+        Some(("scroll", "x")) => Ok(AxisBinding::ScrollX),
+        Some(("scroll", "y")) => Ok(AxisBinding::ScrollY),
+        _ => Err(unknown()),
+    }
+}
+
+fn parse_mouse_button(name: &str) -> Option<MouseButton> {
+    match name {
+        "Left" => Some(MouseButton::Left),
+        "Right" => Some(MouseButton::Right),
+        "Middle" => Some(MouseButton::Middle),
+        "Back" => Some(MouseButton::Back),
+        "Forward" => Some(MouseButton::Forward),
+        other => other.parse().ok().map(MouseButton::Other),
+    }
+}
+
+fn parse_gamepad_button(name: &str) -> Option<GamepadButton> {
+    use GamepadButton::*;
+    Some(match name {
+        "South" => South,
+        "East" => East,
+        "West" => West,
+        "North" => North,
+        "LeftBumper" => LeftBumper,
+        "RightBumper" => RightBumper,
+        "LeftTrigger" => LeftTrigger,
+        "RightTrigger" => RightTrigger,
+        "Select" => Select,
+        "Start" => Start,
+        "LeftStick" => LeftStick,
+        "RightStick" => RightStick,
+        "DPadUp" => DPadUp,
+        "DPadDown" => DPadDown,
+        "DPadLeft" => DPadLeft,
+        "DPadRight" => DPadRight,
+        _ => return None,
+    })
+}
+
+fn parse_gamepad_axis(name: &str) -> Option<GamepadAxis> {
+    use GamepadAxis::*;
+    Some(match name {
+        "LeftStickX" => LeftStickX,
+        "LeftStickY" => LeftStickY,
+        "RightStickX" => RightStickX,
+        "RightStickY" => RightStickY,
+        "LeftTrigger" => LeftTrigger,
+        "RightTrigger" => RightTrigger,
+        _ => return None,
+    })
+}
+
+///the names are the ones of the `KeyCode` variants, only the most common keys are supported
+pub(crate) fn parse_key_code(name: &str) -> Option<KeyCode> {
+    use KeyCode::*;
+
+    const LETTERS: [KeyCode; 26] = [
+        KeyA, KeyB, KeyC, KeyD, KeyE, KeyF, KeyG, KeyH, KeyI, KeyJ, KeyK, KeyL, KeyM, KeyN, KeyO,
+        KeyP, KeyQ, KeyR, KeyS, KeyT, KeyU, KeyV, KeyW, KeyX, KeyY, KeyZ,
+    ];
+    const DIGITS: [KeyCode; 10] = [
+        Digit0, Digit1, Digit2, Digit3, Digit4, Digit5, Digit6, Digit7, Digit8, Digit9,
+    ];
+    const NUMPAD_DIGITS: [KeyCode; 10] = [
+        Numpad0, Numpad1, Numpad2, Numpad3, Numpad4, Numpad5, Numpad6, Numpad7, Numpad8, Numpad9,
+    ];
+    const FUNCTIONS: [KeyCode; 12] = [F1, F2, F3, F4, F5, F6, F7, F8, F9, F10, F11, F12];
+
+    let single_char = |rest: &str| {
+        let mut chars = rest.chars();
+        match (chars.next(), chars.next()) {
+            (Some(c), None) => Some(c),
+            _ => None,
+        }
+    };
+
+    if let Some(letter) = name.strip_prefix("Key").and_then(single_char) {
+        if letter.is_ascii_uppercase() {
+            return Some(LETTERS[(letter as u8 - b'A') as usize]);
+        }
+    }
+    if let Some(digit) = name.strip_prefix("Digit").and_then(single_char) {
+        return digit.to_digit(10).map(|d| DIGITS[d as usize]);
+    }
+    if let Some(digit) = name.strip_prefix("Numpad").and_then(single_char) {
+        if let Some(d) = digit.to_digit(10) {
+            return Some(NUMPAD_DIGITS[d as usize]);
+        }
+    }
+    if let Some(n) = name.strip_prefix('F').and_then(|n| n.parse::<usize>().ok()) {
+        return (1..=12).contains(&n).then(|| FUNCTIONS[n - 1]);
+    }
+
+    Some(match name {
+        "Space" => Space,
+        "Enter" => Enter,
+        "Escape" => Escape,
+        "Tab" => Tab,
+        "Backspace" => Backspace,
+        "Delete" => Delete,
+        "Insert" => Insert,
+        "Home" => Home,
+        "End" => End,
+        "PageUp" => PageUp,
+        "PageDown" => PageDown,
+        "ArrowUp" => ArrowUp,
+        "ArrowDown" => ArrowDown,
+        "ArrowLeft" => ArrowLeft,
+        "ArrowRight" => ArrowRight,
+        "ShiftLeft" => ShiftLeft,
+        "ShiftRight" => ShiftRight,
+        "ControlLeft" => ControlLeft,
+        "ControlRight" => ControlRight,
+        "AltLeft" => AltLeft,
+        "AltRight" => AltRight,
+        "SuperLeft" => SuperLeft,
+        "SuperRight" => SuperRight,
+        "CapsLock" => CapsLock,
+        "Minus" => Minus,
+        "Equal" => Equal,
+        "Comma" => Comma,
+        "Period" => Period,
+        "Slash" => Slash,
+        "Backslash" => Backslash,
+        "Semicolon" => Semicolon,
+        "Quote" => Quote,
+        "Backquote" => Backquote,
+        "BracketLeft" => BracketLeft,
+        "BracketRight" => BracketRight,
+        "NumpadAdd" => NumpadAdd,
+        "NumpadSubtract" => NumpadSubtract,
+        "NumpadMultiply" => NumpadMultiply,
+        "NumpadDivide" => NumpadDivide,
+        "NumpadEnter" => NumpadEnter,
+        "NumpadDecimal" => NumpadDecimal,
+        _ => return None,
+    })
+}
+
+/******************\
+*                 *
+*------Errors-----*
+*                 *
+\******************/
+
+#[derive(Debug)]
+pub enum ActionMapError {
+    IOError(std::io::Error),
+
+    InvalidLine(String),
+    UnknownInput(String),
+}
+
+impl Display for ActionMapError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ActionMapError::IOError(e) => write!(f, "IO error :{}", e),
+            ActionMapError::InvalidLine(line) => {
+                write!(f, "invalid action map line has been found :{}", line)
+            }
+            ActionMapError::UnknownInput(input) => {
+                write!(f, "unknown input has been found :{}", input)
+            }
+        }
+    }
+}
+
+impl std::error::Error for ActionMapError {}
@@ -0,0 +1,63 @@
+use glium::winit::event_loop::{ActiveEventLoop, ControlFlow};
+
+///given to the `Scene` callbacks to control the `App`.
+///The requests are applied by the `App` once the callback returns
+pub struct SceneControl<'a> {
+    event_loop: Option<&'a ActiveEventLoop>,
+
+    exit: bool,
+    control_flow: Option<ControlFlow>,
+    redraw: bool,
+}
+
+impl<'a> SceneControl<'a> {
+    pub(crate) fn new(event_loop: &'a ActiveEventLoop) -> Self {
+        Self {
+            event_loop: Some(event_loop),
+            ..Self::headless()
+        }
+    }
+
+    ///a control without event loop, used by the `HeadlessApp`
+    pub(crate) fn headless() -> Self {
+        Self {
+            event_loop: None,
+            exit: false,
+            control_flow: None,
+            redraw: false,
+        }
+    }
+
+    ///`None` when the scene is run by a `HeadlessApp`
+    pub fn event_loop(&self) -> Option<&'a ActiveEventLoop> {
+        self.event_loop
+    }
+
+    ///the `App` will exit once the callback returns
+    pub fn exit(&mut self) {
+        self.exit = true;
+    }
+
+    pub fn is_exiting(&self) -> bool {
+        self.exit
+    }
+
+    ///the `App` keeps this control flow until another one is set.
+    ///With `ControlFlow::Wait` or `ControlFlow::WaitUntil`, frames are only drawn
+    ///when a redraw is requested
+    pub fn set_control_flow(&mut self, control_flow: ControlFlow) {
+        self.control_flow = Some(control_flow);
+    }
+
+    pub fn control_flow(&self) -> Option<ControlFlow> {
+        self.control_flow
+    }
+
+    pub fn request_redraw(&mut self) {
+        self.redraw = true;
+    }
+
+    pub fn redraw_requested(&self) -> bool {
+        self.redraw
+    }
+}
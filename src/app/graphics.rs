@@ -0,0 +1,197 @@
+use glium::{backend::glutin::Display, DrawError, Frame, Surface};
+use glutin::surface::WindowSurface;
+
+///everything a `Scene` draws goes through this trait,
+///so scenes can run without a GPU using a [`RecordingGraphics`] or a [`NullGraphics`]
+pub trait Graphics {
+    ///dimensions of the render target, in pixels
+    fn dimensions(&self) -> (u32, u32);
+
+    fn clear(&mut self, color: Option<[f32; 4]>, depth: Option<f32>);
+
+    ///run `draw` on the frame if there is one, `label` is kept by the recording backends
+    fn draw(
+        &mut self,
+        label: &str,
+        draw: &mut dyn FnMut(&mut Frame) -> Result<(), DrawError>,
+    ) -> Result<(), DrawError>;
+
+    ///the display used to create GPU resources, `None` when running headless
+    fn display(&self) -> Option<&Display<WindowSurface>> {
+        None
+    }
+
+    fn clear_color_and_depth(&mut self, color: [f32; 4], depth: f32) {
+        self.clear(Some(color), Some(depth));
+    }
+
+    ///called by the headless runner when its target is resized
+    fn resize(&mut self, _width: u32, _height: u32) {}
+
+    ///called once the frame has been drawn
+    fn end_frame(&mut self) {}
+}
+
+/******************\
+*                 *
+*------Glium------*
+*                 *
+\******************/
+
+pub struct GliumGraphics<'a> {
+    display: &'a Display<WindowSurface>,
+    frame: &'a mut Frame,
+}
+
+impl<'a> GliumGraphics<'a> {
+    pub fn new(display: &'a Display<WindowSurface>, frame: &'a mut Frame) -> Self {
+        Self { display, frame }
+    }
+
+    pub fn frame(&mut self) -> &mut Frame {
+        &mut *self.frame
+    }
+}
+
+impl Graphics for GliumGraphics<'_> {
+    fn dimensions(&self) -> (u32, u32) {
+        self.frame.get_dimensions()
+    }
+
+    fn clear(&mut self, color: Option<[f32; 4]>, depth: Option<f32>) {
+        self.frame.clear(
+            None,
+            color.map(|[r, g, b, a]| (r, g, b, a)),
+            false,
+            depth,
+            None,
+        );
+    }
+
+    fn draw(
+        &mut self,
+        _label: &str,
+        draw: &mut dyn FnMut(&mut Frame) -> Result<(), DrawError>,
+    ) -> Result<(), DrawError> {
+        draw(&mut *self.frame)
+    }
+
+    fn display(&self) -> Option<&Display<WindowSurface>> {
+        Some(self.display)
+    }
+}
+
+/******************\
+*                 *
+*----Recording----*
+*                 *
+\******************/
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum GraphicsCommand {
+    Clear {
+        color: Option<[f32; 4]>,
+        depth: Option<f32>,
+    },
+    Draw(String),
+    EndFrame,
+}
+
+///keeps every command it receives, draw closures are never called
+#[derive(Debug, Clone, Default)]
+pub struct RecordingGraphics {
+    dimensions: (u32, u32),
+    commands: Vec<GraphicsCommand>,
+}
+
+impl RecordingGraphics {
+    pub fn new(width: u32, height: u32) -> Self {
+        Self {
+            dimensions: (width, height),
+            commands: vec![],
+        }
+    }
+
+    pub fn commands(&self) -> &[GraphicsCommand] {
+        &self.commands
+    }
+
+    ///labels of the recorded draws
+    pub fn draws(&self) -> impl Iterator<Item = &str> {
+        self.commands.iter().filter_map(|command| match command {
+            GraphicsCommand::Draw(label) => Some(label.as_str()),
+            _ => None,
+        })
+    }
+
+    pub fn clear_commands(&mut self) {
+        self.commands.clear();
+    }
+}
+
+impl Graphics for RecordingGraphics {
+    fn dimensions(&self) -> (u32, u32) {
+        self.dimensions
+    }
+
+    fn clear(&mut self, color: Option<[f32; 4]>, depth: Option<f32>) {
+        self.commands.push(GraphicsCommand::Clear { color, depth });
+    }
+
+    fn draw(
+        &mut self,
+        label: &str,
+        _draw: &mut dyn FnMut(&mut Frame) -> Result<(), DrawError>,
+    ) -> Result<(), DrawError> {
+        self.commands.push(GraphicsCommand::Draw(label.to_string()));
+        Ok(())
+    }
+
+    fn resize(&mut self, width: u32, height: u32) {
+        self.dimensions = (width, height);
+    }
+
+    fn end_frame(&mut self) {
+        self.commands.push(GraphicsCommand::EndFrame);
+    }
+}
+
+/******************\
+*                 *
+*------Null-------*
+*                 *
+\******************/
+
+///ignores every command
+#[derive(Debug, Clone, Copy, Default)]
+pub struct NullGraphics {
+    dimensions: (u32, u32),
+}
+
+impl NullGraphics {
+    pub fn new(width: u32, height: u32) -> Self {
+        Self {
+            dimensions: (width, height),
+        }
+    }
+}
+
+impl Graphics for NullGraphics {
+    fn dimensions(&self) -> (u32, u32) {
+        self.dimensions
+    }
+
+    fn clear(&mut self, _color: Option<[f32; 4]>, _depth: Option<f32>) {}
+
+    fn draw(
+        &mut self,
+        _label: &str,
+        _draw: &mut dyn FnMut(&mut Frame) -> Result<(), DrawError>,
+    ) -> Result<(), DrawError> {
+        Ok(())
+    }
+
+    fn resize(&mut self, width: u32, height: u32) {
+        self.dimensions = (width, height);
+    }
+}
@@ -0,0 +1,215 @@
+use std::{collections::VecDeque, time::Duration};
+
+use glium::winit::event::{StartCause, WindowEvent};
+
+use super::{
+    ActionMap, FrameStats, GameLoop, Graphics, InputState, RecordingGraphics, Scene, SceneControl,
+};
+
+///runs a `Scene` without a window nor an event loop, with a simulated clock.
+///Useful to test the scene logic on a machine without GPU.
+///
+///The callbacks are called in the order of `App`: `Scene::handle_event` with
+///`StartCause::Init`, `Scene::resumed` then `Scene::init`. Each frame starts with
+///`Scene::handle_event` with `StartCause::Poll`, then the pending events are delivered.
+///
+///The app exits when a `WindowEvent::CloseRequested` is received or when the scene calls
+///`SceneControl::exit`, the control flow and the redraw requests are ignored.
+pub struct HeadlessApp<S: Scene<E>, G: Graphics = RecordingGraphics, E: 'static = ()> {
+    scene: S,
+    graphics: G,
+
+    game_loop: GameLoop,
+    input: InputState,
+    ///one tick when `None`
+    frame_time: Option<Duration>,
+    elapsed: Duration,
+
+    ///events are delivered before the frame they are scheduled for
+    events: VecDeque<(u64, WindowEvent)>,
+    user_events: VecDeque<E>,
+
+    initialized: bool,
+    exited: bool,
+}
+
+impl<S: Scene<E>, E: 'static> HeadlessApp<S, RecordingGraphics, E> {
+    pub fn new(scene: S) -> Self {
+        Self::with_graphics(scene, RecordingGraphics::new(800, 600))
+    }
+}
+
+impl<S: Scene<E>, G: Graphics, E: 'static> HeadlessApp<S, G, E> {
+    pub fn with_graphics(scene: S, graphics: G) -> Self {
+        Self {
+            scene,
+            graphics,
+            game_loop: GameLoop::default(),
+            input: InputState::default(),
+            frame_time: None,
+            elapsed: Duration::ZERO,
+            events: VecDeque::new(),
+            user_events: VecDeque::new(),
+            initialized: false,
+            exited: false,
+        }
+    }
+
+    pub fn with_tick_rate(mut self, tick_rate: f64) -> Self {
+        self.game_loop.timestep.set_tick_rate(tick_rate);
+        self
+    }
+
+    pub fn with_action_map(mut self, actions: ActionMap) -> Self {
+        self.input.set_action_map(actions);
+        self
+    }
+
+    ///the simulated duration of each frame, one tick by default
+    pub fn with_frame_time(mut self, frame_time: Duration) -> Self {
+        self.frame_time = Some(frame_time);
+        self
+    }
+
+    ///the event will be delivered before the next frame
+    pub fn push_window_event(&mut self, event: WindowEvent) {
+        let frame = self.game_loop.stats.frame_count();
+        self.push_window_event_at(frame, event);
+    }
+
+    ///the event will be delivered before the frame number `frame` (starting at 0)
+    pub fn push_window_event_at(&mut self, frame: u64, event: WindowEvent) {
+        let index = self.events.partition_point(|(f, _)| *f <= frame);
+        self.events.insert(index, (frame, event));
+    }
+
+    ///the event will be delivered before the next frame, after the window events
+    pub fn push_user_event(&mut self, event: E) {
+        self.user_events.push_back(event);
+    }
+
+    ///run `frames` frames, stops early if the app exits
+    pub fn run_frames(&mut self, frames: u64) {
+        if self.exited {
+            return;
+        }
+
+        if !self.initialized {
+            self.new_events(StartCause::Init);
+
+            let mut control = SceneControl::headless();
+            self.scene.resumed(&mut control);
+            self.apply_control(control);
+
+            self.scene.init();
+            self.initialized = true;
+        }
+
+        for _ in 0..frames {
+            if self.exited {
+                break;
+            }
+            self.new_events(StartCause::Poll);
+            self.dispatch_events();
+            if self.exited {
+                break;
+            }
+
+            let frame_time = self
+                .frame_time
+                .unwrap_or(self.game_loop.timestep.tick_duration());
+            let mut control = SceneControl::headless();
+            self.game_loop.frame(
+                &mut self.scene,
+                &mut self.graphics,
+                &mut self.input,
+                &mut control,
+                frame_time,
+            );
+            self.elapsed += frame_time;
+            self.apply_control(control);
+
+            let mut control = SceneControl::headless();
+            self.scene.about_to_wait(&mut control);
+            self.apply_control(control);
+        }
+
+        if self.exited {
+            self.scene.on_exiting(&mut SceneControl::headless());
+        }
+    }
+
+    fn apply_control(&mut self, control: SceneControl) {
+        self.exited |= control.is_exiting();
+    }
+
+    fn new_events(&mut self, cause: StartCause) {
+        let mut control = SceneControl::headless();
+        self.scene.handle_event(&mut control, cause);
+        self.apply_control(control);
+    }
+
+    fn dispatch_events(&mut self) {
+        let frame = self.game_loop.stats.frame_count();
+        while self.events.front().is_some_and(|(f, _)| *f <= frame) {
+            let Some((_, event)) = self.events.pop_front() else {
+                break;
+            };
+
+            match &event {
+                WindowEvent::Resized(size) => self.graphics.resize(size.width, size.height),
+                WindowEvent::CloseRequested => self.exited = true,
+                _ => (),
+            }
+            self.input.handle_window_event(&event);
+
+            let mut control = SceneControl::headless();
+            self.scene.handle_window_event(&mut control, &event);
+            self.apply_control(control);
+        }
+
+        while let Some(event) = self.user_events.pop_front() {
+            let mut control = SceneControl::headless();
+            self.scene.handle_user_event(&mut control, &event);
+            self.apply_control(control);
+        }
+    }
+
+    pub fn scene(&self) -> &S {
+        &self.scene
+    }
+
+    pub fn scene_mut(&mut self) -> &mut S {
+        &mut self.scene
+    }
+
+    pub fn graphics(&self) -> &G {
+        &self.graphics
+    }
+
+    pub fn graphics_mut(&mut self) -> &mut G {
+        &mut self.graphics
+    }
+
+    ///the input state can be modified directly to simulate the keyboard or a gamepad
+    pub fn input_mut(&mut self) -> &mut InputState {
+        &mut self.input
+    }
+
+    pub fn stats(&self) -> &FrameStats {
+        &self.game_loop.stats
+    }
+
+    ///the simulated time since the start
+    pub fn elapsed(&self) -> Duration {
+        self.elapsed
+    }
+
+    pub fn has_exited(&self) -> bool {
+        self.exited
+    }
+
+    pub fn into_scene(self) -> S {
+        self.scene
+    }
+}
@@ -0,0 +1,301 @@
+use std::collections::{HashMap, HashSet};
+
+use glium::winit::{
+    event::{DeviceEvent, ElementState, MouseButton, MouseScrollDelta, WindowEvent},
+    keyboard::{KeyCode, PhysicalKey},
+};
+
+use super::ActionMap;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum GamepadButton {
+    South,
+    East,
+    West,
+    North,
+    LeftBumper,
+    RightBumper,
+    LeftTrigger,
+    RightTrigger,
+    Select,
+    Start,
+    LeftStick,
+    RightStick,
+    DPadUp,
+    DPadDown,
+    DPadLeft,
+    DPadRight,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum GamepadAxis {
+    LeftStickX,
+    LeftStickY,
+    RightStickX,
+    RightStickY,
+    LeftTrigger,
+    RightTrigger,
+}
+
+///keeps track of the keyboard, mouse and gamepad.
+///The `just_*` states and the deltas are reset after each `Scene::update`
+#[derive(Debug, Clone, Default)]
+pub struct InputState {
+    keys: ButtonState<KeyCode>,
+    mouse_buttons: ButtonState<MouseButton>,
+    gamepad_buttons: ButtonState<GamepadButton>,
+    gamepad_axes: HashMap<GamepadAxis, f32>,
+
+    cursor_position: Option<(f64, f64)>,
+    cursor_delta: (f64, f64),
+    mouse_motion: (f64, f64),
+    scroll: (f32, f32),
+
+    pub(crate) actions: ActionMap,
+}
+
+impl InputState {
+    ///number of pixels corresponding to one line, for the touchpads scroll
+    pub const PIXELS_PER_LINE: f32 = 20.;
+
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_action_map(mut self, actions: ActionMap) -> Self {
+        self.actions = actions;
+        self
+    }
+
+    pub fn set_action_map(&mut self, actions: ActionMap) {
+        self.actions = actions;
+    }
+
+    pub fn action_map(&self) -> &ActionMap {
+        &self.actions
+    }
+
+    pub fn handle_window_event(&mut self, event: &WindowEvent) {
+        match event {
+            WindowEvent::KeyboardInput { event, .. } => {
+                if let PhysicalKey::Code(code) = event.physical_key {
+                    match event.state {
+                        ElementState::Pressed if !event.repeat => self.press_key(code),
+                        ElementState::Released => self.release_key(code),
+                        _ => (),
+                    }
+                }
+            }
+            WindowEvent::MouseInput { state, button, .. } => match state {
+                ElementState::Pressed => self.press_mouse_button(*button),
+                ElementState::Released => self.release_mouse_button(*button),
+            },
+            WindowEvent::CursorMoved { position, .. } => self.move_cursor(position.x, position.y),
+            WindowEvent::CursorLeft { .. } => self.cursor_position = None,
+            WindowEvent::MouseWheel { delta, .. } => match delta {
+                MouseScrollDelta::LineDelta(x, y) => self.scroll(*x, *y),
+                MouseScrollDelta::PixelDelta(position) => self.scroll(
+                    position.x as f32 / Self::PIXELS_PER_LINE,
+                    position.y as f32 / Self::PIXELS_PER_LINE,
+                ),
+            },
+            WindowEvent::Focused(false) => self.release_all(),
+            _ => (),
+        }
+    }
+
+    pub fn handle_device_event(&mut self, event: &DeviceEvent) {
+        if let DeviceEvent::MouseMotion { delta: (x, y) } = event {
+            self.mouse_motion.0 += x;
+            self.mouse_motion.1 += y;
+        }
+    }
+
+    ///reset the `just_*` states and the deltas, called after each `Scene::update`
+    pub fn end_tick(&mut self) {
+        self.keys.end_tick();
+        self.mouse_buttons.end_tick();
+        self.gamepad_buttons.end_tick();
+
+        self.cursor_delta = (0., 0.);
+        self.mouse_motion = (0., 0.);
+        self.scroll = (0., 0.);
+    }
+
+    ///release every button, used when the window loses the focus
+    pub fn release_all(&mut self) {
+        self.keys.release_all();
+        self.mouse_buttons.release_all();
+        self.gamepad_buttons.release_all();
+    }
+}
+
+/******************\
+*                 *
+*------Writes-----*
+*                 *
+\******************/
+
+impl InputState {
+    pub fn press_key(&mut self, key: KeyCode) {
+        self.keys.press(key);
+    }
+
+    pub fn release_key(&mut self, key: KeyCode) {
+        self.keys.release(key);
+    }
+
+    pub fn press_mouse_button(&mut self, button: MouseButton) {
+        self.mouse_buttons.press(button);
+    }
+
+    pub fn release_mouse_button(&mut self, button: MouseButton) {
+        self.mouse_buttons.release(button);
+    }
+
+    ///gamepads are not handled by winit, their state has to be given by the user
+    pub fn press_gamepad_button(&mut self, button: GamepadButton) {
+        self.gamepad_buttons.press(button);
+    }
+
+    pub fn release_gamepad_button(&mut self, button: GamepadButton) {
+        self.gamepad_buttons.release(button);
+    }
+
+    pub fn set_gamepad_axis(&mut self, axis: GamepadAxis, value: f32) {
+        self.gamepad_axes.insert(axis, value);
+    }
+
+    pub fn move_cursor(&mut self, x: f64, y: f64) {
+        if let Some((old_x, old_y)) = self.cursor_position {
+            self.cursor_delta.0 += x - old_x;
+            self.cursor_delta.1 += y - old_y;
+        }
+        self.cursor_position = Some((x, y));
+    }
+
+    ///`x` and `y` are in lines
+    pub fn scroll(&mut self, x: f32, y: f32) {
+        self.scroll.0 += x;
+        self.scroll.1 += y;
+    }
+}
+
+/******************\
+*                 *
+*------Reads------*
+*                 *
+\******************/
+
+impl InputState {
+    pub fn is_key_pressed(&self, key: KeyCode) -> bool {
+        self.keys.pressed.contains(&key)
+    }
+
+    pub fn is_key_just_pressed(&self, key: KeyCode) -> bool {
+        self.keys.just_pressed.contains(&key)
+    }
+
+    pub fn is_key_just_released(&self, key: KeyCode) -> bool {
+        self.keys.just_released.contains(&key)
+    }
+
+    pub fn pressed_keys(&self) -> impl Iterator<Item = &KeyCode> {
+        self.keys.pressed.iter()
+    }
+
+    pub fn is_mouse_button_pressed(&self, button: MouseButton) -> bool {
+        self.mouse_buttons.pressed.contains(&button)
+    }
+
+    pub fn is_mouse_button_just_pressed(&self, button: MouseButton) -> bool {
+        self.mouse_buttons.just_pressed.contains(&button)
+    }
+
+    pub fn is_mouse_button_just_released(&self, button: MouseButton) -> bool {
+        self.mouse_buttons.just_released.contains(&button)
+    }
+
+    pub fn is_gamepad_button_pressed(&self, button: GamepadButton) -> bool {
+        self.gamepad_buttons.pressed.contains(&button)
+    }
+
+    pub fn is_gamepad_button_just_pressed(&self, button: GamepadButton) -> bool {
+        self.gamepad_buttons.just_pressed.contains(&button)
+    }
+
+    pub fn is_gamepad_button_just_released(&self, button: GamepadButton) -> bool {
+        self.gamepad_buttons.just_released.contains(&button)
+    }
+
+    pub fn gamepad_axis(&self, axis: GamepadAxis) -> f32 {
+        self.gamepad_axes.get(&axis).copied().unwrap_or(0.)
+    }
+
+    ///cursor position in physical pixels, `None` if the cursor is outside of the window
+    pub fn cursor_position(&self) -> Option<(f64, f64)> {
+        self.cursor_position
+    }
+
+    ///cursor movement since the last update
+    pub fn cursor_delta(&self) -> (f64, f64) {
+        self.cursor_delta
+    }
+
+    ///raw mouse movement since the last update, not affected by the cursor acceleration
+    ///and still reported when the cursor is grabbed
+    pub fn mouse_motion(&self) -> (f64, f64) {
+        self.mouse_motion
+    }
+
+    ///scrolled lines since the last update
+    pub fn scroll_delta(&self) -> (f32, f32) {
+        self.scroll
+    }
+}
+
+/******************\
+*                 *
+*---ButtonState---*
+*                 *
+\******************/
+
+#[derive(Debug, Clone)]
+struct ButtonState<B> {
+    pressed: HashSet<B>,
+    just_pressed: HashSet<B>,
+    just_released: HashSet<B>,
+}
+
+impl<B> Default for ButtonState<B> {
+    fn default() -> Self {
+        Self {
+            pressed: HashSet::new(),
+            just_pressed: HashSet::new(),
+            just_released: HashSet::new(),
+        }
+    }
+}
+
+impl<B: Copy + Eq + core::hash::Hash> ButtonState<B> {
+    fn press(&mut self, button: B) {
+        if self.pressed.insert(button) {
+            self.just_pressed.insert(button);
+        }
+    }
+
+    fn release(&mut self, button: B) {
+        if self.pressed.remove(&button) {
+            self.just_released.insert(button);
+        }
+    }
+
+    fn release_all(&mut self) {
+        self.just_released.extend(self.pressed.drain());
+    }
+
+    fn end_tick(&mut self) {
+        self.just_pressed.clear();
+        self.just_released.clear();
+    }
+}
@@ -1,40 +1,105 @@
 #![allow(dead_code)] // ! Toremove
 
-use glium::winit::{
-    self, application::ApplicationHandler, error::EventLoopError, event_loop::EventLoop,
+mod actions;
+mod control;
+mod graphics;
+mod headless;
+mod input;
+mod scene_manager;
+mod timing;
+mod window;
+
+#[cfg(test)]
+mod test;
+
+use std::time::{Duration, Instant};
+
+use glium::{
+    backend::glutin::Display,
+    winit::{
+        self,
+        application::ApplicationHandler,
+        error::EventLoopError,
+        event::WindowEvent,
+        event_loop::{ActiveEventLoop, ControlFlow, EventLoop, EventLoopProxy},
+        window::Window,
+    },
+    SwapBuffersError,
 };
+use glutin::surface::WindowSurface;
 
-pub struct App<S: Scene> {
-    context: Context<S>,
+pub use actions::*;
+pub use control::*;
+pub use graphics::*;
+pub use headless::HeadlessApp;
+pub use input::*;
+pub use scene_manager::*;
+pub use timing::*;
+pub use window::WindowConfig;
 
-    event_loop: EventLoop<()>,
+///`E` is the type of the user events, sent through an `EventLoopProxy` given by `App::proxy`
+pub struct App<E: 'static = ()> {
+    context: Context<E>,
+
+    event_loop: EventLoop<E>,
 }
 
-struct Context<S: Scene> {
-    scene: S,
-    scene_name: String,
+struct Context<E: 'static> {
+    scenes: SceneManager<E>,
+
+    window_config: WindowConfig,
+    window: Option<Window>,
+    display: Option<Display<WindowSurface>>,
+
+    initialized: bool,
+    game_loop: GameLoop,
+    input: InputState,
+    frame_cap: Option<Duration>,
+    ///set by the scenes through `SceneControl::set_control_flow`
+    control_flow: Option<ControlFlow>,
+    last_frame: Option<Instant>,
+
+    error: Option<AppError>,
 }
 
-impl<S: Scene> From<S> for Context<S> {
-    fn from(scene: S) -> Self {
+impl<E: 'static> From<SceneManager<E>> for Context<E> {
+    fn from(scenes: SceneManager<E>) -> Self {
         Self {
-            scene,
-            scene_name: String::new(),
+            scenes,
+
+            window_config: WindowConfig::default(),
+            window: None,
+            display: None,
+
+            initialized: false,
+            game_loop: GameLoop::default(),
+            input: InputState::default(),
+            frame_cap: None,
+            control_flow: None,
+            last_frame: None,
+
+            error: None,
         }
     }
 }
 #[derive(Debug)]
 pub enum AppError {
     EventLoopError(EventLoopError),
+    DisplayCreationError(String),
+    SwapBuffersError(SwapBuffersError),
 }
 
-impl<S: Scene> App<S> {
-    pub fn build(scene: S) -> Result<Self, AppError> {
+impl<E: 'static> App<E> {
+    pub fn build<S: Scene<E> + 'static>(scene: S) -> Result<Self, AppError> {
+        Self::build_with_scenes(SceneManager::with_scene(scene))
+    }
+
+    pub fn build_with_scenes(scenes: SceneManager<E>) -> Result<Self, AppError> {
         //setting the context
-        let context = scene.into();
+        let context = scenes.into();
 
         //building the event loop
-        let event_loop = EventLoop::builder()
+        let event_loop = EventLoop::with_user_event()
             .build()
             .map_err(AppError::EventLoopError)?;
 
@@ -44,71 +109,322 @@ impl<S: Scene> App<S> {
         })
     }
 
+    ///the proxy can be sent to other threads, the events it sends
+    ///are given to `Scene::handle_user_event`
+    pub fn proxy(&self) -> EventLoopProxy<E> {
+        self.event_loop.create_proxy()
+    }
+
+    pub fn with_window(mut self, config: WindowConfig) -> Self {
+        self.context.window_config = config;
+        self
+    }
+
+    pub fn with_action_map(mut self, actions: ActionMap) -> Self {
+        self.context.input.set_action_map(actions);
+        self
+    }
+
+    ///number of `Scene::update` calls per second, 60 by default
+    pub fn with_tick_rate(mut self, tick_rate: f64) -> Self {
+        self.context.game_loop.timestep.set_tick_rate(tick_rate);
+        self
+    }
+
+    ///maximum number of `Scene::draw_frame` calls per second, `None` or a rate that isn't positive
+    ///to not limit it
+    pub fn with_frame_cap(mut self, max_fps: Option<f64>) -> Self {
+        self.context.frame_cap = max_fps.and_then(period);
+        self
+    }
+
     pub fn run(mut self) -> Result<(), AppError> {
         self.event_loop
             .run_app(&mut self.context)
-            .map_err(AppError::EventLoopError)
+            .map_err(AppError::EventLoopError)?;
+
+        match self.context.error.take() {
+            Some(e) => Err(e),
+            None => Ok(()),
+        }
+    }
+}
+
+impl<E: 'static> Context<E> {
+    fn fail(&mut self, event_loop: &ActiveEventLoop, error: AppError) {
+        self.error = Some(error);
+        event_loop.exit();
+    }
+
+    fn create_display(&mut self, event_loop: &ActiveEventLoop) -> Result<(), AppError> {
+        if self.display.is_none() {
+            let (window, display) = window::create_display(event_loop, &self.window_config)?;
+            self.scenes.display_created(&display);
+
+            self.window = Some(window);
+            self.display = Some(display);
+        }
+        Ok(())
+    }
+
+    fn init_scene(&mut self) {
+        if !self.initialized {
+            self.scenes.init();
+            self.initialized = true;
+        }
+    }
+
+    ///returns when the next frame should be drawn, if the frame rate is capped
+    fn next_frame(&self) -> Option<Instant> {
+        match (self.frame_cap, self.last_frame) {
+            (Some(cap), Some(last_frame)) => Some(last_frame + cap),
+            _ => None,
+        }
+    }
+
+    ///apply the requests made by the scenes during a callback
+    fn apply_control(&mut self, event_loop: &ActiveEventLoop, control: SceneControl) {
+        if control.is_exiting() {
+            event_loop.exit();
+        }
+        if let Some(control_flow) = control.control_flow() {
+            self.control_flow = Some(control_flow);
+        }
+        if control.redraw_requested() {
+            if let Some(window) = &self.window {
+                window.request_redraw();
+            }
+        }
+    }
+
+    ///run the pending updates and draw a frame
+    fn run_frame(&mut self, event_loop: &ActiveEventLoop) -> Result<(), AppError> {
+        let Some(display) = &self.display else {
+            return Ok(());
+        };
+
+        let now = Instant::now();
+        let frame_time = now - *self.last_frame.get_or_insert(now);
+        self.last_frame = Some(now);
+
+        let mut control = SceneControl::new(event_loop);
+        let mut frame = display.draw();
+        self.game_loop.frame(
+            &mut self.scenes,
+            &mut GliumGraphics::new(display, &mut frame),
+            &mut self.input,
+            &mut control,
+            frame_time,
+        );
+        let result = frame.finish().map_err(AppError::SwapBuffersError);
+
+        self.apply_control(event_loop, control);
+        result
     }
 }
 
-impl<S: Scene> ApplicationHandler for Context<S> {
-    fn resumed(&mut self, event_loop: &winit::event_loop::ActiveEventLoop) {
-        self.scene.resumed(event_loop);
+impl<E: 'static> ApplicationHandler<E> for Context<E> {
+    fn resumed(&mut self, event_loop: &ActiveEventLoop) {
+        if let Err(e) = self.create_display(event_loop) {
+            return self.fail(event_loop, e);
+        }
+
+        let mut control = SceneControl::new(event_loop);
+        self.scenes.resumed(&mut control);
+        self.apply_control(event_loop, control);
+        self.init_scene();
+    }
+
+    fn suspended(&mut self, event_loop: &ActiveEventLoop) {
+        let mut control = SceneControl::new(event_loop);
+        self.scenes.suspended(&mut control);
+        self.apply_control(event_loop, control);
+
+        //the surface is no longer valid on some platforms (Android), it is recreated on resume
+        self.display = None;
+        self.window = None;
     }
 
     fn window_event(
         &mut self,
-        _event_loop: &winit::event_loop::ActiveEventLoop,
+        event_loop: &ActiveEventLoop,
         _window_id: winit::window::WindowId,
-        event: winit::event::WindowEvent,
+        event: WindowEvent,
     ) {
-        self.scene.handle_window_event(&event);
+        match &event {
+            WindowEvent::Resized(size) => {
+                if let Some(display) = &self.display {
+                    display.resize((*size).into());
+                }
+            }
+            WindowEvent::CloseRequested => event_loop.exit(),
+            WindowEvent::RedrawRequested => {
+                if let Err(e) = self.run_frame(event_loop) {
+                    return self.fail(event_loop, e);
+                }
+            }
+            _ => (),
+        }
+
+        self.input.handle_window_event(&event);
+
+        let mut control = SceneControl::new(event_loop);
+        self.scenes.handle_window_event(&mut control, &event);
+        self.apply_control(event_loop, control);
     }
 
     fn device_event(
         &mut self,
-        event_loop: &winit::event_loop::ActiveEventLoop,
+        event_loop: &ActiveEventLoop,
         device_id: winit::event::DeviceId,
         event: winit::event::DeviceEvent,
     ) {
-        self.scene.handle_device_event(event_loop, device_id, event);
+        self.input.handle_device_event(&event);
+
+        let mut control = SceneControl::new(event_loop);
+        self.scenes
+            .handle_device_event(&mut control, device_id, event);
+        self.apply_control(event_loop, control);
+    }
+
+    fn user_event(&mut self, event_loop: &ActiveEventLoop, event: E) {
+        let mut control = SceneControl::new(event_loop);
+        self.scenes.handle_user_event(&mut control, &event);
+        self.apply_control(event_loop, control);
+    }
+
+    fn new_events(&mut self, event_loop: &ActiveEventLoop, cause: winit::event::StartCause) {
+        let mut control = SceneControl::new(event_loop);
+        self.scenes.handle_event(&mut control, cause);
+        self.apply_control(event_loop, control);
+    }
+
+    fn about_to_wait(&mut self, event_loop: &ActiveEventLoop) {
+        let mut control = SceneControl::new(event_loop);
+        self.scenes.about_to_wait(&mut control);
+        self.apply_control(event_loop, control);
+
+        if self.initialized && self.scenes.is_empty() {
+            return event_loop.exit();
+        }
+
+        //with `Wait` and `WaitUntil`, the frames are only drawn when a scene asks for it
+        match self.control_flow {
+            Some(ControlFlow::Poll) | None => (),
+            Some(control_flow) => return event_loop.set_control_flow(control_flow),
+        }
+
+        let Some(window) = &self.window else {
+            return;
+        };
+
+        match self.next_frame() {
+            Some(next_frame) if Instant::now() < next_frame => {
+                event_loop.set_control_flow(ControlFlow::WaitUntil(next_frame));
+            }
+            _ => {
+                window.request_redraw();
+                event_loop.set_control_flow(ControlFlow::Poll);
+            }
+        }
+    }
+
+    fn exiting(&mut self, event_loop: &ActiveEventLoop) {
+        let mut control = SceneControl::new(event_loop);
+        self.scenes.on_exiting(&mut control);
     }
 
-    fn user_event(&mut self, event_loop: &winit::event_loop::ActiveEventLoop, _event: ()) {
-        self.scene.handle_user_event(event_loop);
+    fn memory_warning(&mut self, event_loop: &ActiveEventLoop) {
+        let mut control = SceneControl::new(event_loop);
+        self.scenes.memory_warning(&mut control);
+        self.apply_control(event_loop, control);
     }
 }
 
 #[allow(unused)]
-pub trait Scene {
+pub trait Scene<E: 'static = ()> {
+    ///called once, before the first update
     fn init(&mut self);
 
-    fn draw_frame(&mut self);
+    ///`alpha` is how far we are between the last update and the next one (in `[0., 1.[`),
+    ///it can be used to interpolate the rendered state.
+    ///The frame is swapped by the `App` once this returns
+    fn draw_frame(&mut self, graphics: &mut dyn Graphics, alpha: f32);
 
-    fn update(&mut self);
+    ///called at a fixed rate, `dt` is the duration of a tick in seconds
+    fn update(&mut self, dt: f32, input: &InputState, control: &mut SceneControl);
 
-    fn resumed(&mut self, event_loop: &winit::event_loop::ActiveEventLoop) {}
+    ///called each time the display is (re)created, GPU resources should be created here
+    fn display_created(&mut self, display: &Display<WindowSurface>) {}
 
-    fn handle_window_event(&mut self, event: &glium::winit::event::WindowEvent) {
+    fn resumed(&mut self, control: &mut SceneControl) {}
+
+    ///the display is dropped after this call, and recreated on the next `resumed`
+    fn suspended(&mut self, control: &mut SceneControl) {}
+
+    fn handle_window_event(
+        &mut self,
+        control: &mut SceneControl,
+        event: &glium::winit::event::WindowEvent,
+    ) {
         /*Nothing*/
     }
 
     fn handle_device_event(
         &mut self,
-        _event_loop: &winit::event_loop::ActiveEventLoop,
+        _control: &mut SceneControl,
         _device_id: winit::event::DeviceId,
         _event: winit::event::DeviceEvent,
     ) {
     }
 
-    fn handle_user_event(&mut self, _event_loop: &winit::event_loop::ActiveEventLoop) {}
+    ///`event` has been sent through the `App::proxy`
+    fn handle_user_event(&mut self, _control: &mut SceneControl, _event: &E) {}
 
-    fn handle_event(
-        &mut self,
-        _event_loop: &winit::event_loop::ActiveEventLoop,
-        _cause: winit::event::StartCause,
-    ) {
+    ///called when the event loop wakes up, before the events are delivered
+    fn handle_event(&mut self, _control: &mut SceneControl, _cause: winit::event::StartCause) {}
+
+    ///called once the events are delivered, before the event loop waits
+    fn about_to_wait(&mut self, control: &mut SceneControl) {}
+
+    ///the `App` is already exiting, the requests made through `control` are ignored
+    fn on_exiting(&mut self, _control: &mut SceneControl) {}
+
+    ///the system is low on memory (Android, iOS), caches should be freed
+    fn memory_warning(&mut self, control: &mut SceneControl) {}
+
+    ///called after each frame with the updated frame timings
+    fn handle_frame_stats(&mut self, _stats: &FrameStats) {}
+
+    ///the name used by the `SceneManager` to find the scene
+    fn scene_name(&self) -> &str {
+        std::any::type_name::<Self>()
+    }
+
+    ///overlays are drawn above the scene underneath, which is neither paused nor hidden
+    fn is_overlay(&self) -> bool {
+        false
+    }
+
+    ///if true, the scenes underneath do not receive the inputs
+    fn blocks_input(&self) -> bool {
+        false
     }
 
-    fn on_exiting(&mut self, _event_loop: &winit::event_loop::ActiveEventLoop) {}
+    ///polled by the `SceneManager` after each update and event
+    fn next_transition(&mut self) -> Option<SceneTransition<E>> {
+        None
+    }
+
+    ///called when the scene is pushed on the stack, after `init`
+    fn on_enter(&mut self) {}
+
+    ///called when the scene is removed from the stack
+    fn on_exit(&mut self) {}
+
+    ///called when a scene that is not an overlay is pushed above this one
+    fn on_pause(&mut self) {}
+
+    ///called when the scene is on the top of the stack again
+    fn on_resume(&mut self) {}
 }
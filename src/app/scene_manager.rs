@@ -0,0 +1,374 @@
+use std::ops::Range;
+
+use glium::{
+    backend::glutin::Display,
+    winit::event::{DeviceEvent, DeviceId, StartCause, WindowEvent},
+};
+use glutin::surface::WindowSurface;
+
+use super::{FrameStats, Graphics, InputState, Scene, SceneControl};
+
+///returned by `Scene::next_transition` to change the scene stack
+pub enum SceneTransition<E: 'static = ()> {
+    ///pause the current scene (unless the new one is an overlay) and start the new one
+    Push(Box<dyn Scene<E>>),
+    Pop,
+    Replace(Box<dyn Scene<E>>),
+    ///pop every scene above the first scene with this name
+    PopTo(String),
+    ///pop every scene, the `App` exits once the stack is empty
+    Clear,
+}
+
+impl<E: 'static> std::fmt::Debug for SceneTransition<E> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SceneTransition::Push(scene) => write!(f, "Push({})", scene.scene_name()),
+            SceneTransition::Pop => write!(f, "Pop"),
+            SceneTransition::Replace(scene) => write!(f, "Replace({})", scene.scene_name()),
+            SceneTransition::PopTo(name) => write!(f, "PopTo({})", name),
+            SceneTransition::Clear => write!(f, "Clear"),
+        }
+    }
+}
+
+struct SceneEntry<E: 'static> {
+    scene: Box<dyn Scene<E>>,
+    started: bool,
+}
+
+///a stack of scenes, only the top scene and the overlays above it are updated and drawn.
+///Input events go from the top of the stack to the bottom, until a scene that is not an
+///overlay or a scene that blocks the input is reached.
+///
+///User events are given to every scene of the stack, paused or not.
+pub struct SceneManager<E: 'static = ()> {
+    stack: Vec<SceneEntry<E>>,
+
+    display: Option<Display<WindowSurface>>,
+    initialized: bool,
+}
+
+impl<E: 'static> Default for SceneManager<E> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<E: 'static> SceneManager<E> {
+    pub fn new() -> Self {
+        Self {
+            stack: vec![],
+            display: None,
+            initialized: false,
+        }
+    }
+
+    pub fn with_scene<S: Scene<E> + 'static>(scene: S) -> Self {
+        let mut manager = Self::new();
+        manager.push(Box::new(scene));
+        manager
+    }
+
+    pub fn len(&self) -> usize {
+        self.stack.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.stack.is_empty()
+    }
+
+    pub fn top(&self) -> Option<&dyn Scene<E>> {
+        self.stack.last().map(|entry| entry.scene.as_ref())
+    }
+
+    ///the names of the scenes, from the bottom of the stack to the top
+    pub fn scene_names(&self) -> impl Iterator<Item = &str> {
+        self.stack.iter().map(|entry| entry.scene.scene_name())
+    }
+
+    pub fn get(&self, name: &str) -> Option<&dyn Scene<E>> {
+        self.stack
+            .iter()
+            .rev()
+            .find(|entry| entry.scene.scene_name() == name)
+            .map(|entry| entry.scene.as_ref())
+    }
+
+    pub fn get_mut(&mut self, name: &str) -> Option<&mut (dyn Scene<E> + 'static)> {
+        self.stack
+            .iter_mut()
+            .rev()
+            .find(|entry| entry.scene.scene_name() == name)
+            .map(|entry| entry.scene.as_mut())
+    }
+
+    pub fn push(&mut self, scene: Box<dyn Scene<E>>) {
+        if !scene.is_overlay() {
+            let active = self.active_range();
+            for entry in &mut self.stack[active] {
+                entry.scene.on_pause();
+            }
+        }
+
+        let mut entry = SceneEntry {
+            scene,
+            started: false,
+        };
+        self.start(&mut entry);
+        self.stack.push(entry);
+    }
+
+    pub fn pop(&mut self) -> Option<Box<dyn Scene<E>>> {
+        let mut entry = self.stack.pop()?;
+        if entry.started {
+            entry.scene.on_exit();
+        }
+
+        if !entry.scene.is_overlay() {
+            let active = self.active_range();
+            for entry in &mut self.stack[active] {
+                entry.scene.on_resume();
+            }
+        }
+        Some(entry.scene)
+    }
+
+    ///the scenes below are paused or resumed if only one of the old and the new scenes is an
+    ///overlay
+    pub fn replace(&mut self, scene: Box<dyn Scene<E>>) -> Option<Box<dyn Scene<E>>> {
+        let mut old = self.stack.pop();
+        if let Some(old) = &mut old {
+            if old.started {
+                old.scene.on_exit();
+            }
+        }
+
+        let old_overlay = match &old {
+            Some(old) => old.scene.is_overlay(),
+            None => true,
+        };
+        let active = self.active_range();
+        match (old_overlay, scene.is_overlay()) {
+            (true, false) => {
+                for entry in &mut self.stack[active] {
+                    entry.scene.on_pause();
+                }
+            }
+            (false, true) => {
+                for entry in &mut self.stack[active] {
+                    entry.scene.on_resume();
+                }
+            }
+            _ => {}
+        }
+
+        let mut entry = SceneEntry {
+            scene,
+            started: false,
+        };
+        self.start(&mut entry);
+        self.stack.push(entry);
+
+        old.map(|entry| entry.scene)
+    }
+
+    ///pop every scene above the first scene named `name`, nothing is done if there is none
+    pub fn pop_to(&mut self, name: &str) {
+        let Some(index) = self
+            .stack
+            .iter()
+            .rposition(|entry| entry.scene.scene_name() == name)
+        else {
+            return;
+        };
+        while self.stack.len() > index + 1 {
+            self.pop();
+        }
+    }
+
+    pub fn clear(&mut self) {
+        while self.pop().is_some() {}
+    }
+
+    pub fn apply(&mut self, transition: SceneTransition<E>) {
+        match transition {
+            SceneTransition::Push(scene) => self.push(scene),
+            SceneTransition::Pop => {
+                self.pop();
+            }
+            SceneTransition::Replace(scene) => {
+                self.replace(scene);
+            }
+            SceneTransition::PopTo(name) => self.pop_to(&name),
+            SceneTransition::Clear => self.clear(),
+        }
+    }
+
+    fn apply_transitions(&mut self) {
+        let transitions: Vec<SceneTransition<E>> = self
+            .stack
+            .iter_mut()
+            .rev()
+            .filter_map(|entry| entry.scene.next_transition())
+            .collect();
+
+        for transition in transitions {
+            self.apply(transition);
+        }
+    }
+
+    ///init the scene and call `on_enter`, only once the manager itself has been initialized
+    fn start(&self, entry: &mut SceneEntry<E>) {
+        if !self.initialized || entry.started {
+            return;
+        }
+        entry.scene.init();
+        if let Some(display) = &self.display {
+            entry.scene.display_created(display);
+        }
+        entry.scene.on_enter();
+        entry.started = true;
+    }
+
+    ///the top scene that is not an overlay, and the overlays above it
+    fn active_range(&self) -> Range<usize> {
+        let start = self
+            .stack
+            .iter()
+            .rposition(|entry| !entry.scene.is_overlay())
+            .unwrap_or(0);
+        start..self.stack.len()
+    }
+
+    ///the active scenes receiving the inputs, from the top to the bottom
+    fn input_receivers(&mut self) -> impl Iterator<Item = &mut Box<dyn Scene<E>>> {
+        let active = self.active_range();
+        let mut blocked = false;
+        self.stack[active]
+            .iter_mut()
+            .rev()
+            .map(|entry| &mut entry.scene)
+            .take_while(move |scene| {
+                let receives = !blocked;
+                blocked |= scene.blocks_input();
+                receives
+            })
+    }
+}
+
+impl<E: 'static> Scene<E> for SceneManager<E> {
+    fn init(&mut self) {
+        self.initialized = true;
+
+        let mut stack = std::mem::take(&mut self.stack);
+        for entry in &mut stack {
+            self.start(entry);
+        }
+        self.stack = stack;
+    }
+
+    fn draw_frame(&mut self, graphics: &mut dyn Graphics, alpha: f32) {
+        let active = self.active_range();
+        for entry in &mut self.stack[active] {
+            entry.scene.draw_frame(graphics, alpha);
+        }
+    }
+
+    fn update(&mut self, dt: f32, input: &InputState, control: &mut SceneControl) {
+        let active = self.active_range();
+        let no_input = InputState::default();
+        let mut blocked = false;
+
+        //the scenes are updated from the top to the bottom so a blocking overlay hides the inputs
+        for entry in self.stack[active].iter_mut().rev() {
+            let scene_input = if blocked { &no_input } else { input };
+            entry.scene.update(dt, scene_input, control);
+            blocked |= entry.scene.blocks_input();
+        }
+
+        self.apply_transitions();
+    }
+
+    fn display_created(&mut self, display: &Display<WindowSurface>) {
+        self.display = Some(display.clone());
+        for entry in self.stack.iter_mut().filter(|entry| entry.started) {
+            entry.scene.display_created(display);
+        }
+    }
+
+    fn resumed(&mut self, control: &mut SceneControl) {
+        for entry in &mut self.stack {
+            entry.scene.resumed(control);
+        }
+    }
+
+    fn suspended(&mut self, control: &mut SceneControl) {
+        for entry in &mut self.stack {
+            entry.scene.suspended(control);
+        }
+        //the GL context must not outlive the suspension
+        self.display = None;
+    }
+
+    fn handle_window_event(&mut self, control: &mut SceneControl, event: &WindowEvent) {
+        for scene in self.input_receivers() {
+            scene.handle_window_event(control, event);
+        }
+        self.apply_transitions();
+    }
+
+    fn handle_device_event(
+        &mut self,
+        control: &mut SceneControl,
+        device_id: DeviceId,
+        event: DeviceEvent,
+    ) {
+        for scene in self.input_receivers() {
+            scene.handle_device_event(control, device_id, event.clone());
+        }
+        self.apply_transitions();
+    }
+
+    fn handle_user_event(&mut self, control: &mut SceneControl, event: &E) {
+        for entry in &mut self.stack {
+            entry.scene.handle_user_event(control, event);
+        }
+        self.apply_transitions();
+    }
+
+    fn handle_event(&mut self, control: &mut SceneControl, cause: StartCause) {
+        for entry in &mut self.stack {
+            entry.scene.handle_event(control, cause);
+        }
+    }
+
+    fn about_to_wait(&mut self, control: &mut SceneControl) {
+        for entry in &mut self.stack {
+            entry.scene.about_to_wait(control);
+        }
+    }
+
+    fn on_exiting(&mut self, control: &mut SceneControl) {
+        for entry in &mut self.stack {
+            entry.scene.on_exiting(control);
+        }
+    }
+
+    fn memory_warning(&mut self, control: &mut SceneControl) {
+        for entry in &mut self.stack {
+            entry.scene.memory_warning(control);
+        }
+    }
+
+    fn handle_frame_stats(&mut self, stats: &FrameStats) {
+        let active = self.active_range();
+        for entry in &mut self.stack[active] {
+            entry.scene.handle_frame_stats(stats);
+        }
+    }
+
+    fn scene_name(&self) -> &str {
+        "SceneManager"
+    }
+}
@@ -0,0 +1,594 @@
+mod test_timing {
+    use std::time::Duration;
+
+    use crate::app::{FixedTimestep, FrameStats};
+
+    #[test]
+    fn test_fixed_timestep_accumulates() {
+        let mut timestep = FixedTimestep::new(10.);
+
+        assert_eq!(timestep.advance(Duration::from_millis(50)), 0);
+        assert!((timestep.alpha() - 0.5).abs() < 1e-5);
+
+        assert_eq!(timestep.advance(Duration::from_millis(160)), 2);
+        assert!((timestep.alpha() - 0.1).abs() < 1e-5);
+    }
+
+    #[test]
+    fn test_fixed_timestep_clamps_long_frames() {
+        let mut timestep = FixedTimestep::new(100.);
+        timestep.set_max_frame_time(Duration::from_millis(50));
+
+        assert_eq!(timestep.advance(Duration::from_secs(10)), 5);
+    }
+
+    #[test]
+    fn test_fixed_timestep_invalid_rates() {
+        let default = FixedTimestep::default().tick_duration();
+        for tick_rate in [0., -10., f64::NAN, f64::NEG_INFINITY] {
+            assert_eq!(FixedTimestep::new(tick_rate).tick_duration(), default);
+        }
+
+        let mut timestep = FixedTimestep::new(10.);
+        timestep.set_tick_rate(0.);
+        timestep.set_tick_rate(f64::NAN);
+        assert_eq!(timestep.tick_duration(), Duration::from_millis(100));
+
+        //the extreme rates are clamped instead of giving an empty or overflowing duration
+        timestep.set_tick_rate(f64::INFINITY);
+        assert!(timestep.tick_duration() > Duration::ZERO);
+        timestep.set_tick_rate(1e-300);
+        assert!((timestep.tick_rate() - FixedTimestep::MIN_TICK_RATE).abs() < 1e-9);
+        assert_eq!(timestep.advance(Duration::from_secs(1)), 0);
+    }
+
+    #[test]
+    fn test_period() {
+        //the frame cap of `App` is disabled for these rates
+        for rate in [0., -1., f64::NAN] {
+            assert_eq!(crate::app::period(rate), None);
+        }
+        assert_eq!(crate::app::period(50.), Some(Duration::from_millis(20)));
+    }
+
+    #[test]
+    fn test_frame_stats() {
+        let mut stats = FrameStats::new(2);
+        stats.record_frame(Duration::from_millis(30), 1);
+        stats.record_frame(Duration::from_millis(10), 1);
+        stats.record_frame(Duration::from_millis(10), 0);
+
+        assert_eq!(stats.frame_count(), 3);
+        assert_eq!(stats.tick_count(), 2);
+        assert_eq!(stats.frame_time(), Duration::from_millis(10));
+        assert_eq!(stats.max_frame_time(), Duration::from_millis(10));
+        assert!((stats.fps() - 100.).abs() < 1e-6);
+    }
+}
+
+mod test_headless {
+    use std::{cell::RefCell, rc::Rc, time::Duration};
+
+    use glium::winit::{
+        dpi::PhysicalSize,
+        event::{StartCause, WindowEvent},
+    };
+
+    use crate::app::{
+        Graphics, GraphicsCommand, HeadlessApp, InputState, Scene, SceneControl, SceneManager,
+    };
+
+    #[derive(Default)]
+    struct CountingScene {
+        init_count: u32,
+        update_count: u32,
+        draw_count: u32,
+        resized_to: Option<(u32, u32)>,
+    }
+
+    impl Scene for CountingScene {
+        fn init(&mut self) {
+            self.init_count += 1;
+        }
+
+        fn draw_frame(&mut self, graphics: &mut dyn Graphics, _alpha: f32) {
+            self.draw_count += 1;
+            graphics.clear_color_and_depth([0., 0., 0., 1.], 1.);
+            graphics
+                .draw("counter", &mut |_frame| Ok(()))
+                .expect("recording draws can't fail");
+        }
+
+        fn update(&mut self, _dt: f32, _input: &InputState, _control: &mut SceneControl) {
+            self.update_count += 1;
+        }
+
+        fn handle_window_event(&mut self, _control: &mut SceneControl, event: &WindowEvent) {
+            if let WindowEvent::Resized(size) = event {
+                self.resized_to = Some((size.width, size.height));
+            }
+        }
+    }
+
+    #[test]
+    fn test_headless_runs_frames() {
+        let mut app = HeadlessApp::new(CountingScene::default()).with_tick_rate(100.);
+        app.run_frames(10);
+        app.run_frames(5);
+
+        let scene = app.scene();
+        assert_eq!(scene.init_count, 1);
+        assert_eq!(scene.update_count, 15);
+        assert_eq!(scene.draw_count, 15);
+        assert_eq!(app.stats().frame_count(), 15);
+        assert_eq!(app.elapsed(), Duration::from_millis(150));
+        assert_eq!(app.graphics().draws().count(), 15);
+    }
+
+    #[test]
+    fn test_headless_simulated_clock() {
+        let mut app = HeadlessApp::new(CountingScene::default())
+            .with_tick_rate(100.)
+            .with_frame_time(Duration::from_millis(25));
+        app.run_frames(4);
+
+        assert_eq!(app.scene().update_count, 10);
+        assert_eq!(app.scene().draw_count, 4);
+    }
+
+    #[test]
+    fn test_headless_events() {
+        let mut app = HeadlessApp::new(CountingScene::default());
+        app.push_window_event_at(2, WindowEvent::Resized(PhysicalSize::new(320, 240)));
+        app.push_window_event_at(3, WindowEvent::CloseRequested);
+
+        app.run_frames(1);
+        assert_eq!(app.scene().resized_to, None);
+
+        app.run_frames(10);
+        assert_eq!(app.scene().resized_to, Some((320, 240)));
+        assert_eq!(app.graphics().dimensions(), (320, 240));
+        assert!(app.has_exited());
+        assert_eq!(app.scene().draw_count, 3);
+        assert_eq!(
+            app.graphics().commands().last(),
+            Some(&GraphicsCommand::EndFrame)
+        );
+    }
+
+    ///sums the user events, exits on the first `0`
+    #[derive(Default)]
+    struct SumScene {
+        sum: u32,
+        exiting: bool,
+    }
+
+    impl Scene<u32> for SumScene {
+        fn init(&mut self) {}
+
+        fn draw_frame(&mut self, _graphics: &mut dyn Graphics, _alpha: f32) {}
+
+        fn update(&mut self, _dt: f32, _input: &InputState, _control: &mut SceneControl) {}
+
+        fn handle_user_event(&mut self, control: &mut SceneControl, event: &u32) {
+            match event {
+                0 => control.exit(),
+                n => self.sum += n,
+            }
+        }
+
+        fn on_exiting(&mut self, _control: &mut SceneControl) {
+            self.exiting = true;
+        }
+    }
+
+    #[test]
+    fn test_headless_user_events() {
+        let mut app = HeadlessApp::new(SumScene::default());
+        app.push_user_event(3);
+        app.push_user_event(4);
+        app.run_frames(2);
+        assert_eq!(app.scene().sum, 7);
+        assert!(!app.has_exited());
+
+        app.push_user_event(0);
+        app.push_user_event(5);
+        app.run_frames(2);
+        assert!(app.has_exited());
+        assert!(app.scene().exiting);
+        assert_eq!(app.stats().frame_count(), 2);
+    }
+
+    ///logs the user events it receives
+    struct EventLogScene {
+        name: &'static str,
+        overlay: bool,
+        log: Rc<RefCell<Vec<String>>>,
+    }
+
+    impl Scene<u32> for EventLogScene {
+        fn init(&mut self) {}
+
+        fn draw_frame(&mut self, _graphics: &mut dyn Graphics, _alpha: f32) {}
+
+        fn update(&mut self, _dt: f32, _input: &InputState, _control: &mut SceneControl) {}
+
+        fn handle_user_event(&mut self, _control: &mut SceneControl, event: &u32) {
+            self.log
+                .borrow_mut()
+                .push(format!("{} {}", self.name, event));
+        }
+
+        fn scene_name(&self) -> &str {
+            self.name
+        }
+
+        fn is_overlay(&self) -> bool {
+            self.overlay
+        }
+    }
+
+    #[test]
+    fn test_user_events_reach_overlays() {
+        let log = Rc::new(RefCell::new(vec![]));
+        let scene = |name, overlay| EventLogScene {
+            name,
+            overlay,
+            log: log.clone(),
+        };
+
+        let mut scenes = SceneManager::with_scene(scene("level", false));
+        scenes.push(Box::new(scene("hud", true)));
+        scenes.push(Box::new(scene("chat", true)));
+
+        let mut app = HeadlessApp::new(scenes);
+        app.push_user_event(7);
+        app.run_frames(1);
+        assert_eq!(*log.borrow(), ["level 7", "hud 7", "chat 7"]);
+    }
+
+    ///logs the lifecycle callbacks
+    #[derive(Default)]
+    struct LifecycleScene {
+        log: Vec<String>,
+    }
+
+    impl Scene for LifecycleScene {
+        fn init(&mut self) {
+            self.log.push("init".to_string());
+        }
+
+        fn draw_frame(&mut self, _graphics: &mut dyn Graphics, _alpha: f32) {
+            self.log.push("draw".to_string());
+        }
+
+        fn update(&mut self, _dt: f32, _input: &InputState, _control: &mut SceneControl) {}
+
+        fn resumed(&mut self, _control: &mut SceneControl) {
+            self.log.push("resumed".to_string());
+        }
+
+        fn handle_event(&mut self, _control: &mut SceneControl, cause: StartCause) {
+            self.log.push(format!("{:?}", cause));
+        }
+    }
+
+    #[test]
+    fn test_headless_lifecycle_order() {
+        let mut app = HeadlessApp::new(LifecycleScene::default());
+        app.run_frames(2);
+        assert_eq!(
+            app.scene().log,
+            ["Init", "resumed", "init", "Poll", "draw", "Poll", "draw"]
+        );
+    }
+}
+
+mod test_input {
+    use glium::winit::{event::MouseButton, keyboard::KeyCode};
+
+    use crate::app::{
+        ActionMap, AxisBinding, GamepadAxis, GamepadButton, InputBinding, InputState,
+    };
+
+    #[test]
+    fn test_just_pressed_lasts_one_tick() {
+        let mut input = InputState::new();
+        input.press_key(KeyCode::KeyW);
+
+        assert!(input.is_key_pressed(KeyCode::KeyW));
+        assert!(input.is_key_just_pressed(KeyCode::KeyW));
+
+        input.end_tick();
+        assert!(input.is_key_pressed(KeyCode::KeyW));
+        assert!(!input.is_key_just_pressed(KeyCode::KeyW));
+
+        input.release_key(KeyCode::KeyW);
+        assert!(!input.is_key_pressed(KeyCode::KeyW));
+        assert!(input.is_key_just_released(KeyCode::KeyW));
+    }
+
+    #[test]
+    fn test_cursor_delta() {
+        let mut input = InputState::new();
+        input.move_cursor(10., 10.);
+        input.move_cursor(15., 8.);
+        assert_eq!(input.cursor_delta(), (5., -2.));
+
+        input.end_tick();
+        assert_eq!(input.cursor_delta(), (0., 0.));
+        assert_eq!(input.cursor_position(), Some((15., 8.)));
+    }
+
+    #[test]
+    fn test_release_all() {
+        let mut input = InputState::new();
+        input.press_mouse_button(MouseButton::Left);
+        input.release_all();
+
+        assert!(!input.is_mouse_button_pressed(MouseButton::Left));
+        assert!(input.is_mouse_button_just_released(MouseButton::Left));
+    }
+
+    #[test]
+    fn test_actions() {
+        let mut actions = ActionMap::new();
+        actions.bind_action("jump", InputBinding::Key(KeyCode::Space));
+        actions.bind_action("jump", InputBinding::Gamepad(GamepadButton::South));
+        actions.bind_axis(
+            "move_x",
+            AxisBinding::Buttons {
+                negative: InputBinding::Key(KeyCode::KeyA),
+                positive: InputBinding::Key(KeyCode::KeyD),
+            },
+        );
+        actions.bind_axis("move_x", AxisBinding::Gamepad(GamepadAxis::LeftStickX));
+
+        let mut input = InputState::new().with_action_map(actions);
+        input.press_gamepad_button(GamepadButton::South);
+        input.set_gamepad_axis(GamepadAxis::LeftStickX, 0.5);
+        assert!(input.is_action_just_pressed("jump"));
+        assert_eq!(input.axis("move_x"), 0.5);
+
+        input.press_key(KeyCode::KeyA);
+        assert_eq!(input.axis("move_x"), -1.);
+        assert_eq!(input.axis("unbound"), 0.);
+    }
+
+    #[test]
+    fn test_action_map_parsing() {
+        let data = "# controls\n\
+                    action jump key:Space gamepad:South\n\
+                    action fire mouse:Left # shoot\n\
+                    \n\
+                    axis move_x keys:KeyA,KeyD gamepad:LeftStickX\n\
+                    axis look_x mouse:x\n";
+        let actions: ActionMap = data.parse().expect("valid action map");
+
+        assert_eq!(
+            actions.action_bindings("jump"),
+            &[
+                InputBinding::Key(KeyCode::Space),
+                InputBinding::Gamepad(GamepadButton::South)
+            ]
+        );
+        assert_eq!(
+            actions.action_bindings("fire"),
+            &[InputBinding::Mouse(MouseButton::Left)]
+        );
+        assert_eq!(
+            actions.axis_bindings("move_x"),
+            &[
+                AxisBinding::Buttons {
+                    negative: InputBinding::Key(KeyCode::KeyA),
+                    positive: InputBinding::Key(KeyCode::KeyD),
+                },
+                AxisBinding::Gamepad(GamepadAxis::LeftStickX)
+            ]
+        );
+        assert_eq!(actions.axis_bindings("look_x"), &[AxisBinding::MouseX]);
+    }
+
+    #[test]
+    fn test_action_map_parsing_errors() {
+        assert!("action jump key:NotAKey".parse::<ActionMap>().is_err());
+        assert!("bind jump key:Space".parse::<ActionMap>().is_err());
+        assert!("action".parse::<ActionMap>().is_err());
+    }
+}
+
+mod test_scene_manager {
+    use std::{cell::RefCell, rc::Rc};
+
+    use glium::winit::event::WindowEvent;
+
+    use crate::app::{Graphics, InputState, Scene, SceneControl, SceneManager, SceneTransition};
+
+    type Log = Rc<RefCell<Vec<String>>>;
+
+    struct LoggingScene {
+        name: &'static str,
+        overlay: bool,
+        blocks_input: bool,
+        log: Log,
+        transition: Option<SceneTransition>,
+    }
+
+    impl LoggingScene {
+        fn new(name: &'static str, log: &Log) -> Self {
+            Self {
+                name,
+                overlay: false,
+                blocks_input: false,
+                log: log.clone(),
+                transition: None,
+            }
+        }
+
+        fn overlay(mut self, blocks_input: bool) -> Self {
+            self.overlay = true;
+            self.blocks_input = blocks_input;
+            self
+        }
+
+        fn push(&self, message: &str) {
+            self.log
+                .borrow_mut()
+                .push(format!("{} {}", self.name, message));
+        }
+    }
+
+    impl Scene for LoggingScene {
+        fn init(&mut self) {
+            self.push("init");
+        }
+
+        fn draw_frame(&mut self, _graphics: &mut dyn Graphics, _alpha: f32) {
+            self.push("draw");
+        }
+
+        fn update(&mut self, _dt: f32, _input: &InputState, _control: &mut SceneControl) {
+            self.push("update");
+        }
+
+        fn handle_window_event(&mut self, _control: &mut SceneControl, _event: &WindowEvent) {
+            self.push("event");
+        }
+
+        fn scene_name(&self) -> &str {
+            self.name
+        }
+
+        fn is_overlay(&self) -> bool {
+            self.overlay
+        }
+
+        fn blocks_input(&self) -> bool {
+            self.blocks_input
+        }
+
+        fn next_transition(&mut self) -> Option<SceneTransition> {
+            self.transition.take()
+        }
+
+        fn on_enter(&mut self) {
+            self.push("enter");
+        }
+
+        fn on_exit(&mut self) {
+            self.push("exit");
+        }
+
+        fn on_pause(&mut self) {
+            self.push("pause");
+        }
+
+        fn on_resume(&mut self) {
+            self.push("resume");
+        }
+    }
+
+    fn take(log: &Log) -> Vec<String> {
+        std::mem::take(&mut *log.borrow_mut())
+    }
+
+    #[test]
+    fn test_push_and_pop() {
+        let log = Log::default();
+        let mut scenes = SceneManager::with_scene(LoggingScene::new("menu", &log));
+        assert!(take(&log).is_empty());
+
+        scenes.init();
+        assert_eq!(take(&log), vec!["menu init", "menu enter"]);
+
+        scenes.push(Box::new(LoggingScene::new("level", &log)));
+        assert_eq!(take(&log), vec!["menu pause", "level init", "level enter"]);
+
+        scenes.update(0.1, &InputState::default(), &mut SceneControl::headless());
+        assert_eq!(take(&log), vec!["level update"]);
+
+        scenes.pop();
+        assert_eq!(take(&log), vec!["level exit", "menu resume"]);
+        assert_eq!(scenes.scene_names().collect::<Vec<_>>(), vec!["menu"]);
+    }
+
+    #[test]
+    fn test_overlays() {
+        let log = Log::default();
+        let mut scenes = SceneManager::with_scene(LoggingScene::new("level", &log));
+        scenes.init();
+        scenes.push(Box::new(LoggingScene::new("hud", &log).overlay(false)));
+        scenes.push(Box::new(LoggingScene::new("pause", &log).overlay(true)));
+        take(&log);
+
+        scenes.handle_window_event(&mut SceneControl::headless(), &WindowEvent::Focused(true));
+        assert_eq!(take(&log), vec!["pause event"]);
+
+        scenes.pop();
+        take(&log);
+        scenes.handle_window_event(&mut SceneControl::headless(), &WindowEvent::Focused(true));
+        assert_eq!(take(&log), vec!["hud event", "level event"]);
+
+        scenes.draw_frame(&mut crate::app::NullGraphics::default(), 0.);
+        assert_eq!(take(&log), vec!["level draw", "hud draw"]);
+    }
+
+    #[test]
+    fn test_transitions() {
+        let log = Log::default();
+        let mut menu = LoggingScene::new("menu", &log);
+        menu.transition = Some(SceneTransition::Replace(Box::new(LoggingScene::new(
+            "level", &log,
+        ))));
+
+        let mut scenes = SceneManager::with_scene(menu);
+        scenes.init();
+        take(&log);
+
+        scenes.update(0.1, &InputState::default(), &mut SceneControl::headless());
+        assert_eq!(
+            take(&log),
+            vec!["menu update", "menu exit", "level init", "level enter"]
+        );
+        assert_eq!(scenes.top().map(|scene| scene.scene_name()), Some("level"));
+
+        scenes.apply(SceneTransition::Clear);
+        assert!(scenes.is_empty());
+    }
+
+    #[test]
+    fn test_replace_overlays() {
+        let log = Log::default();
+        let mut scenes = SceneManager::with_scene(LoggingScene::new("level", &log));
+        scenes.init();
+        scenes.push(Box::new(LoggingScene::new("hud", &log).overlay(false)));
+        take(&log);
+
+        scenes.replace(Box::new(LoggingScene::new("menu", &log)));
+        assert_eq!(
+            take(&log),
+            vec!["hud exit", "level pause", "menu init", "menu enter"]
+        );
+
+        scenes.replace(Box::new(LoggingScene::new("hud", &log).overlay(false)));
+        assert_eq!(
+            take(&log),
+            vec!["menu exit", "level resume", "hud init", "hud enter"]
+        );
+
+        scenes.replace(Box::new(LoggingScene::new("pause", &log).overlay(true)));
+        assert_eq!(take(&log), vec!["hud exit", "pause init", "pause enter"]);
+
+        scenes.draw_frame(&mut crate::app::NullGraphics::default(), 0.);
+        assert_eq!(take(&log), vec!["level draw", "pause draw"]);
+    }
+}
+
+mod test_window {
+    use crate::app::window::pick_config;
+
+    #[test]
+    fn test_no_config() {
+        assert!(pick_config(std::iter::empty()).is_none());
+    }
+}
@@ -0,0 +1,215 @@
+use std::{collections::VecDeque, time::Duration};
+
+use super::{Graphics, InputState, Scene, SceneControl};
+
+/******************\
+*                 *
+*--FixedTimestep--*
+*                 *
+\******************/
+
+///accumulates the elapsed time and splits it in fixed update ticks
+#[derive(Debug, Clone)]
+pub struct FixedTimestep {
+    tick_duration: Duration,
+    accumulator: Duration,
+
+    ///frame times are clamped to this value, to avoid spiraling when a frame takes too long
+    max_frame_time: Duration,
+}
+
+impl FixedTimestep {
+    pub const DEFAULT_TICK_RATE: f64 = 60.;
+    pub const MIN_TICK_RATE: f64 = 1e-6;
+    pub const MAX_TICK_RATE: f64 = 1e6;
+
+    ///`tick_rate` is the number of updates per second, the default rate is used if it isn't
+    ///positive
+    pub fn new(tick_rate: f64) -> Self {
+        Self {
+            tick_duration: period(tick_rate)
+                .or(period(Self::DEFAULT_TICK_RATE))
+                .expect("the default tick rate is valid"),
+            accumulator: Duration::ZERO,
+            max_frame_time: Duration::from_millis(250),
+        }
+    }
+
+    ///a rate that isn't positive is ignored
+    pub fn set_tick_rate(&mut self, tick_rate: f64) {
+        if let Some(tick_duration) = period(tick_rate) {
+            self.tick_duration = tick_duration;
+        }
+    }
+
+    pub fn set_max_frame_time(&mut self, max_frame_time: Duration) {
+        self.max_frame_time = max_frame_time;
+    }
+
+    pub fn tick_rate(&self) -> f64 {
+        1. / self.tick_duration.as_secs_f64()
+    }
+
+    pub fn tick_duration(&self) -> Duration {
+        self.tick_duration
+    }
+
+    ///the `dt` given to `Scene::update`
+    pub fn dt(&self) -> f32 {
+        self.tick_duration.as_secs_f32()
+    }
+
+    ///add the frame time to the accumulator and returns the number of ticks to run
+    pub fn advance(&mut self, frame_time: Duration) -> u32 {
+        self.accumulator += frame_time.min(self.max_frame_time);
+
+        let mut ticks = 0;
+        while self.accumulator >= self.tick_duration {
+            self.accumulator -= self.tick_duration;
+            ticks += 1;
+        }
+        ticks
+    }
+
+    ///how far we are between the last tick and the next one, in `[0., 1.[`
+    pub fn alpha(&self) -> f32 {
+        (self.accumulator.as_secs_f64() / self.tick_duration.as_secs_f64()) as f32
+    }
+}
+
+impl Default for FixedTimestep {
+    fn default() -> Self {
+        Self::new(Self::DEFAULT_TICK_RATE)
+    }
+}
+
+///the time between two events happening `rate` times per second, `None` if the rate isn't
+///positive. The rate is clamped to `[MIN_TICK_RATE, MAX_TICK_RATE]`
+pub(crate) fn period(rate: f64) -> Option<Duration> {
+    (rate > 0.).then(|| {
+        let rate = rate.clamp(FixedTimestep::MIN_TICK_RATE, FixedTimestep::MAX_TICK_RATE);
+        Duration::from_secs_f64(1. / rate)
+    })
+}
+
+/******************\
+*                 *
+*----FrameStats---*
+*                 *
+\******************/
+
+#[derive(Debug, Clone)]
+pub struct FrameStats {
+    frame_times: VecDeque<Duration>,
+    window: usize,
+
+    frame_count: u64,
+    tick_count: u64,
+}
+
+impl FrameStats {
+    pub const DEFAULT_WINDOW: usize = 120;
+
+    ///`window` is the number of frames used to compute the averages
+    pub fn new(window: usize) -> Self {
+        Self {
+            frame_times: VecDeque::with_capacity(window),
+            window: window.max(1),
+            frame_count: 0,
+            tick_count: 0,
+        }
+    }
+
+    pub fn record_frame(&mut self, frame_time: Duration, ticks: u32) {
+        if self.frame_times.len() == self.window {
+            self.frame_times.pop_front();
+        }
+        self.frame_times.push_back(frame_time);
+        self.frame_count += 1;
+        self.tick_count += ticks as u64;
+    }
+
+    ///number of frames since the start
+    pub fn frame_count(&self) -> u64 {
+        self.frame_count
+    }
+
+    ///number of updates since the start
+    pub fn tick_count(&self) -> u64 {
+        self.tick_count
+    }
+
+    ///duration of the last frame
+    pub fn frame_time(&self) -> Duration {
+        self.frame_times.back().copied().unwrap_or_default()
+    }
+
+    pub fn average_frame_time(&self) -> Duration {
+        if self.frame_times.is_empty() {
+            return Duration::ZERO;
+        }
+        self.frame_times.iter().sum::<Duration>() / self.frame_times.len() as u32
+    }
+
+    pub fn min_frame_time(&self) -> Duration {
+        self.frame_times.iter().min().copied().unwrap_or_default()
+    }
+
+    pub fn max_frame_time(&self) -> Duration {
+        self.frame_times.iter().max().copied().unwrap_or_default()
+    }
+
+    ///frames per second, averaged over the stats window
+    pub fn fps(&self) -> f64 {
+        let average = self.average_frame_time().as_secs_f64();
+        if average == 0. {
+            0.
+        } else {
+            1. / average
+        }
+    }
+}
+
+impl Default for FrameStats {
+    fn default() -> Self {
+        Self::new(Self::DEFAULT_WINDOW)
+    }
+}
+
+/******************\
+*                 *
+*-----GameLoop----*
+*                 *
+\******************/
+
+///the frame logic shared by `App` and `HeadlessApp`
+#[derive(Debug, Clone, Default)]
+pub(crate) struct GameLoop {
+    pub(crate) timestep: FixedTimestep,
+    pub(crate) stats: FrameStats,
+}
+
+impl GameLoop {
+    ///run the pending updates then draw a frame
+    pub(crate) fn frame<S: Scene<E> + ?Sized, E: 'static>(
+        &mut self,
+        scene: &mut S,
+        graphics: &mut dyn Graphics,
+        input: &mut InputState,
+        control: &mut SceneControl,
+        frame_time: Duration,
+    ) {
+        let ticks = self.timestep.advance(frame_time);
+        let dt = self.timestep.dt();
+        for _ in 0..ticks {
+            scene.update(dt, input, control);
+            input.end_tick();
+        }
+
+        scene.draw_frame(graphics, self.timestep.alpha());
+        graphics.end_frame();
+
+        self.stats.record_frame(frame_time, ticks);
+        scene.handle_frame_stats(&self.stats);
+    }
+}
@@ -0,0 +1,207 @@
+use std::num::NonZeroU32;
+
+use glium::{
+    backend::glutin::Display,
+    winit::{
+        dpi::PhysicalSize,
+        event_loop::ActiveEventLoop,
+        raw_window_handle::{HasDisplayHandle, HasWindowHandle, RawWindowHandle},
+        window::{Fullscreen, Window},
+    },
+};
+use glutin::{
+    config::{Config, ConfigTemplateBuilder},
+    context::ContextAttributesBuilder,
+    display::{Display as GlutinDisplay, DisplayApiPreference},
+    prelude::*,
+    surface::{SurfaceAttributesBuilder, SwapInterval, WindowSurface},
+};
+
+use super::AppError;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct WindowConfig {
+    pub title: String,
+    pub width: u32,
+    pub height: u32,
+
+    pub vsync: bool,
+    ///number of samples for multisample anti-aliasing, 0 to disable it
+    pub msaa_samples: u8,
+    pub depth_bits: u8,
+
+    ///borderless fullscreen on the current monitor
+    pub fullscreen: bool,
+    pub resizable: bool,
+}
+
+impl Default for WindowConfig {
+    fn default() -> Self {
+        Self {
+            title: "my_glium_util".to_string(),
+            width: 800,
+            height: 600,
+            vsync: true,
+            msaa_samples: 0,
+            depth_bits: 24,
+            fullscreen: false,
+            resizable: true,
+        }
+    }
+}
+
+impl WindowConfig {
+    pub fn with_title(mut self, title: impl Into<String>) -> Self {
+        self.title = title.into();
+        self
+    }
+
+    pub fn with_size(mut self, width: u32, height: u32) -> Self {
+        self.width = width;
+        self.height = height;
+        self
+    }
+
+    pub fn with_vsync(mut self, vsync: bool) -> Self {
+        self.vsync = vsync;
+        self
+    }
+
+    pub fn with_msaa_samples(mut self, samples: u8) -> Self {
+        self.msaa_samples = samples;
+        self
+    }
+
+    pub fn with_depth_bits(mut self, bits: u8) -> Self {
+        self.depth_bits = bits;
+        self
+    }
+
+    pub fn with_fullscreen(mut self, fullscreen: bool) -> Self {
+        self.fullscreen = fullscreen;
+        self
+    }
+
+    pub fn with_resizable(mut self, resizable: bool) -> Self {
+        self.resizable = resizable;
+        self
+    }
+}
+
+///creates the window and the glium display described by `config`
+pub(crate) fn create_display(
+    event_loop: &ActiveEventLoop,
+    config: &WindowConfig,
+) -> Result<(Window, Display<WindowSurface>), AppError> {
+    let mut window_attributes = Window::default_attributes()
+        .with_title(config.title.clone())
+        .with_inner_size(PhysicalSize::new(config.width, config.height))
+        .with_resizable(config.resizable);
+    if config.fullscreen {
+        window_attributes = window_attributes.with_fullscreen(Some(Fullscreen::Borderless(None)));
+    }
+
+    let mut template = ConfigTemplateBuilder::new().with_depth_size(config.depth_bits);
+    if config.msaa_samples > 0 {
+        template = template.with_multisampling(config.msaa_samples);
+    }
+
+    //WGL needs the window before the config, with the other APIs the window is made to
+    //match the config
+    let early_window = match cfg!(target_os = "windows") {
+        true => Some(
+            event_loop
+                .create_window(window_attributes.clone())
+                .map_err(|e| AppError::DisplayCreationError(e.to_string()))?,
+        ),
+        false => None,
+    };
+    let early_handle = match &early_window {
+        Some(window) => Some(
+            window
+                .window_handle()
+                .map_err(|e| AppError::DisplayCreationError(e.to_string()))?
+                .as_raw(),
+        ),
+        None => None,
+    };
+    if let Some(handle) = early_handle {
+        template = template.compatible_with_native_window(handle);
+    }
+
+    let gl_display = create_gl_display(event_loop, early_handle)?;
+    let configs = unsafe { gl_display.find_configs(template.build()) }
+        .map_err(|e| AppError::DisplayCreationError(e.to_string()))?;
+    let gl_config = pick_config(configs)
+        .ok_or_else(|| AppError::DisplayCreationError("no OpenGL config available".to_string()))?;
+
+    let window = match early_window {
+        Some(window) => window,
+        None => glutin_winit::finalize_window(event_loop, window_attributes, &gl_config)
+            .map_err(|e| AppError::DisplayCreationError(e.to_string()))?,
+    };
+
+    let window_handle = window
+        .window_handle()
+        .map_err(|e| AppError::DisplayCreationError(e.to_string()))?;
+
+    let (width, height): (u32, u32) = window.inner_size().into();
+    let surface_attributes = SurfaceAttributesBuilder::<WindowSurface>::new().build(
+        window_handle.as_raw(),
+        NonZeroU32::new(width.max(1)).unwrap(),
+        NonZeroU32::new(height.max(1)).unwrap(),
+    );
+    let context_attributes = ContextAttributesBuilder::new().build(Some(window_handle.as_raw()));
+
+    let surface = unsafe { gl_display.create_window_surface(&gl_config, &surface_attributes) }
+        .map_err(|e| AppError::DisplayCreationError(e.to_string()))?;
+    let context = unsafe { gl_display.create_context(&gl_config, &context_attributes) }
+        .and_then(|context| context.make_current(&surface))
+        .map_err(|e| AppError::DisplayCreationError(e.to_string()))?;
+
+    let swap_interval = if config.vsync {
+        SwapInterval::Wait(NonZeroU32::MIN)
+    } else {
+        SwapInterval::DontWait
+    };
+    //not every platform let us choose, so this is not an error
+    let _ = surface.set_swap_interval(&context, swap_interval);
+
+    let display = Display::from_context_surface(context, surface)
+        .map_err(|e| AppError::DisplayCreationError(e.to_string()))?;
+
+    Ok((window, display))
+}
+
+///the display of the OpenGL API of the platform, like the one of `glutin_winit::DisplayBuilder`.
+///WGL needs a window to load its extensions
+fn create_gl_display(
+    event_loop: &ActiveEventLoop,
+    window: Option<RawWindowHandle>,
+) -> Result<GlutinDisplay, AppError> {
+    #[cfg(target_os = "windows")]
+    let preference = DisplayApiPreference::WglThenEgl(window);
+    #[cfg(target_os = "macos")]
+    let preference = DisplayApiPreference::Cgl;
+    #[cfg(not(any(target_os = "windows", target_os = "macos")))]
+    let preference = DisplayApiPreference::Egl;
+    #[cfg(not(target_os = "windows"))]
+    let _ = window;
+
+    let display_handle = event_loop
+        .display_handle()
+        .map_err(|e| AppError::DisplayCreationError(e.to_string()))?;
+    unsafe { GlutinDisplay::new(display_handle.as_raw(), preference) }
+        .map_err(|e| AppError::DisplayCreationError(e.to_string()))
+}
+
+///the config with the most samples, `None` if there is no config
+pub(crate) fn pick_config(configs: impl Iterator<Item = Config>) -> Option<Config> {
+    configs.reduce(|best, config| {
+        if config.num_samples() > best.num_samples() {
+            config
+        } else {
+            best
+        }
+    })
+}
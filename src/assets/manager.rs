@@ -0,0 +1,208 @@
+use std::{
+    any::{Any, TypeId},
+    marker::PhantomData,
+    path::{Path, PathBuf},
+    time::SystemTime,
+};
+
+use crate::file_parser::LoaderRegistry;
+
+use super::{Asset, AssetError};
+
+///a reference to an asset loaded by an `AssetManager`,
+///it stays valid when the asset is reloaded
+pub struct AssetHandle<T> {
+    index: usize,
+    _marker: PhantomData<fn() -> T>,
+}
+
+impl<T> Clone for AssetHandle<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for AssetHandle<T> {}
+
+impl<T> PartialEq for AssetHandle<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.index == other.index
+    }
+}
+
+impl<T> Eq for AssetHandle<T> {}
+
+impl<T> std::hash::Hash for AssetHandle<T> {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.index.hash(state);
+    }
+}
+
+impl<T> std::fmt::Debug for AssetHandle<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "AssetHandle({})", self.index)
+    }
+}
+
+///returned by `AssetManager::poll`
+#[derive(Debug)]
+pub enum AssetEvent {
+    Reloaded(PathBuf),
+    ///the last good version of the asset is kept
+    ReloadFailed(PathBuf, AssetError),
+}
+
+type Loader = fn(&Path, &LoaderRegistry) -> Result<Box<dyn Any>, AssetError>;
+
+struct AssetEntry {
+    path: PathBuf,
+    type_id: TypeId,
+    modified: Option<SystemTime>,
+    generation: u32,
+
+    value: Box<dyn Any>,
+    load: Loader,
+}
+
+///loads assets by path and caches them behind handles.
+///`AssetManager::poll` reloads the assets whose file has been modified
+#[derive(Default)]
+pub struct AssetManager {
+    entries: Vec<AssetEntry>,
+    ///reads the `SceneData` assets
+    registry: LoaderRegistry,
+}
+
+impl AssetManager {
+    ///with the default `LoaderRegistry`
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_registry(registry: LoaderRegistry) -> Self {
+        Self {
+            entries: vec![],
+            registry,
+        }
+    }
+
+    pub fn registry(&self) -> &LoaderRegistry {
+        &self.registry
+    }
+
+    ///the loaders added to the registry are used by the next loads and reloads
+    pub fn registry_mut(&mut self) -> &mut LoaderRegistry {
+        &mut self.registry
+    }
+
+    ///loading the same file twice gives the same handle
+    pub fn load<T: Asset, P: AsRef<Path>>(
+        &mut self,
+        path: P,
+    ) -> Result<AssetHandle<T>, AssetError> {
+        let path = path.as_ref();
+        let type_id = TypeId::of::<T>();
+
+        let index = match self
+            .entries
+            .iter()
+            .position(|entry| entry.type_id == type_id && entry.path == path)
+        {
+            Some(index) => index,
+            None => {
+                let load: Loader = |path, registry| {
+                    Ok(Box::new(T::load_with_registry(path, registry)?) as Box<dyn Any>)
+                };
+                let modified = modified_time(path);
+                let value = load(path, &self.registry)?;
+
+                self.entries.push(AssetEntry {
+                    path: path.to_path_buf(),
+                    type_id,
+                    modified,
+                    generation: 0,
+                    value,
+                    load,
+                });
+                self.entries.len() - 1
+            }
+        };
+
+        Ok(AssetHandle {
+            index,
+            _marker: PhantomData,
+        })
+    }
+
+    pub fn get<T: Asset>(&self, handle: AssetHandle<T>) -> Option<&T> {
+        self.entries.get(handle.index)?.value.downcast_ref()
+    }
+
+    pub fn path<T>(&self, handle: AssetHandle<T>) -> Option<&Path> {
+        self.entries
+            .get(handle.index)
+            .map(|entry| entry.path.as_path())
+    }
+
+    ///incremented each time the asset is reloaded,
+    ///can be used to know when the GPU data has to be rebuilt
+    pub fn generation<T>(&self, handle: AssetHandle<T>) -> u32 {
+        self.entries
+            .get(handle.index)
+            .map_or(0, |entry| entry.generation)
+    }
+
+    ///the paths of every loaded asset, to be given to a `FileWatcher`
+    pub fn paths(&self) -> impl Iterator<Item = &Path> {
+        self.entries.iter().map(|entry| entry.path.as_path())
+    }
+
+    ///reload the assets whose file modification time changed
+    pub fn poll(&mut self) -> Vec<AssetEvent> {
+        let mut events = vec![];
+        for entry in &mut self.entries {
+            let modified = modified_time(&entry.path);
+            if modified == entry.modified {
+                continue;
+            }
+            entry.modified = modified;
+
+            events.push(entry.reload(&self.registry));
+        }
+        events
+    }
+
+    ///reload every asset loaded from `path`, even if the file did not change
+    pub fn reload<P: AsRef<Path>>(&mut self, path: P) -> Vec<AssetEvent> {
+        let path = path.as_ref();
+        self.entries
+            .iter_mut()
+            .filter(|entry| entry.path == path)
+            .map(|entry| {
+                entry.modified = modified_time(&entry.path);
+                entry.reload(&self.registry)
+            })
+            .collect()
+    }
+}
+
+impl AssetEntry {
+    fn reload(&mut self, registry: &LoaderRegistry) -> AssetEvent {
+        let path = self.path.clone();
+        match (self.load)(&self.path, registry) {
+            Ok(value) => {
+                self.value = value;
+                self.generation += 1;
+                AssetEvent::Reloaded(path)
+            }
+            Err(e) => AssetEvent::ReloadFailed(path, e),
+        }
+    }
+}
+
+///`None` if the file does not exist (anymore)
+pub(crate) fn modified_time(path: &Path) -> Option<SystemTime> {
+    std::fs::metadata(path)
+        .and_then(|metadata| metadata.modified())
+        .ok()
+}
@@ -0,0 +1,79 @@
+mod manager;
+mod watcher;
+
+#[cfg(test)]
+mod test;
+
+use std::{fmt::Display, path::Path};
+
+use crate::file_parser::{
+    FormatError, LoaderRegistry, SceneData, WavefrontError, WavefrontParsable,
+};
+
+pub use manager::*;
+pub use watcher::*;
+
+///something that can be loaded from a file by the `AssetManager`
+pub trait Asset: Sized + 'static {
+    fn load_from_file(path: &Path) -> Result<Self, AssetError>;
+
+    ///used by the `AssetManager`, the mesh files are read with its registry
+    fn load_with_registry(path: &Path, _registry: &LoaderRegistry) -> Result<Self, AssetError> {
+        Self::load_from_file(path)
+    }
+}
+
+impl<T: WavefrontParsable + 'static> Asset for T {
+    fn load_from_file(path: &Path) -> Result<Self, AssetError> {
+        T::read_from_obj(path).map_err(AssetError::WavefrontError)
+    }
+}
+
+///any mesh file known by the default `LoaderRegistry`, or by the registry of the `AssetManager`
+impl Asset for SceneData {
+    fn load_from_file(path: &Path) -> Result<Self, AssetError> {
+        Self::load_with_registry(path, &LoaderRegistry::default())
+    }
+
+    fn load_with_registry(path: &Path, registry: &LoaderRegistry) -> Result<Self, AssetError> {
+        registry.load(path).map_err(AssetError::FormatError)
+    }
+}
+
+///the source code of a GLSL shader
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ShaderSource {
+    pub source: String,
+}
+
+impl ShaderSource {
+    pub fn as_str(&self) -> &str {
+        &self.source
+    }
+}
+
+impl Asset for ShaderSource {
+    fn load_from_file(path: &Path) -> Result<Self, AssetError> {
+        let source = std::fs::read_to_string(path).map_err(AssetError::IOError)?;
+        Ok(Self { source })
+    }
+}
+
+#[derive(Debug)]
+pub enum AssetError {
+    IOError(std::io::Error),
+    WavefrontError(WavefrontError),
+    FormatError(FormatError),
+}
+
+impl Display for AssetError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AssetError::IOError(e) => write!(f, "IO error :{}", e),
+            AssetError::WavefrontError(e) => write!(f, "wavefront error :{}", e),
+            AssetError::FormatError(e) => write!(f, "format error :{}", e),
+        }
+    }
+}
+
+impl std::error::Error for AssetError {}
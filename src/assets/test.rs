@@ -0,0 +1,147 @@
+mod test_asset_manager {
+    use std::{
+        fs::File,
+        path::PathBuf,
+        time::{Duration, SystemTime},
+    };
+
+    use crate::{
+        assets::{AssetError, AssetEvent, AssetManager, ShaderSource},
+        file_parser::{FormatError, LoaderRegistry, SceneData, WavefrontLoader, WavefrontObj},
+    };
+
+    fn temp_file(name: &str, content: &str) -> PathBuf {
+        let path = std::env::temp_dir().join(format!("my_glium_util_{}", name));
+        std::fs::write(&path, content).unwrap();
+        path
+    }
+
+    ///the mtime resolution can be coarse, so the modification time is moved forward by hand
+    fn touch(path: &PathBuf, content: &str) {
+        std::fs::write(path, content).unwrap();
+        File::options()
+            .write(true)
+            .open(path)
+            .unwrap()
+            .set_modified(SystemTime::now() + Duration::from_secs(10))
+            .unwrap();
+    }
+
+    #[test]
+    fn test_load_is_cached() {
+        let mut assets = AssetManager::new();
+        let triangle = assets
+            .load::<WavefrontObj, _>("tests/obj/valid/triangle.obj")
+            .unwrap();
+        let again = assets
+            .load::<WavefrontObj, _>("tests/obj/valid/triangle.obj")
+            .unwrap();
+
+        assert_eq!(triangle, again);
+        assert!(assets.get(triangle).is_some());
+        assert_eq!(assets.paths().count(), 1);
+        assert!(assets.poll().is_empty());
+    }
+
+    #[test]
+    fn test_shader_reload() {
+        let path = temp_file("reload.vert", "void main() {}");
+        let mut assets = AssetManager::new();
+        let shader = assets.load::<ShaderSource, _>(&path).unwrap();
+        assert_eq!(assets.generation(shader), 0);
+
+        touch(&path, "void main() { gl_Position = vec4(0.); }");
+        let events = assets.poll();
+        assert!(matches!(events.as_slice(), [AssetEvent::Reloaded(p)] if *p == path));
+        assert_eq!(
+            assets.get(shader).map(ShaderSource::as_str),
+            Some("void main() { gl_Position = vec4(0.); }")
+        );
+        assert_eq!(assets.generation(shader), 1);
+
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_failed_reload_keeps_last_version() {
+        let path = temp_file("failed.frag", "void main() {}");
+        let mut assets = AssetManager::new();
+        let shader = assets.load::<ShaderSource, _>(&path).unwrap();
+
+        std::fs::remove_file(&path).unwrap();
+        let events = assets.poll();
+        assert!(matches!(
+            events.as_slice(),
+            [AssetEvent::ReloadFailed(_, _)]
+        ));
+        assert_eq!(
+            assets.get(shader).map(ShaderSource::as_str),
+            Some("void main() {}")
+        );
+        assert_eq!(assets.generation(shader), 0);
+    }
+
+    #[test]
+    fn test_invalid_reload_keeps_last_version() {
+        let path = temp_file("invalid.obj", "v 0 0 0\nv 1 0 0\n");
+        let mut assets = AssetManager::new();
+        let obj = assets.load::<WavefrontObj, _>(&path).unwrap();
+
+        touch(&path, "v 0 0 0\nf a b c\n");
+        let events = assets.poll();
+        assert!(matches!(
+            events.as_slice(),
+            [AssetEvent::ReloadFailed(p, _)] if *p == path
+        ));
+        assert_eq!(
+            assets.get(obj).map(|obj| obj.geometric_vertices.len()),
+            Some(2)
+        );
+        assert_eq!(assets.generation(obj), 0);
+
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_scene_uses_the_registry() {
+        let mut assets = AssetManager::with_registry(LoaderRegistry::empty());
+        let result = assets.load::<SceneData, _>("tests/obj/valid/cube.obj");
+        assert!(matches!(
+            result,
+            Err(AssetError::FormatError(FormatError::UnknownFormat(_)))
+        ));
+        assert_eq!(assets.paths().count(), 0);
+
+        assets.registry_mut().register(WavefrontLoader);
+        let scene = assets
+            .load::<SceneData, _>("tests/obj/valid/cube.obj")
+            .unwrap();
+        assert_eq!(
+            assets.get(scene).map(|scene| scene.meshes[0].indices.len()),
+            Some(36)
+        );
+    }
+}
+
+mod test_file_watcher {
+    use std::{sync::mpsc, time::Duration};
+
+    use crate::assets::FileWatcher;
+
+    #[test]
+    fn test_watcher_notifies() {
+        let path = std::env::temp_dir().join("my_glium_util_watched.glsl");
+        std::fs::write(&path, "a").unwrap();
+
+        let (sender, receiver) = mpsc::channel();
+        let watcher = FileWatcher::spawn(vec![path.clone()], Duration::from_millis(10), move |p| {
+            let _ = sender.send(p);
+        });
+
+        std::fs::remove_file(&path).unwrap();
+        let changed = receiver.recv_timeout(Duration::from_secs(2));
+        drop(watcher);
+
+        assert_eq!(changed.ok(), Some(path));
+    }
+}
@@ -0,0 +1,69 @@
+use std::{
+    path::PathBuf,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    thread::JoinHandle,
+    time::{Duration, SystemTime},
+};
+
+use super::manager::modified_time;
+
+///polls the modification time of files from a background thread.
+///
+///`on_change` is called from the watcher thread, an `EventLoopProxy` (see `App::proxy`)
+///can be used to send the path to `Scene::handle_user_event`, which can then call
+///`AssetManager::reload`
+pub struct FileWatcher {
+    stop: Arc<AtomicBool>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl FileWatcher {
+    pub const DEFAULT_INTERVAL: Duration = Duration::from_millis(500);
+
+    pub fn spawn<F>(paths: Vec<PathBuf>, interval: Duration, mut on_change: F) -> Self
+    where
+        F: FnMut(PathBuf) + Send + 'static,
+    {
+        let stop = Arc::new(AtomicBool::new(false));
+        let thread_stop = stop.clone();
+
+        let thread = std::thread::spawn(move || {
+            let mut files: Vec<(PathBuf, Option<SystemTime>)> = paths
+                .into_iter()
+                .map(|path| {
+                    let modified = modified_time(&path);
+                    (path, modified)
+                })
+                .collect();
+
+            while !thread_stop.load(Ordering::Relaxed) {
+                std::thread::sleep(interval);
+
+                for (path, last_modified) in &mut files {
+                    let modified = modified_time(path);
+                    if modified != *last_modified {
+                        *last_modified = modified;
+                        on_change(path.clone());
+                    }
+                }
+            }
+        });
+
+        Self {
+            stop,
+            thread: Some(thread),
+        }
+    }
+}
+
+impl Drop for FileWatcher {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
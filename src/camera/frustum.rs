@@ -0,0 +1,57 @@
+use super::Camera;
+use crate::utils::types_util::Arr3F32;
+
+///the six planes of a view projection, used to skip what the camera can't see.
+///Each plane is `[a, b, c, d]` with `a*x + b*y + c*z + d >= 0` inside
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Frustum {
+    planes: [[f32; 4]; 6],
+}
+
+impl Frustum {
+    ///`view_projection` is column major, like glium uniforms
+    pub fn from_view_projection(view_projection: [[f32; 4]; 4]) -> Self {
+        let row = |i: usize| view_projection.map(|column| column[i]);
+        let (x, y, z, w) = (row(0), row(1), row(2), row(3));
+
+        let plane = |sign: f32, axis: [f32; 4]| {
+            let plane: [f32; 4] = std::array::from_fn(|i| w[i] + sign * axis[i]);
+            let length = (plane[0] * plane[0] + plane[1] * plane[1] + plane[2] * plane[2]).sqrt();
+            if length == 0. {
+                plane
+            } else {
+                plane.map(|c| c / length)
+            }
+        };
+
+        Self {
+            planes: [
+                plane(1., x),
+                plane(-1., x),
+                plane(1., y),
+                plane(-1., y),
+                plane(1., z),
+                plane(-1., z),
+            ],
+        }
+    }
+
+    pub fn from_camera<C: Camera + ?Sized>(camera: &C) -> Self {
+        Self::from_view_projection(camera.view_projection_matrix().into())
+    }
+
+    pub fn planes(&self) -> &[[f32; 4]; 6] {
+        &self.planes
+    }
+
+    pub fn contains_point(&self, point: Arr3F32) -> bool {
+        self.intersects_sphere(point, 0.)
+    }
+
+    ///true if a part of the sphere may be visible
+    pub fn intersects_sphere(&self, center: Arr3F32, radius: f32) -> bool {
+        self.planes
+            .iter()
+            .all(|[a, b, c, d]| a * center[0] + b * center[1] + c * center[2] + d >= -radius)
+    }
+}
@@ -1,2 +1,8 @@
 mod camera;
+mod frustum;
+
+#[cfg(test)]
+mod test;
+
 pub use camera::*;
+pub use frustum::*;
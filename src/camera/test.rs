@@ -0,0 +1,15 @@
+mod test_frustum {
+    use crate::{camera::Frustum, utils::matrix_util::IDENTITY};
+
+    #[test]
+    fn test_frustum() {
+        let frustum = Frustum::from_view_projection(IDENTITY);
+
+        assert!(frustum.contains_point([0., 0., 0.]));
+        assert!(frustum.contains_point([1., -1., 1.]));
+        assert!(!frustum.contains_point([2., 0., 0.]));
+        assert!(!frustum.contains_point([0., 0., -1.5]));
+        assert!(frustum.intersects_sphere([2., 0., 0.], 1.5));
+        assert!(!frustum.intersects_sphere([0., 3., 0.], 1.5));
+    }
+}
@@ -0,0 +1,372 @@
+use std::path::Path;
+
+use gltf::{animation::util::ReadOutputs, buffer::Data, mesh::Mode, Document};
+
+use crate::{
+    animation::{AnimationClip, Interpolation, Keyframe, Track},
+    mesh::vertex::Vertex,
+    object_traits::{Rotation, Scale, Translation},
+    utils::types_util::{Arr3F32, Arr4F32, QuatF32},
+};
+
+use super::{
+    GltfAlphaMode, GltfAnimation, GltfError, GltfImageSource, GltfMaterial, GltfMesh, GltfNode,
+    GltfPrimitive, GltfScene,
+};
+
+impl GltfScene {
+    ///a .gltf file, its buffers are relative to it, or a .glb file
+    pub fn read_from_gltf<P: AsRef<Path>>(path: P) -> Result<Self, GltfError> {
+        let bytes = std::fs::read(path.as_ref()).map_err(GltfError::IOError)?;
+        Self::from_slice(&bytes, path.as_ref().parent())
+    }
+
+    ///the content of a .gltf or .glb file, the external buffers are relative to `base`
+    pub fn from_slice(bytes: &[u8], base: Option<&Path>) -> Result<Self, GltfError> {
+        let gltf = gltf::Gltf::from_slice(bytes).map_err(GltfError::from)?;
+        let buffers =
+            gltf::import_buffers(&gltf.document, base, gltf.blob).map_err(GltfError::from)?;
+        let document = gltf.document;
+
+        let mut nodes: Vec<_> = document.nodes().map(parse_node).collect();
+        for node in document.nodes() {
+            for child in node.children() {
+                nodes[child.index()].parent = Some(node.index());
+            }
+        }
+
+        let roots = document
+            .default_scene()
+            .or_else(|| document.scenes().next())
+            .map(|scene| scene.nodes().map(|node| node.index()).collect())
+            .unwrap_or_default();
+
+        let meshes = document
+            .meshes()
+            .map(|mesh| parse_mesh(mesh, &buffers))
+            .collect::<Result<_, _>>()?;
+        let materials = document
+            .materials()
+            .map(|material| parse_material(material, &buffers))
+            .collect();
+        let animations = parse_animations(&document, &buffers)?;
+
+        Ok(Self {
+            nodes,
+            roots,
+            meshes,
+            materials,
+            animations,
+        })
+    }
+}
+
+impl From<gltf::Error> for GltfError {
+    fn from(value: gltf::Error) -> Self {
+        match value {
+            gltf::Error::Io(e) => GltfError::IOError(e),
+            e => GltfError::InvalidGltfData(e.to_string()),
+        }
+    }
+}
+
+/*------------------*\
+|       Nodes        |
+\*------------------*/
+
+fn parse_node(node: gltf::Node) -> GltfNode {
+    let (translation, [x, y, z, w], scale) = node.transform().decomposed();
+    GltfNode {
+        name: node.name().map(str::to_string),
+        parent: None,
+        children: node.children().map(|child| child.index()).collect(),
+        mesh: node.mesh().map(|mesh| mesh.index()),
+        translation: translation.into(),
+        rotation: QuatF32::from((w, x, y, z)).into(),
+        scale: scale.into(),
+    }
+}
+
+/*------------------*\
+|       Meshes       |
+\*------------------*/
+
+fn parse_mesh(mesh: gltf::Mesh, buffers: &[Data]) -> Result<GltfMesh, GltfError> {
+    let name = mesh.name().map(str::to_string);
+    let primitives = mesh
+        .primitives()
+        .map(|primitive| parse_primitive(&name, primitive, buffers))
+        .collect::<Result<_, _>>()?;
+    Ok(GltfMesh { name, primitives })
+}
+
+fn parse_primitive(
+    mesh_name: &Option<String>,
+    primitive: gltf::Primitive,
+    buffers: &[Data],
+) -> Result<GltfPrimitive, GltfError> {
+    let mesh_name = mesh_name.as_deref().unwrap_or("unnamed");
+    let reader = primitive.reader(|buffer| Some(buffers[buffer.index()].0.as_slice()));
+
+    let positions: Vec<Arr3F32> = reader
+        .read_positions()
+        .ok_or_else(|| GltfError::MissingData(format!("positions of the mesh {}", mesh_name)))?
+        .collect();
+    let normals: Option<Vec<Arr3F32>> = reader.read_normals().map(Iterator::collect);
+    let tex_coords: Option<Vec<[f32; 2]>> = reader
+        .read_tex_coords(0)
+        .map(|tex_coords| tex_coords.into_f32().collect());
+    let indices: Vec<u32> = match reader.read_indices() {
+        Some(indices) => indices.into_u32().collect(),
+        None => (0..positions.len() as u32).collect(),
+    };
+
+    if let Some(index) = indices.iter().find(|i| **i as usize >= positions.len()) {
+        return Err(GltfError::InvalidGltfData(format!(
+            "the index {} of the mesh {} is out of bounds",
+            index, mesh_name
+        )));
+    }
+    let indices = triangle_list(primitive.mode(), indices)
+        .ok_or_else(|| GltfError::UnsupportedPrimitive(format!("{:?}", primitive.mode())))?;
+
+    let mut vertices: Vec<_> = positions
+        .iter()
+        .enumerate()
+        .map(|(i, position)| {
+            let mut vertex = Vertex::from(*position);
+            if let Some(normal) = normals.as_ref().and_then(|normals| normals.get(i)) {
+                vertex.normal = *normal;
+            }
+            if let Some([u, v]) = tex_coords.as_ref().and_then(|tex_coords| tex_coords.get(i)) {
+                vertex.texture = [*u, 1. - v, 0.];
+            }
+            vertex
+        })
+        .collect();
+
+    //without normals, the triangles are flat shaded
+    let indices = match normals {
+        Some(_) => indices,
+        None => {
+            vertices = flat_shaded(&vertices, &indices);
+            (0..vertices.len() as u32).collect()
+        }
+    };
+
+    Ok(GltfPrimitive {
+        vertices,
+        indices,
+        material: primitive.material().index(),
+    })
+}
+
+///the strips and the fans are converted, the points and the lines are not supported
+pub(crate) fn triangle_list(mode: Mode, indices: Vec<u32>) -> Option<Vec<u32>> {
+    let count = indices.len().saturating_sub(2);
+    match mode {
+        Mode::Triangles => Some(indices),
+        //every other triangle is flipped to keep the winding
+        Mode::TriangleStrip => Some(
+            (0..count)
+                .flat_map(|i| match i % 2 {
+                    0 => [indices[i], indices[i + 1], indices[i + 2]],
+                    _ => [indices[i + 1], indices[i], indices[i + 2]],
+                })
+                .collect(),
+        ),
+        Mode::TriangleFan => Some(
+            (0..count)
+                .flat_map(|i| [indices[0], indices[i + 1], indices[i + 2]])
+                .collect(),
+        ),
+        _ => None,
+    }
+}
+
+///a vertex per corner, with the normal of its triangle
+fn flat_shaded(vertices: &[Vertex], indices: &[u32]) -> Vec<Vertex> {
+    indices
+        .chunks_exact(3)
+        .flat_map(|triangle| {
+            let [a, b, c] = [0, 1, 2].map(|i| vertices[triangle[i] as usize]);
+            let u: Arr3F32 = core::array::from_fn(|i| b.position[i] - a.position[i]);
+            let v: Arr3F32 = core::array::from_fn(|i| c.position[i] - a.position[i]);
+            let normal = [
+                u[1] * v[2] - u[2] * v[1],
+                u[2] * v[0] - u[0] * v[2],
+                u[0] * v[1] - u[1] * v[0],
+            ];
+            let length = normal.iter().map(|n| n * n).sum::<f32>().sqrt();
+            let normal = match length > 0. {
+                true => normal.map(|n| n / length),
+                false => normal,
+            };
+
+            [a, b, c].map(|mut vertex| {
+                vertex.normal = normal;
+                vertex
+            })
+        })
+        .collect()
+}
+
+/*------------------*\
+|     Materials      |
+\*------------------*/
+
+fn parse_material(material: gltf::Material, buffers: &[Data]) -> GltfMaterial {
+    let pbr = material.pbr_metallic_roughness();
+    let image = |texture: gltf::Texture| image_source(texture.source(), buffers);
+
+    GltfMaterial {
+        name: material.name().map(str::to_string),
+        base_color: pbr.base_color_factor(),
+        metallic: pbr.metallic_factor(),
+        roughness: pbr.roughness_factor(),
+        emissive: material.emissive_factor(),
+        alpha_mode: match material.alpha_mode() {
+            gltf::material::AlphaMode::Opaque => GltfAlphaMode::Opaque,
+            gltf::material::AlphaMode::Mask => {
+                GltfAlphaMode::Mask(material.alpha_cutoff().unwrap_or(0.5))
+            }
+            gltf::material::AlphaMode::Blend => GltfAlphaMode::Blend,
+        },
+        double_sided: material.double_sided(),
+        base_color_map: pbr.base_color_texture().map(|info| image(info.texture())),
+        metallic_roughness_map: pbr
+            .metallic_roughness_texture()
+            .map(|info| image(info.texture())),
+        normal_map: material
+            .normal_texture()
+            .map(|normal| image(normal.texture())),
+        emissive_map: material
+            .emissive_texture()
+            .map(|info| image(info.texture())),
+    }
+}
+
+fn image_source(image: gltf::Image, buffers: &[Data]) -> GltfImageSource {
+    match image.source() {
+        gltf::image::Source::Uri { uri, .. } => GltfImageSource::Uri(uri.to_string()),
+        gltf::image::Source::View { view, mime_type } => {
+            let buffer = &buffers[view.buffer().index()].0;
+            GltfImageSource::Embedded {
+                mime_type: mime_type.to_string(),
+                bytes: buffer[view.offset()..view.offset() + view.length()].to_vec(),
+            }
+        }
+    }
+}
+
+/*------------------*\
+|     Animations     |
+\*------------------*/
+
+fn parse_animations(
+    document: &Document,
+    buffers: &[Data],
+) -> Result<Vec<GltfAnimation>, GltfError> {
+    let mut animations = vec![];
+    for animation in document.animations() {
+        let name = animation.name().map(str::to_string);
+        let mut clips: Vec<(usize, AnimationClip)> = vec![];
+
+        for channel in animation.channels() {
+            let node = channel.target().node().index();
+            let interpolation = match channel.sampler().interpolation() {
+                gltf::animation::Interpolation::Step => Interpolation::Step,
+                gltf::animation::Interpolation::Linear => Interpolation::Linear,
+                gltf::animation::Interpolation::CubicSpline => Interpolation::CubicSpline,
+            };
+
+            let reader = channel.reader(|buffer| Some(buffers[buffer.index()].0.as_slice()));
+            let missing = || {
+                GltfError::MissingData(format!(
+                    "keyframes of the animation {}",
+                    name.as_deref().unwrap_or("unnamed")
+                ))
+            };
+            let times: Vec<f32> = reader.read_inputs().ok_or_else(missing)?.collect();
+
+            let index = match clips.iter().position(|(n, _)| *n == node) {
+                Some(index) => index,
+                None => {
+                    let mut clip = AnimationClip::empty();
+                    if let Some(name) = &name {
+                        clip = clip.with_name(name.clone());
+                    }
+                    clips.push((node, clip));
+                    clips.len() - 1
+                }
+            };
+            let clip = &mut clips[index].1;
+
+            match reader.read_outputs().ok_or_else(missing)? {
+                ReadOutputs::Translations(values) => {
+                    let values = values.map(|[x, y, z]| [x, y, z, 0.]).collect();
+                    clip.translation =
+                        Some(track(interpolation, &times, values, |[x, y, z, _]| {
+                            Translation::from([x, y, z])
+                        })?);
+                }
+                ReadOutputs::Scales(values) => {
+                    let values = values.map(|[x, y, z]| [x, y, z, 0.]).collect();
+                    clip.scale = Some(track(interpolation, &times, values, |[x, y, z, _]| {
+                        Scale::from([x, y, z])
+                    })?);
+                }
+                ReadOutputs::Rotations(values) => {
+                    let values = values.into_f32().map(|[x, y, z, w]| [w, x, y, z]).collect();
+                    clip.rotation = Some(track(interpolation, &times, values, |[w, x, y, z]| {
+                        Rotation::from(QuatF32::from((w, x, y, z)))
+                    })?);
+                }
+                //the morph targets are not supported
+                ReadOutputs::MorphTargetWeights(_) => {}
+            }
+        }
+
+        clips.retain(|(_, clip)| {
+            clip.translation.is_some() || clip.scale.is_some() || clip.rotation.is_some()
+        });
+        animations.push(GltfAnimation { name, clips });
+    }
+    Ok(animations)
+}
+
+///`values` are the components of the keyframes, with their tangents for the cubic splines
+pub(crate) fn track<T, F>(
+    interpolation: Interpolation,
+    times: &[f32],
+    values: Vec<Arr4F32>,
+    value: F,
+) -> Result<Track<T>, GltfError>
+where
+    T: crate::animation::Animatable,
+    F: Fn(Arr4F32) -> T,
+{
+    let per_keyframe = match interpolation {
+        Interpolation::CubicSpline => 3,
+        _ => 1,
+    };
+    if values.len() != times.len() * per_keyframe {
+        return Err(GltfError::InvalidGltfData(format!(
+            "{} keyframe values for {} keyframe times",
+            values.len(),
+            times.len()
+        )));
+    }
+
+    let keyframes = times
+        .iter()
+        .zip(values.chunks_exact(per_keyframe))
+        .map(|(time, values)| match values {
+            [in_tangent, v, out_tangent] => {
+                Keyframe::with_tangents(*time, value(*v), *in_tangent, *out_tangent)
+            }
+            _ => Keyframe::new(*time, value(values[0])),
+        })
+        .collect();
+    Ok(Track::from_keyframes(interpolation, keyframes))
+}
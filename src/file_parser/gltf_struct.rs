@@ -0,0 +1,246 @@
+use crate::{
+    animation::AnimationClip,
+    mesh::{vertex::Vertex, Mesh, MeshCreationError},
+    object_traits::{Rotation, Scale, SceneObject, Translation},
+    utils::{
+        matrix_util::mul,
+        types_util::{Arr3F32, Arr4F32, Mat4Arr, QuatF32},
+    },
+};
+
+/*------------------*\
+|       glTF         |
+\*------------------*/
+
+///the content of a .gltf or .glb file, the nodes, meshes, materials and animations
+///reference each other by their index
+#[derive(Debug, Clone)]
+pub struct GltfScene {
+    pub(crate) nodes: Vec<GltfNode>,
+    ///the nodes of the default scene, or of the first scene
+    pub(crate) roots: Vec<usize>,
+    pub(crate) meshes: Vec<GltfMesh>,
+    pub(crate) materials: Vec<GltfMaterial>,
+    pub(crate) animations: Vec<GltfAnimation>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct GltfNode {
+    pub name: Option<String>,
+    pub parent: Option<usize>,
+    pub children: Vec<usize>,
+    pub mesh: Option<usize>,
+
+    ///relative to the parent node
+    pub translation: Translation,
+    pub rotation: Rotation,
+    pub scale: Scale,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct GltfMesh {
+    pub name: Option<String>,
+    pub primitives: Vec<GltfPrimitive>,
+}
+
+///a list of triangles sharing a material
+#[derive(Debug, Clone, PartialEq)]
+pub struct GltfPrimitive {
+    pub(crate) vertices: Vec<Vertex>,
+    pub(crate) indices: Vec<u32>,
+    pub(crate) material: Option<usize>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum GltfAlphaMode {
+    Opaque,
+    ///the fragments under the cutoff are discarded
+    Mask(f32),
+    Blend,
+}
+
+///where the pixels of a texture are
+#[derive(Debug, Clone, PartialEq)]
+pub enum GltfImageSource {
+    ///relative to the gltf file
+    Uri(String),
+    ///stored in a buffer, like in the .glb files
+    Embedded { mime_type: String, bytes: Vec<u8> },
+}
+
+///a metallic-roughness PBR material
+#[derive(Debug, Clone, PartialEq)]
+pub struct GltfMaterial {
+    pub name: Option<String>,
+
+    pub base_color: Arr4F32,
+    pub metallic: f32,
+    pub roughness: f32,
+    pub emissive: Arr3F32,
+    pub alpha_mode: GltfAlphaMode,
+    pub double_sided: bool,
+
+    pub base_color_map: Option<GltfImageSource>,
+    ///metallic in the blue channel and roughness in the green channel
+    pub metallic_roughness_map: Option<GltfImageSource>,
+    pub normal_map: Option<GltfImageSource>,
+    pub emissive_map: Option<GltfImageSource>,
+}
+
+///the tracks of an animation, grouped in a clip per animated node
+#[derive(Debug, Clone, PartialEq)]
+pub struct GltfAnimation {
+    pub name: Option<String>,
+    pub clips: Vec<(usize, AnimationClip)>,
+}
+
+impl GltfScene {
+    pub fn nodes(&self) -> &[GltfNode] {
+        &self.nodes
+    }
+
+    pub fn roots(&self) -> &[usize] {
+        &self.roots
+    }
+
+    pub fn meshes(&self) -> &[GltfMesh] {
+        &self.meshes
+    }
+
+    pub fn materials(&self) -> &[GltfMaterial] {
+        &self.materials
+    }
+
+    pub fn animations(&self) -> &[GltfAnimation] {
+        &self.animations
+    }
+
+    pub fn node_by_name(&self, name: &str) -> Option<usize> {
+        self.nodes
+            .iter()
+            .position(|node| node.name.as_deref() == Some(name))
+    }
+
+    pub fn animation_by_name(&self, name: &str) -> Option<&GltfAnimation> {
+        self.animations
+            .iter()
+            .find(|animation| animation.name.as_deref() == Some(name))
+    }
+
+    ///the transform of the node in the scene, with the transforms of its parents.
+    ///The matrix is column major, like glium uniforms
+    pub fn world_matrix(&self, node: usize) -> Mat4Arr {
+        let mut matrix = self.nodes[node].local_matrix();
+        let mut parent = self.nodes[node].parent;
+        while let Some(index) = parent {
+            matrix = mul(self.nodes[index].local_matrix(), matrix);
+            parent = self.nodes[index].parent;
+        }
+        matrix
+    }
+
+    ///a `Mesh` per primitive of the mesh, named like it
+    pub fn mesh(&self, index: usize) -> Result<Vec<Mesh>, MeshCreationError> {
+        let mesh = &self.meshes[index];
+        mesh.primitives
+            .iter()
+            .map(|primitive| {
+                let result = primitive.to_mesh()?;
+                Ok(match &mesh.name {
+                    Some(name) => result.with_name(name),
+                    None => result,
+                })
+            })
+            .collect()
+    }
+
+    ///the meshes of the node, with its translation, rotation and scale.
+    ///The transforms of the parents are not applied, see `GltfScene::world_matrix`
+    pub fn node_meshes(&self, node: usize) -> Result<Vec<Mesh>, MeshCreationError> {
+        let node = &self.nodes[node];
+        let Some(index) = node.mesh else {
+            return Ok(vec![]);
+        };
+
+        let mut meshes = self.mesh(index)?;
+        for mesh in &mut meshes {
+            mesh.set_position(node.translation);
+            mesh.set_rotation(node.rotation);
+            mesh.set_scale(node.scale);
+        }
+        Ok(meshes)
+    }
+}
+
+impl GltfNode {
+    ///translation * rotation * scale, column major
+    pub fn local_matrix(&self) -> Mat4Arr {
+        let (w, x, y, z): (f32, f32, f32, f32) = QuatF32::from(self.rotation).into();
+        let [sx, sy, sz]: Arr3F32 = self.scale.into();
+        let [tx, ty, tz]: Arr3F32 = self.translation.into();
+
+        [
+            [
+                (1. - 2. * (y * y + z * z)) * sx,
+                2. * (x * y + w * z) * sx,
+                2. * (x * z - w * y) * sx,
+                0.,
+            ],
+            [
+                2. * (x * y - w * z) * sy,
+                (1. - 2. * (x * x + z * z)) * sy,
+                2. * (y * z + w * x) * sy,
+                0.,
+            ],
+            [
+                2. * (x * z + w * y) * sz,
+                2. * (y * z - w * x) * sz,
+                (1. - 2. * (x * x + y * y)) * sz,
+                0.,
+            ],
+            [tx, ty, tz, 1.],
+        ]
+    }
+}
+
+impl GltfPrimitive {
+    ///the texture coordinates start from the bottom left corner, like the obj ones
+    pub fn vertices(&self) -> &[Vertex] {
+        &self.vertices
+    }
+
+    ///three indices per triangle
+    pub fn indices(&self) -> &[u32] {
+        &self.indices
+    }
+
+    pub fn material(&self) -> Option<usize> {
+        self.material
+    }
+
+    pub fn triangle_count(&self) -> usize {
+        self.indices.len() / 3
+    }
+
+    ///the material is not kept
+    pub fn to_mesh(&self) -> Result<Mesh, MeshCreationError> {
+        Mesh::from_buffers_data(self.vertices.clone(), &self.indices)
+    }
+}
+
+impl GltfAnimation {
+    pub fn clip(&self, node: usize) -> Option<&AnimationClip> {
+        self.clips
+            .iter()
+            .find(|(index, _)| *index == node)
+            .map(|(_, clip)| clip)
+    }
+
+    ///the duration of the longest clip
+    pub fn duration(&self) -> f32 {
+        self.clips
+            .iter()
+            .map(|(_, clip)| clip.duration())
+            .fold(0., f32::max)
+    }
+}
@@ -1,3 +1,11 @@
+mod gltf_parser;
+mod gltf_struct;
+mod ply_parser;
+mod ply_struct;
+mod registry;
+mod stl_parser;
+mod stl_struct;
+mod wavefront_mtl;
 mod wavefront_parser;
 mod wavefront_struct;
 
@@ -6,6 +14,11 @@ mod test;
 use std::{fmt::Display, path::Path};
 
 // pub use wavefront_parser::*;
+pub use gltf_struct::*;
+pub use ply_struct::*;
+pub use registry::*;
+pub use stl_struct::*;
+pub use wavefront_mtl::*;
 pub use wavefront_struct::*;
 
 pub trait WavefrontParsable {
@@ -52,3 +65,111 @@ impl Display for WavefrontError {
 }
 
 impl std::error::Error for WavefrontError {}
+
+#[derive(Debug)]
+pub enum GltfError {
+    IOError(std::io::Error),
+
+    InvalidGltfData(String),
+    MissingData(String),
+    UnsupportedPrimitive(String),
+}
+
+impl Display for GltfError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            GltfError::IOError(e) => write!(f, "IO error :{}", e),
+            GltfError::InvalidGltfData(data) => {
+                write!(f, "invalid glTF data has been found :{}", data)
+            }
+            GltfError::MissingData(data) => write!(f, "missing glTF data :{}", data),
+            GltfError::UnsupportedPrimitive(mode) => {
+                write!(f, "unsupported primitive mode :{}", mode)
+            }
+        }
+    }
+}
+
+impl std::error::Error for GltfError {}
+
+#[derive(Debug)]
+pub enum StlError {
+    IOError(std::io::Error),
+
+    InvalidLineData(String),
+    InvalidFacetData(String),
+    InvalidBinaryData(String),
+}
+
+impl Display for StlError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            StlError::IOError(e) => write!(f, "IO error :{}", e),
+            StlError::InvalidLineData(line) => {
+                write!(f, "invalid line data has been found :{}", line)
+            }
+            StlError::InvalidFacetData(line) => {
+                write!(f, "invalid facet data has been found :{}", line)
+            }
+            StlError::InvalidBinaryData(data) => {
+                write!(f, "invalid binary data has been found :{}", data)
+            }
+        }
+    }
+}
+
+impl std::error::Error for StlError {}
+
+#[derive(Debug)]
+pub enum PlyError {
+    IOError(std::io::Error),
+
+    InvalidHeaderData(String),
+    InvalidElementData(String),
+}
+
+impl Display for PlyError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PlyError::IOError(e) => write!(f, "IO error :{}", e),
+            PlyError::InvalidHeaderData(line) => {
+                write!(f, "invalid header data has been found :{}", line)
+            }
+            PlyError::InvalidElementData(data) => {
+                write!(f, "invalid element data has been found :{}", data)
+            }
+        }
+    }
+}
+
+impl std::error::Error for PlyError {}
+
+#[derive(Debug)]
+pub enum FormatError {
+    IOError(std::io::Error),
+
+    UnknownFormat(String),
+
+    WavefrontError(WavefrontError),
+    GltfError(GltfError),
+    StlError(StlError),
+    PlyError(PlyError),
+    ///the error of a loader registered outside of this crate
+    LoaderError(Box<dyn std::error::Error + Send + Sync>),
+}
+
+impl Display for FormatError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FormatError::IOError(e) => write!(f, "IO error :{}", e),
+            FormatError::UnknownFormat(file) => write!(f, "no loader has been found for :{}", file),
+            FormatError::WavefrontError(e) => write!(f, "wavefront error :{}", e),
+            FormatError::GltfError(e) => write!(f, "glTF error :{}", e),
+            FormatError::StlError(e) => write!(f, "STL error :{}", e),
+            FormatError::PlyError(e) => write!(f, "PLY error :{}", e),
+            FormatError::LoaderError(e) => write!(f, "loader error :{}", e),
+        }
+    }
+}
+
+impl std::error::Error for FormatError {}
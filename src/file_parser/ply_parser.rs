@@ -0,0 +1,517 @@
+use std::{
+    fs::File,
+    io::{BufWriter, Write},
+    path::Path,
+};
+
+use crate::mesh::vertex::Vertex;
+
+use super::{PlyAttribute, PlyError, PlyFormat, PlyModel, PlyScalar};
+
+/*------------------*\
+|       Header       |
+\*------------------*/
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum PlyProperty {
+    Scalar(String, PlyScalar),
+    ///the type of the length of the list, then the type of its items
+    List(String, PlyScalar, PlyScalar),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct PlyElement {
+    pub(crate) name: String,
+    pub(crate) count: usize,
+    pub(crate) properties: Vec<PlyProperty>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct PlyHeader {
+    pub(crate) format: PlyFormat,
+    pub(crate) elements: Vec<PlyElement>,
+    pub(crate) comments: Vec<String>,
+    ///where the data starts
+    pub(crate) data_offset: usize,
+}
+
+impl PlyProperty {
+    fn name(&self) -> &str {
+        match self {
+            PlyProperty::Scalar(name, _) | PlyProperty::List(name, _, _) => name,
+        }
+    }
+}
+
+///the header ends with the `end_header` line
+pub(crate) fn parse_header(bytes: &[u8]) -> Result<PlyHeader, PlyError> {
+    let mut format = None;
+    let mut elements: Vec<PlyElement> = vec![];
+    let mut comments = vec![];
+    let mut offset = 0;
+    let mut first_line = true;
+
+    loop {
+        let Some(length) = bytes[offset..].iter().position(|b| *b == b'\n') else {
+            return Err(PlyError::InvalidHeaderData(
+                "the end_header line is missing".to_string(),
+            ));
+        };
+        let line = String::from_utf8_lossy(&bytes[offset..offset + length]);
+        let line = line.trim();
+        offset += length + 1;
+
+        let invalid = || PlyError::InvalidHeaderData(line.to_string());
+        let tokens: Vec<_> = line.split_whitespace().collect();
+        if first_line {
+            if line != "ply" {
+                return Err(invalid());
+            }
+            first_line = false;
+            continue;
+        }
+
+        match tokens.as_slice() {
+            [] => {}
+            ["end_header"] => break,
+            ["comment", ..] | ["obj_info", ..] => {
+                comments.push(line[tokens[0].len()..].trim().to_string())
+            }
+            ["format", name, "1.0"] => {
+                format = Some(match *name {
+                    "ascii" => PlyFormat::Ascii,
+                    "binary_little_endian" => PlyFormat::BinaryLittleEndian,
+                    "binary_big_endian" => PlyFormat::BinaryBigEndian,
+                    _ => return Err(invalid()),
+                })
+            }
+            ["element", name, count] => elements.push(PlyElement {
+                name: name.to_string(),
+                count: count.parse().map_err(|_| invalid())?,
+                properties: vec![],
+            }),
+            ["property", "list", count, item, name] => {
+                let count = PlyScalar::from_name(count).ok_or_else(invalid)?;
+                let item = PlyScalar::from_name(item).ok_or_else(invalid)?;
+                elements
+                    .last_mut()
+                    .ok_or_else(invalid)?
+                    .properties
+                    .push(PlyProperty::List(name.to_string(), count, item));
+            }
+            ["property", scalar, name] => {
+                let scalar = PlyScalar::from_name(scalar).ok_or_else(invalid)?;
+                elements
+                    .last_mut()
+                    .ok_or_else(invalid)?
+                    .properties
+                    .push(PlyProperty::Scalar(name.to_string(), scalar));
+            }
+            _ => return Err(invalid()),
+        }
+    }
+
+    Ok(PlyHeader {
+        format: format
+            .ok_or_else(|| PlyError::InvalidHeaderData("the format line is missing".to_string()))?,
+        elements,
+        comments,
+        data_offset: offset,
+    })
+}
+
+/*------------------*\
+|      Reading       |
+\*------------------*/
+
+enum ValueReader<'a> {
+    Ascii(std::str::SplitWhitespace<'a>),
+    Binary { bytes: &'a [u8], big_endian: bool },
+}
+
+impl ValueReader<'_> {
+    fn read(&mut self, scalar: PlyScalar) -> Result<f64, PlyError> {
+        let end = || PlyError::InvalidElementData("unexpected end of data".to_string());
+        match self {
+            ValueReader::Ascii(tokens) => {
+                let token = tokens.next().ok_or_else(end)?;
+                token
+                    .parse()
+                    .map_err(|_| PlyError::InvalidElementData(token.to_string()))
+            }
+            ValueReader::Binary { bytes, big_endian } => {
+                if bytes.len() < scalar.size() {
+                    return Err(end());
+                }
+                let (value, rest) = bytes.split_at(scalar.size());
+                *bytes = rest;
+                Ok(decode(scalar, value, *big_endian))
+            }
+        }
+    }
+
+    ///the number of tokens or bytes left
+    fn remaining(&self) -> usize {
+        match self {
+            ValueReader::Ascii(tokens) => tokens.clone().count(),
+            ValueReader::Binary { bytes, .. } => bytes.len(),
+        }
+    }
+
+    ///the tokens or bytes of the smallest value of `property`, a list is at least its length
+    fn min_size(&self, property: &PlyProperty) -> usize {
+        match (self, property) {
+            (ValueReader::Ascii(_), _) => 1,
+            (
+                ValueReader::Binary { .. },
+                PlyProperty::Scalar(_, scalar) | PlyProperty::List(_, scalar, _),
+            ) => scalar.size(),
+        }
+    }
+
+    ///the counts of the file are checked before reading the rows, an element without
+    ///property would be read forever from no data
+    fn check_element(&self, element: &PlyElement) -> Result<(), PlyError> {
+        if element.count > 0 && element.properties.is_empty() {
+            return Err(PlyError::InvalidElementData(format!(
+                "the element {} has no property",
+                element.name
+            )));
+        }
+
+        let row_size: usize = element
+            .properties
+            .iter()
+            .map(|property| self.min_size(property))
+            .sum();
+        match element.count.checked_mul(row_size) {
+            Some(size) if size <= self.remaining() => Ok(()),
+            _ => Err(PlyError::InvalidElementData(format!(
+                "the {} {} elements are longer than the data",
+                element.count, element.name
+            ))),
+        }
+    }
+}
+
+fn le_bytes<const N: usize>(bytes: &[u8], big_endian: bool) -> [u8; N] {
+    let mut result: [u8; N] = bytes.try_into().expect("the size of the scalar");
+    if big_endian {
+        result.reverse();
+    }
+    result
+}
+
+fn decode(scalar: PlyScalar, bytes: &[u8], big_endian: bool) -> f64 {
+    match scalar {
+        PlyScalar::Int8 => bytes[0] as i8 as f64,
+        PlyScalar::UInt8 => bytes[0] as f64,
+        PlyScalar::Int16 => i16::from_le_bytes(le_bytes(bytes, big_endian)) as f64,
+        PlyScalar::UInt16 => u16::from_le_bytes(le_bytes(bytes, big_endian)) as f64,
+        PlyScalar::Int32 => i32::from_le_bytes(le_bytes(bytes, big_endian)) as f64,
+        PlyScalar::UInt32 => u32::from_le_bytes(le_bytes(bytes, big_endian)) as f64,
+        PlyScalar::Float32 => f32::from_le_bytes(le_bytes(bytes, big_endian)) as f64,
+        PlyScalar::Float64 => f64::from_le_bytes(le_bytes(bytes, big_endian)),
+    }
+}
+
+fn encode(scalar: PlyScalar, value: f64, big_endian: bool) -> Vec<u8> {
+    let mut bytes = match scalar {
+        PlyScalar::Int8 => (value as i8).to_le_bytes().to_vec(),
+        PlyScalar::UInt8 => (value as u8).to_le_bytes().to_vec(),
+        PlyScalar::Int16 => (value as i16).to_le_bytes().to_vec(),
+        PlyScalar::UInt16 => (value as u16).to_le_bytes().to_vec(),
+        PlyScalar::Int32 => (value as i32).to_le_bytes().to_vec(),
+        PlyScalar::UInt32 => (value as u32).to_le_bytes().to_vec(),
+        PlyScalar::Float32 => (value as f32).to_le_bytes().to_vec(),
+        PlyScalar::Float64 => value.to_le_bytes().to_vec(),
+    };
+    if big_endian {
+        bytes.reverse();
+    }
+    bytes
+}
+
+///where a vertex property goes
+enum VertexSlot {
+    Position(usize),
+    Normal(usize),
+    Texture(usize),
+    Attribute(usize),
+    Ignored,
+}
+
+impl PlyModel {
+    pub fn read_from_ply<P: AsRef<Path>>(path: P) -> Result<Self, PlyError> {
+        let bytes = std::fs::read(path).map_err(PlyError::IOError)?;
+        Self::from_bytes(&bytes)
+    }
+
+    ///the elements other than `vertex` and `face` are skipped, like the face properties other
+    ///than `vertex_indices`
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, PlyError> {
+        let header = parse_header(bytes)?;
+        let data = &bytes[header.data_offset..];
+        let mut reader = match header.format {
+            PlyFormat::Ascii => ValueReader::Ascii(
+                std::str::from_utf8(data)
+                    .map_err(|e| PlyError::InvalidElementData(e.to_string()))?
+                    .split_whitespace(),
+            ),
+            PlyFormat::BinaryLittleEndian => ValueReader::Binary {
+                bytes: data,
+                big_endian: false,
+            },
+            PlyFormat::BinaryBigEndian => ValueReader::Binary {
+                bytes: data,
+                big_endian: true,
+            },
+        };
+
+        let mut model = PlyModel {
+            comments: header.comments.clone(),
+            ..Default::default()
+        };
+
+        for element in &header.elements {
+            reader.check_element(element)?;
+            match element.name.as_str() {
+                "vertex" => read_vertices(&mut model, element, &mut reader)?,
+                "face" => read_faces(&mut model, element, &mut reader)?,
+                _ => {
+                    for _ in 0..element.count {
+                        for property in &element.properties {
+                            read_property(property, &mut reader)?;
+                        }
+                    }
+                }
+            }
+        }
+
+        let vertex_count = model.vertices.len() as u32;
+        if let Some(index) = model.faces.iter().flatten().find(|i| **i >= vertex_count) {
+            return Err(PlyError::InvalidElementData(format!(
+                "the vertex index {} is out of bounds",
+                index
+            )));
+        }
+        Ok(model)
+    }
+}
+
+fn read_property(property: &PlyProperty, reader: &mut ValueReader) -> Result<Vec<f64>, PlyError> {
+    match property {
+        PlyProperty::Scalar(_, scalar) => Ok(vec![reader.read(*scalar)?]),
+        //the counts of the file are not trusted for the allocations, the data can be shorter
+        PlyProperty::List(_, count, item) => {
+            let count = reader.read(*count)?;
+            let mut values = vec![];
+            for _ in 0..count as usize {
+                values.push(reader.read(*item)?);
+            }
+            Ok(values)
+        }
+    }
+}
+
+fn read_vertices(
+    model: &mut PlyModel,
+    element: &PlyElement,
+    reader: &mut ValueReader,
+) -> Result<(), PlyError> {
+    let slots: Vec<_> = element
+        .properties
+        .iter()
+        .map(|property| match (property, property.name()) {
+            (PlyProperty::List(..), _) => VertexSlot::Ignored,
+            (_, "x") => VertexSlot::Position(0),
+            (_, "y") => VertexSlot::Position(1),
+            (_, "z") => VertexSlot::Position(2),
+            (_, "nx") => VertexSlot::Normal(0),
+            (_, "ny") => VertexSlot::Normal(1),
+            (_, "nz") => VertexSlot::Normal(2),
+            (_, "u" | "s" | "texture_u") => VertexSlot::Texture(0),
+            (_, "v" | "t" | "texture_v") => VertexSlot::Texture(1),
+            (PlyProperty::Scalar(name, scalar), _) => {
+                model.attributes.push(PlyAttribute {
+                    name: name.clone(),
+                    scalar_type: *scalar,
+                    values: vec![],
+                });
+                VertexSlot::Attribute(model.attributes.len() - 1)
+            }
+        })
+        .collect();
+
+    model.has_normals = slots.iter().any(|s| matches!(s, VertexSlot::Normal(_)));
+    model.has_tex_coords = slots.iter().any(|s| matches!(s, VertexSlot::Texture(_)));
+
+    for _ in 0..element.count {
+        let mut vertex = Vertex::from([0f32; 3]);
+        for (property, slot) in element.properties.iter().zip(&slots) {
+            let values = read_property(property, reader)?;
+            let value = values.first().copied().unwrap_or(0.);
+            match slot {
+                VertexSlot::Position(i) => vertex.position[*i] = value as f32,
+                VertexSlot::Normal(i) => vertex.normal[*i] = value as f32,
+                VertexSlot::Texture(i) => vertex.texture[*i] = value as f32,
+                VertexSlot::Attribute(i) => model.attributes[*i].values.push(value),
+                VertexSlot::Ignored => {}
+            }
+        }
+        model.vertices.push(vertex);
+    }
+    Ok(())
+}
+
+fn read_faces(
+    model: &mut PlyModel,
+    element: &PlyElement,
+    reader: &mut ValueReader,
+) -> Result<(), PlyError> {
+    let indices = element.properties.iter().position(|property| {
+        matches!(property, PlyProperty::List(name, _, _)
+            if name == "vertex_indices" || name == "vertex_index")
+    });
+
+    for _ in 0..element.count {
+        for (i, property) in element.properties.iter().enumerate() {
+            let values = read_property(property, reader)?;
+            if Some(i) == indices {
+                let face = values
+                    .iter()
+                    .map(|index| match *index >= 0. {
+                        true => Ok(*index as u32),
+                        false => Err(PlyError::InvalidElementData(format!(
+                            "negative vertex index {}",
+                            index
+                        ))),
+                    })
+                    .collect::<Result<_, _>>()?;
+                model.faces.push(face);
+            }
+        }
+    }
+    Ok(())
+}
+
+/*------------------*\
+|      Writing       |
+\*------------------*/
+
+impl PlyModel {
+    pub fn write_to_ply<P: AsRef<Path>>(&self, path: P, format: PlyFormat) -> Result<(), PlyError> {
+        let mut writer = BufWriter::new(File::create(path).map_err(PlyError::IOError)?);
+        self.write(&mut writer, format)?;
+        writer.flush().map_err(PlyError::IOError)
+    }
+
+    ///the faces are only written if there are some
+    pub fn write<W: Write>(&self, writer: &mut W, format: PlyFormat) -> Result<(), PlyError> {
+        self.write_data(writer, format).map_err(PlyError::IOError)
+    }
+
+    ///the properties of the vertices, with their values
+    fn vertex_properties(&self, vertex: &Vertex) -> Vec<(PlyScalar, f64)> {
+        let mut values: Vec<_> = vertex.position[..3]
+            .iter()
+            .map(|v| (PlyScalar::Float32, *v as f64))
+            .collect();
+        if self.has_normals {
+            values.extend(
+                vertex
+                    .normal
+                    .iter()
+                    .map(|v| (PlyScalar::Float32, *v as f64)),
+            );
+        }
+        if self.has_tex_coords {
+            values.extend(
+                vertex.texture[..2]
+                    .iter()
+                    .map(|v| (PlyScalar::Float32, *v as f64)),
+            );
+        }
+        values
+    }
+
+    fn write_data<W: Write>(&self, writer: &mut W, format: PlyFormat) -> std::io::Result<()> {
+        writeln!(writer, "ply")?;
+        writeln!(writer, "format {} 1.0", format.name())?;
+        for comment in &self.comments {
+            writeln!(writer, "comment {}", comment)?;
+        }
+
+        writeln!(writer, "element vertex {}", self.vertices.len())?;
+        let mut names = vec!["x", "y", "z"];
+        if self.has_normals {
+            names.extend(["nx", "ny", "nz"]);
+        }
+        if self.has_tex_coords {
+            names.extend(["u", "v"]);
+        }
+        for name in names {
+            writeln!(writer, "property float {}", name)?;
+        }
+        for attribute in &self.attributes {
+            writeln!(
+                writer,
+                "property {} {}",
+                attribute.scalar_type.name(),
+                attribute.name
+            )?;
+        }
+
+        let count_type = match self.faces.iter().any(|face| face.len() > u8::MAX as usize) {
+            true => PlyScalar::UInt32,
+            false => PlyScalar::UInt8,
+        };
+        if !self.faces.is_empty() {
+            writeln!(writer, "element face {}", self.faces.len())?;
+            writeln!(
+                writer,
+                "property list {} int vertex_indices",
+                count_type.name()
+            )?;
+        }
+        writeln!(writer, "end_header")?;
+
+        let rows = self.vertices.iter().enumerate().map(|(i, vertex)| {
+            let mut row = self.vertex_properties(vertex);
+            row.extend(
+                self.attributes
+                    .iter()
+                    .map(|attribute| (attribute.scalar_type, attribute.values[i])),
+            );
+            row
+        });
+        let faces = self.faces.iter().map(|face| {
+            let mut row = vec![(count_type, face.len() as f64)];
+            row.extend(face.iter().map(|index| (PlyScalar::Int32, *index as f64)));
+            row
+        });
+
+        for row in rows.chain(faces) {
+            match format {
+                PlyFormat::Ascii => {
+                    let values: Vec<_> = row
+                        .iter()
+                        .map(|(scalar, value)| match scalar {
+                            PlyScalar::Float32 => (*value as f32).to_string(),
+                            PlyScalar::Float64 => value.to_string(),
+                            _ => (*value as i64).to_string(),
+                        })
+                        .collect();
+                    writeln!(writer, "{}", values.join(" "))?;
+                }
+                PlyFormat::BinaryLittleEndian | PlyFormat::BinaryBigEndian => {
+                    let big_endian = format == PlyFormat::BinaryBigEndian;
+                    for (scalar, value) in row {
+                        writer.write_all(&encode(scalar, value, big_endian))?;
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,187 @@
+use crate::mesh::vertex::Vertex;
+
+use super::PlyError;
+
+/*------------------*\
+|        PLY         |
+\*------------------*/
+
+///a point cloud or a polygon mesh. The standard vertex properties (`x`, `y`, `z`, `nx`, `ny`,
+///`nz`, `u`, `v`) are read into the vertices, the other vertex properties are kept as
+///attribute channels
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct PlyModel {
+    pub(crate) vertices: Vec<Vertex>,
+    ///true if the file has the `nx`, `ny` and `nz` properties
+    pub(crate) has_normals: bool,
+    ///true if the file has the `u` and `v` (or `s` and `t`) properties
+    pub(crate) has_tex_coords: bool,
+    pub(crate) attributes: Vec<PlyAttribute>,
+    ///the indices of the vertices of each face, the faces can have any number of vertices
+    pub(crate) faces: Vec<Vec<u32>>,
+    pub comments: Vec<String>,
+}
+
+///a vertex property that doesn't fit in a `Vertex`, like a color or a confidence
+#[derive(Debug, Clone, PartialEq)]
+pub struct PlyAttribute {
+    pub name: String,
+    ///the type of the property in the file, used to write it back
+    pub scalar_type: PlyScalar,
+    ///one value per vertex
+    pub values: Vec<f64>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PlyFormat {
+    Ascii,
+    BinaryLittleEndian,
+    BinaryBigEndian,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PlyScalar {
+    Int8,
+    UInt8,
+    Int16,
+    UInt16,
+    Int32,
+    UInt32,
+    Float32,
+    Float64,
+}
+
+impl PlyModel {
+    pub fn from_points(vertices: Vec<Vertex>) -> Self {
+        Self {
+            vertices,
+            ..Default::default()
+        }
+    }
+
+    pub fn from_faces(vertices: Vec<Vertex>, faces: Vec<Vec<u32>>) -> Self {
+        Self {
+            vertices,
+            faces,
+            ..Default::default()
+        }
+    }
+
+    ///the normals and the texture coordinates of the vertices are written
+    pub fn with_normals_and_tex_coords(mut self, normals: bool, tex_coords: bool) -> Self {
+        self.has_normals = normals;
+        self.has_tex_coords = tex_coords;
+        self
+    }
+
+    ///replaces the attribute with the same name, there must be one value per vertex
+    pub fn with_attribute(mut self, attribute: PlyAttribute) -> Result<Self, PlyError> {
+        if attribute.values.len() != self.vertices.len() {
+            return Err(PlyError::InvalidElementData(format!(
+                "{} values for the {} vertices in the attribute {}",
+                attribute.values.len(),
+                self.vertices.len(),
+                attribute.name
+            )));
+        }
+        self.attributes.retain(|a| a.name != attribute.name);
+        self.attributes.push(attribute);
+        Ok(self)
+    }
+
+    pub fn vertices(&self) -> &[Vertex] {
+        &self.vertices
+    }
+
+    pub fn has_normals(&self) -> bool {
+        self.has_normals
+    }
+
+    pub fn has_tex_coords(&self) -> bool {
+        self.has_tex_coords
+    }
+
+    pub fn attributes(&self) -> &[PlyAttribute] {
+        &self.attributes
+    }
+
+    pub fn attribute(&self, name: &str) -> Option<&PlyAttribute> {
+        self.attributes.iter().find(|a| a.name == name)
+    }
+
+    pub fn faces(&self) -> &[Vec<u32>] {
+        &self.faces
+    }
+
+    pub fn is_point_cloud(&self) -> bool {
+        self.faces.is_empty()
+    }
+
+    ///the faces split in triangles around their first vertex
+    pub fn triangulated_indices(&self) -> Vec<u32> {
+        self.faces
+            .iter()
+            .flat_map(|face| {
+                (1..face.len().saturating_sub(1)).flat_map(|i| [face[0], face[i], face[i + 1]])
+            })
+            .collect()
+    }
+
+    ///the vertices and the triangulated faces, like `Mesh::to_buffers_data`
+    pub fn to_buffers_data(&self) -> (Vec<Vertex>, Vec<u32>) {
+        (self.vertices.clone(), self.triangulated_indices())
+    }
+}
+
+impl PlyScalar {
+    pub fn from_name(name: &str) -> Option<Self> {
+        Some(match name {
+            "char" | "int8" => PlyScalar::Int8,
+            "uchar" | "uint8" => PlyScalar::UInt8,
+            "short" | "int16" => PlyScalar::Int16,
+            "ushort" | "uint16" => PlyScalar::UInt16,
+            "int" | "int32" => PlyScalar::Int32,
+            "uint" | "uint32" => PlyScalar::UInt32,
+            "float" | "float32" => PlyScalar::Float32,
+            "double" | "float64" => PlyScalar::Float64,
+            _ => return None,
+        })
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            PlyScalar::Int8 => "char",
+            PlyScalar::UInt8 => "uchar",
+            PlyScalar::Int16 => "short",
+            PlyScalar::UInt16 => "ushort",
+            PlyScalar::Int32 => "int",
+            PlyScalar::UInt32 => "uint",
+            PlyScalar::Float32 => "float",
+            PlyScalar::Float64 => "double",
+        }
+    }
+
+    ///in bytes
+    pub fn size(&self) -> usize {
+        match self {
+            PlyScalar::Int8 | PlyScalar::UInt8 => 1,
+            PlyScalar::Int16 | PlyScalar::UInt16 => 2,
+            PlyScalar::Int32 | PlyScalar::UInt32 | PlyScalar::Float32 => 4,
+            PlyScalar::Float64 => 8,
+        }
+    }
+
+    pub fn is_integer(&self) -> bool {
+        !matches!(self, PlyScalar::Float32 | PlyScalar::Float64)
+    }
+}
+
+impl PlyFormat {
+    pub fn name(&self) -> &'static str {
+        match self {
+            PlyFormat::Ascii => "ascii",
+            PlyFormat::BinaryLittleEndian => "binary_little_endian",
+            PlyFormat::BinaryBigEndian => "binary_big_endian",
+        }
+    }
+}
@@ -0,0 +1,297 @@
+use std::path::Path;
+
+use crate::{
+    mesh::vertex::Vertex,
+    utils::{matrix_util::IDENTITY, types_util::Mat4Arr},
+};
+
+use super::{
+    stl_parser::{has_binary_size, starts_with_solid},
+    wavefront_parser::parse_obj,
+    FormatError, GltfScene, PlyAttribute, PlyModel, StlModel,
+};
+
+/*------------------*\
+|       Scene        |
+\*------------------*/
+
+///the meshes of a file, whatever its format
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct SceneData {
+    pub meshes: Vec<MeshData>,
+}
+
+///a list of triangles, like `Mesh::to_buffers_data`
+#[derive(Debug, Clone, PartialEq)]
+pub struct MeshData {
+    pub name: Option<String>,
+    pub vertices: Vec<Vertex>,
+    pub indices: Vec<u32>,
+    pub material: Option<String>,
+    ///where the mesh is in the scene, column major
+    pub transform: Mat4Arr,
+    ///the vertex channels that don't fit in a `Vertex`, like the colors of a PLY file
+    pub attributes: Vec<PlyAttribute>,
+}
+
+impl MeshData {
+    pub fn new(name: Option<String>, (vertices, indices): (Vec<Vertex>, Vec<u32>)) -> Self {
+        Self {
+            name,
+            vertices,
+            indices,
+            material: None,
+            transform: IDENTITY,
+            attributes: vec![],
+        }
+    }
+}
+
+impl From<GltfScene> for SceneData {
+    ///one mesh per primitive of the nodes, with the world matrix of the node
+    fn from(scene: GltfScene) -> Self {
+        let meshes = scene
+            .nodes()
+            .iter()
+            .enumerate()
+            .filter_map(|(node, n)| Some((node, &scene.meshes()[n.mesh?])))
+            .flat_map(|(node, mesh)| {
+                let scene = &scene;
+                mesh.primitives.iter().map(move |primitive| MeshData {
+                    name: mesh.name.clone(),
+                    vertices: primitive.vertices.clone(),
+                    indices: primitive.indices.clone(),
+                    material: primitive
+                        .material
+                        .and_then(|m| scene.materials()[m].name.clone()),
+                    transform: scene.world_matrix(node),
+                    attributes: vec![],
+                })
+            })
+            .collect();
+        Self { meshes }
+    }
+}
+
+impl From<StlModel> for SceneData {
+    fn from(model: StlModel) -> Self {
+        let name = model.name.clone();
+        Self {
+            meshes: vec![MeshData::new(name, model.to_buffers_data())],
+        }
+    }
+}
+
+impl From<PlyModel> for SceneData {
+    ///a point cloud gives a mesh without indices
+    fn from(model: PlyModel) -> Self {
+        let mut mesh = MeshData::new(None, model.to_buffers_data());
+        mesh.attributes = model.attributes;
+        Self { meshes: vec![mesh] }
+    }
+}
+
+/*------------------*\
+|      Loaders       |
+\*------------------*/
+
+///a file format, it can be added to a `LoaderRegistry`
+pub trait MeshLoader {
+    fn name(&self) -> &str;
+
+    ///in lowercase, without the dot
+    fn extensions(&self) -> &[&str];
+
+    ///true if the start of the file is the signature of the format
+    fn detect(&self, _bytes: &[u8]) -> bool {
+        false
+    }
+
+    ///the external files, like the buffers of a glTF file, are relative to `base`
+    fn load(&self, bytes: &[u8], base: Option<&Path>) -> Result<SceneData, FormatError>;
+}
+
+///.obj files, they have no signature so they are only found by their extension
+#[derive(Debug, Clone, Copy, Default)]
+pub struct WavefrontLoader;
+
+///.gltf and .glb files
+#[derive(Debug, Clone, Copy, Default)]
+pub struct GltfLoader;
+
+///ascii and binary .stl files
+#[derive(Debug, Clone, Copy, Default)]
+pub struct StlLoader;
+
+///.ply files
+#[derive(Debug, Clone, Copy, Default)]
+pub struct PlyLoader;
+
+impl MeshLoader for WavefrontLoader {
+    fn name(&self) -> &str {
+        "wavefront"
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["obj"]
+    }
+
+    fn load(&self, bytes: &[u8], _base: Option<&Path>) -> Result<SceneData, FormatError> {
+        let data = String::from_utf8_lossy(bytes);
+        let obj = parse_obj(&data).map_err(FormatError::WavefrontError)?;
+        let buffers = obj.to_buffers_data().map_err(FormatError::WavefrontError)?;
+        Ok(SceneData {
+            meshes: vec![MeshData::new(obj.object_name.clone(), buffers)],
+        })
+    }
+}
+
+impl MeshLoader for GltfLoader {
+    fn name(&self) -> &str {
+        "gltf"
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["gltf", "glb"]
+    }
+
+    ///the magic of the .glb files, or a json object with an asset
+    fn detect(&self, bytes: &[u8]) -> bool {
+        let start = String::from_utf8_lossy(&bytes[..bytes.len().min(256)]);
+        bytes.starts_with(b"glTF")
+            || (start.trim_start().starts_with('{') && start.contains("\"asset\""))
+    }
+
+    fn load(&self, bytes: &[u8], base: Option<&Path>) -> Result<SceneData, FormatError> {
+        let scene = GltfScene::from_slice(bytes, base).map_err(FormatError::GltfError)?;
+        Ok(scene.into())
+    }
+}
+
+impl MeshLoader for StlLoader {
+    fn name(&self) -> &str {
+        "stl"
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["stl"]
+    }
+
+    fn detect(&self, bytes: &[u8]) -> bool {
+        has_binary_size(bytes) || starts_with_solid(bytes)
+    }
+
+    fn load(&self, bytes: &[u8], _base: Option<&Path>) -> Result<SceneData, FormatError> {
+        let model = StlModel::from_bytes(bytes).map_err(FormatError::StlError)?;
+        Ok(model.into())
+    }
+}
+
+impl MeshLoader for PlyLoader {
+    fn name(&self) -> &str {
+        "ply"
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["ply"]
+    }
+
+    fn detect(&self, bytes: &[u8]) -> bool {
+        bytes.starts_with(b"ply\n") || bytes.starts_with(b"ply\r\n")
+    }
+
+    fn load(&self, bytes: &[u8], _base: Option<&Path>) -> Result<SceneData, FormatError> {
+        let model = PlyModel::from_bytes(bytes).map_err(FormatError::PlyError)?;
+        Ok(model.into())
+    }
+}
+
+/*------------------*\
+|      Registry      |
+\*------------------*/
+
+///picks the loader of a file by its signature, then by its extension.
+///The last registered loaders are tried first, so they can replace the default ones
+pub struct LoaderRegistry {
+    loaders: Vec<Box<dyn MeshLoader>>,
+}
+
+impl Default for LoaderRegistry {
+    ///with the wavefront, glTF, STL and PLY loaders
+    fn default() -> Self {
+        Self::empty()
+            .with_loader(WavefrontLoader)
+            .with_loader(GltfLoader)
+            .with_loader(StlLoader)
+            .with_loader(PlyLoader)
+    }
+}
+
+impl LoaderRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    ///without any loader
+    pub fn empty() -> Self {
+        Self { loaders: vec![] }
+    }
+
+    pub fn register<L: MeshLoader + 'static>(&mut self, loader: L) {
+        self.loaders.push(Box::new(loader));
+    }
+
+    pub fn with_loader<L: MeshLoader + 'static>(mut self, loader: L) -> Self {
+        self.register(loader);
+        self
+    }
+
+    pub fn loader_names(&self) -> Vec<&str> {
+        self.loaders.iter().map(|l| l.name()).collect()
+    }
+
+    pub fn loader_for_extension(&self, extension: &str) -> Option<&dyn MeshLoader> {
+        let extension = extension.trim_start_matches('.').to_lowercase();
+        self.loaders
+            .iter()
+            .rev()
+            .find(|l| l.extensions().contains(&extension.as_str()))
+            .map(|l| l.as_ref())
+    }
+
+    pub fn loader_for_bytes(&self, bytes: &[u8]) -> Option<&dyn MeshLoader> {
+        self.loaders
+            .iter()
+            .rev()
+            .find(|l| l.detect(bytes))
+            .map(|l| l.as_ref())
+    }
+
+    ///the signature is more reliable than the extension, so it is checked first
+    pub fn loader_for(&self, bytes: &[u8], extension: Option<&str>) -> Option<&dyn MeshLoader> {
+        self.loader_for_bytes(bytes)
+            .or_else(|| extension.and_then(|e| self.loader_for_extension(e)))
+    }
+
+    pub fn load<P: AsRef<Path>>(&self, path: P) -> Result<SceneData, FormatError> {
+        let path = path.as_ref();
+        let bytes = std::fs::read(path).map_err(FormatError::IOError)?;
+        let extension = path.extension().and_then(|e| e.to_str());
+        let loader = self
+            .loader_for(&bytes, extension)
+            .ok_or_else(|| FormatError::UnknownFormat(path.display().to_string()))?;
+        loader.load(&bytes, path.parent())
+    }
+
+    ///the external files can't be found, the extension is only used if no signature is found
+    pub fn load_bytes(
+        &self,
+        bytes: &[u8],
+        extension: Option<&str>,
+    ) -> Result<SceneData, FormatError> {
+        let loader = self.loader_for(bytes, extension).ok_or_else(|| {
+            FormatError::UnknownFormat(extension.unwrap_or("no extension").to_string())
+        })?;
+        loader.load(bytes, None)
+    }
+}
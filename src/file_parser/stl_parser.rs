@@ -0,0 +1,201 @@
+use std::{
+    fs::File,
+    io::{BufWriter, Write},
+    path::Path,
+};
+
+use crate::utils::types_util::Arr3F32;
+
+use super::{StlError, StlFormat, StlModel};
+
+const BINARY_HEADER_SIZE: usize = 80;
+const BINARY_FACET_SIZE: usize = 50;
+
+impl StlModel {
+    pub fn read_from_stl<P: AsRef<Path>>(path: P) -> Result<Self, StlError> {
+        let bytes = std::fs::read(path).map_err(StlError::IOError)?;
+        Self::from_bytes(&bytes)
+    }
+
+    ///the format is detected, see `StlFormat::detect`
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, StlError> {
+        match StlFormat::detect(bytes) {
+            StlFormat::Ascii => parse_ascii(
+                std::str::from_utf8(bytes).map_err(|e| StlError::InvalidLineData(e.to_string()))?,
+            ),
+            StlFormat::Binary => parse_binary(bytes),
+        }
+    }
+
+    pub fn write_to_stl<P: AsRef<Path>>(&self, path: P, format: StlFormat) -> Result<(), StlError> {
+        let mut writer = BufWriter::new(File::create(path).map_err(StlError::IOError)?);
+        self.write(&mut writer, format)?;
+        writer.flush().map_err(StlError::IOError)
+    }
+
+    pub fn write<W: Write>(&self, writer: &mut W, format: StlFormat) -> Result<(), StlError> {
+        match format {
+            StlFormat::Ascii => self.write_ascii(writer),
+            StlFormat::Binary => self.write_binary(writer),
+        }
+        .map_err(StlError::IOError)
+    }
+
+    fn write_ascii<W: Write>(&self, writer: &mut W) -> std::io::Result<()> {
+        let name = self.name.as_deref().unwrap_or("");
+        writeln!(writer, "solid {}", name)?;
+        for (normal, positions) in self.facet_positions() {
+            let [nx, ny, nz] = normal;
+            writeln!(writer, "  facet normal {:e} {:e} {:e}", nx, ny, nz)?;
+            writeln!(writer, "    outer loop")?;
+            for [x, y, z] in positions {
+                writeln!(writer, "      vertex {:e} {:e} {:e}", x, y, z)?;
+            }
+            writeln!(writer, "    endloop")?;
+            writeln!(writer, "  endfacet")?;
+        }
+        writeln!(writer, "endsolid {}", name)
+    }
+
+    fn write_binary<W: Write>(&self, writer: &mut W) -> std::io::Result<()> {
+        let mut header = [0u8; BINARY_HEADER_SIZE];
+        let name = self.name.as_deref().unwrap_or("").as_bytes();
+        let length = name.len().min(BINARY_HEADER_SIZE);
+        header[..length].copy_from_slice(&name[..length]);
+
+        writer.write_all(&header)?;
+        writer.write_all(&(self.triangle_count() as u32).to_le_bytes())?;
+        for (normal, positions) in self.facet_positions() {
+            for value in normal.iter().chain(positions.iter().flatten()) {
+                writer.write_all(&value.to_le_bytes())?;
+            }
+            //the attribute byte count
+            writer.write_all(&[0, 0])?;
+        }
+        Ok(())
+    }
+}
+
+impl StlFormat {
+    ///a file is binary if its size matches the facet count of its header.
+    ///Some binary files start with "solid" too, so the keyword alone isn't enough
+    pub fn detect(bytes: &[u8]) -> Self {
+        match !has_binary_size(bytes) && starts_with_solid(bytes) {
+            true => StlFormat::Ascii,
+            false => StlFormat::Binary,
+        }
+    }
+}
+
+pub(crate) fn has_binary_size(bytes: &[u8]) -> bool {
+    let Some(count) = bytes.get(BINARY_HEADER_SIZE..BINARY_HEADER_SIZE + 4) else {
+        return false;
+    };
+    let count = u32::from_le_bytes(count.try_into().expect("four bytes")) as usize;
+    let size = count
+        .checked_mul(BINARY_FACET_SIZE)
+        .and_then(|size| size.checked_add(BINARY_HEADER_SIZE + 4));
+    size == Some(bytes.len())
+}
+
+pub(crate) fn starts_with_solid(bytes: &[u8]) -> bool {
+    let start = String::from_utf8_lossy(&bytes[..bytes.len().min(BINARY_HEADER_SIZE)]);
+    start.trim_start().starts_with("solid")
+}
+
+fn parse_binary(bytes: &[u8]) -> Result<StlModel, StlError> {
+    let Some(count) = bytes.get(BINARY_HEADER_SIZE..BINARY_HEADER_SIZE + 4) else {
+        return Err(StlError::InvalidBinaryData(format!(
+            "the file is {} bytes long, without facet count",
+            bytes.len()
+        )));
+    };
+    let count = u32::from_le_bytes(count.try_into().expect("four bytes")) as usize;
+
+    let data = &bytes[BINARY_HEADER_SIZE + 4..];
+    if data.len() < count.saturating_mul(BINARY_FACET_SIZE) {
+        return Err(StlError::InvalidBinaryData(format!(
+            "{} facets are announced, but only {} bytes follow",
+            count,
+            data.len()
+        )));
+    }
+
+    let read_vector = |bytes: &[u8]| -> Arr3F32 {
+        core::array::from_fn(|i| {
+            f32::from_le_bytes(bytes[i * 4..i * 4 + 4].try_into().expect("four bytes"))
+        })
+    };
+    let facets = data
+        .chunks_exact(BINARY_FACET_SIZE)
+        .take(count)
+        .map(|facet| {
+            let normal = read_vector(&facet[0..12]);
+            let positions = [1, 2, 3].map(|i| read_vector(&facet[i * 12..i * 12 + 12]));
+            (normal, positions)
+        })
+        .collect();
+
+    let header = String::from_utf8_lossy(&bytes[..BINARY_HEADER_SIZE]);
+    let name = header.trim_end_matches('\0').trim();
+    let name = (!name.is_empty()).then(|| name.to_string());
+
+    Ok(StlModel::from_facets(name, facets))
+}
+
+fn parse_ascii(s: &str) -> Result<StlModel, StlError> {
+    let mut name = None;
+    let mut facets = vec![];
+    let mut facet: Option<(Arr3F32, Vec<Arr3F32>)> = None;
+
+    for line in s.lines() {
+        let line = line.trim();
+        let mut tokens = line.split_whitespace();
+        let Some(keyword) = tokens.next() else {
+            continue;
+        };
+
+        match (keyword, &mut facet) {
+            ("solid", None) => {
+                let solid = line["solid".len()..].trim();
+                name = (!solid.is_empty()).then(|| solid.to_string());
+            }
+            ("facet", None) => {
+                if tokens.next() != Some("normal") {
+                    return Err(StlError::InvalidLineData(line.to_string()));
+                }
+                facet = Some((parse_vector(line, tokens)?, vec![]));
+            }
+            ("vertex", Some((_, positions))) => positions.push(parse_vector(line, tokens)?),
+            ("outer" | "endloop", Some(_)) => {}
+            ("endfacet", Some(_)) => {
+                let (normal, positions) = facet.take().expect("inside a facet");
+                let positions: [Arr3F32; 3] = positions
+                    .try_into()
+                    .map_err(|_| StlError::InvalidFacetData(line.to_string()))?;
+                facets.push((normal, positions));
+            }
+            ("endsolid", None) => break,
+            _ => return Err(StlError::InvalidLineData(line.to_string())),
+        }
+    }
+
+    if facet.is_some() {
+        return Err(StlError::InvalidFacetData("unclosed facet".to_string()));
+    }
+    Ok(StlModel::from_facets(name, facets))
+}
+
+fn parse_vector<'a>(
+    line: &str,
+    mut tokens: impl Iterator<Item = &'a str>,
+) -> Result<Arr3F32, StlError> {
+    let mut vector = [0.; 3];
+    for value in &mut vector {
+        *value = tokens
+            .next()
+            .and_then(|token| token.parse().ok())
+            .ok_or_else(|| StlError::InvalidLineData(line.to_string()))?;
+    }
+    Ok(vector)
+}
@@ -0,0 +1,166 @@
+use std::collections::HashMap;
+
+use crate::{
+    mesh::{polygon::Triangle, vertex::Vertex, Mesh, MeshCreationError},
+    utils::{
+        matrix_util::{cross, normalize, sub},
+        types_util::Arr3F32,
+    },
+};
+
+/*------------------*\
+|        STL         |
+\*------------------*/
+
+///an ascii or binary STL file, the duplicate vertices of the facets are welded
+#[derive(Debug, Clone, PartialEq)]
+pub struct StlModel {
+    ///the name of the `solid`, or the header of a binary file
+    pub name: Option<String>,
+
+    ///the normals are the average of the normals of their facets
+    pub(crate) vertices: Vec<Vertex>,
+    pub(crate) indices: Vec<u32>,
+    ///one per facet, as given in the file
+    pub(crate) facet_normals: Vec<Arr3F32>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StlFormat {
+    Ascii,
+    Binary,
+}
+
+impl StlModel {
+    ///a zero normal is replaced by the normal of the facet, given by its winding
+    pub(crate) fn from_facets(name: Option<String>, facets: Vec<(Arr3F32, [Arr3F32; 3])>) -> Self {
+        let mut vertices: Vec<Vertex> = vec![];
+        let mut indices = Vec::with_capacity(facets.len() * 3);
+        let mut facet_normals = Vec::with_capacity(facets.len());
+        let mut known_vertices = HashMap::new();
+
+        for (normal, positions) in facets {
+            let normal = match normal == [0.; 3] {
+                true => facet_normal(&positions),
+                false => normal,
+            };
+            facet_normals.push(normal);
+
+            for position in positions {
+                //-0. and 0. are the same position
+                let key = position.map(|v| (v + 0.).to_bits());
+                let index = *known_vertices.entry(key).or_insert_with(|| {
+                    vertices.push(Vertex::from(position));
+                    vertices.len() - 1
+                });
+
+                let vertex_normal = &mut vertices[index].normal;
+                for i in 0..3 {
+                    vertex_normal[i] += normal[i];
+                }
+                indices.push(index as u32);
+            }
+        }
+
+        for vertex in &mut vertices {
+            vertex.normal = normalize(vertex.normal);
+        }
+
+        Self {
+            name,
+            vertices,
+            indices,
+            facet_normals,
+        }
+    }
+
+    ///the facet normals are the average of the normals of the vertices of the triangles,
+    ///or are given by their winding
+    pub fn from_triangles(name: Option<String>, triangles: &[Triangle]) -> Self {
+        let facets = triangles
+            .iter()
+            .map(|triangle| {
+                let vertices = triangle.points();
+                let normal: Arr3F32 =
+                    core::array::from_fn(|i| vertices.iter().map(|v| v.normal[i]).sum());
+                let positions = vertices.map(|v| [v.position[0], v.position[1], v.position[2]]);
+                (normalize(normal), positions)
+            })
+            .collect();
+        Self::from_facets(name, facets)
+    }
+
+    ///the transform of `mesh` is not applied
+    pub fn from_mesh(mesh: &Mesh) -> Self {
+        let (vertices, indices) = mesh.to_buffers_data();
+        let triangles: Vec<Triangle> = indices
+            .chunks_exact(3)
+            .map(|triangle| Triangle {
+                vertices: [0, 1, 2].map(|i| vertices[triangle[i] as usize]),
+            })
+            .collect();
+        Self::from_triangles(mesh.name.clone(), &triangles)
+    }
+
+    pub fn vertices(&self) -> &[Vertex] {
+        &self.vertices
+    }
+
+    pub fn indices(&self) -> &[u32] {
+        &self.indices
+    }
+
+    pub fn facet_normals(&self) -> &[Arr3F32] {
+        &self.facet_normals
+    }
+
+    pub fn triangle_count(&self) -> usize {
+        self.facet_normals.len()
+    }
+
+    ///the welded vertices and their indices, like `Mesh::to_buffers_data`
+    pub fn to_buffers_data(&self) -> (Vec<Vertex>, Vec<u32>) {
+        (self.vertices.clone(), self.indices.clone())
+    }
+
+    ///a mesh of the welded vertices, named like the solid
+    pub fn to_mesh(&self) -> Result<Mesh, MeshCreationError> {
+        let mesh = Mesh::from_buffers_data(self.vertices.clone(), &self.indices)?;
+        Ok(match &self.name {
+            Some(name) => mesh.with_name(name),
+            None => mesh,
+        })
+    }
+
+    ///the facets, their vertices have the normal of the facet
+    pub fn triangles(&self) -> Vec<Triangle> {
+        self.indices
+            .chunks_exact(3)
+            .zip(&self.facet_normals)
+            .map(|(triangle, normal)| Triangle {
+                vertices: [0, 1, 2].map(|i| {
+                    let mut vertex = self.vertices[triangle[i] as usize];
+                    vertex.normal = *normal;
+                    vertex
+                }),
+            })
+            .collect()
+    }
+
+    pub(crate) fn facet_positions(&self) -> impl Iterator<Item = (Arr3F32, [Arr3F32; 3])> + '_ {
+        self.indices
+            .chunks_exact(3)
+            .zip(&self.facet_normals)
+            .map(|(triangle, normal)| {
+                let positions = [0, 1, 2].map(|i| {
+                    let [x, y, z, _] = self.vertices[triangle[i] as usize].position;
+                    [x, y, z]
+                });
+                (*normal, positions)
+            })
+    }
+}
+
+fn facet_normal([a, b, c]: &[Arr3F32; 3]) -> Arr3F32 {
+    normalize(cross(sub(*b, *a), sub(*c, *a)))
+}
@@ -404,3 +404,655 @@ mod test_wavefront_parsable {
         assert!(result.is_err());
     }
 }
+
+#[cfg(test)]
+mod test_wavefront_mtl {
+    use crate::file_parser::{WavefrontError, WavefrontMtl};
+
+    const MTL: &str = "# two materials
+newmtl red
+Ka 0.1 0.1 0.1
+Kd 1 0 0
+Ks 0.5
+Ns 64
+illum 1
+
+newmtl glass
+Kd 0.9 0.9 1.0
+Tr 0.75
+map_Kd -bm 1.0 textures/glass.png
+";
+
+    #[test]
+    fn test_parse_materials() {
+        let mtl: WavefrontMtl = MTL.parse().unwrap();
+        assert_eq!(mtl.materials().len(), 2);
+
+        let red = mtl.get("red").unwrap();
+        assert_eq!(red.ambient, [0.1; 3]);
+        assert_eq!(red.diffuse, [1., 0., 0.]);
+        assert_eq!(red.specular, [0.5; 3]);
+        assert_eq!(red.shininess, 64.);
+        assert_eq!(red.illumination, 1);
+
+        let glass = mtl.get("glass").unwrap();
+        assert_eq!(glass.opacity, 0.25);
+        assert_eq!(glass.diffuse_map.as_deref(), Some("textures/glass.png"));
+        assert_eq!(glass.illumination, 2);
+    }
+
+    #[test]
+    fn test_statement_before_newmtl() {
+        let result: Result<WavefrontMtl, _> = "Kd 1 1 1".parse();
+        assert!(matches!(result, Err(WavefrontError::InvalidLineData(_))));
+    }
+
+    #[test]
+    fn test_invalid_value() {
+        let result: Result<WavefrontMtl, _> = "newmtl a\nNs shiny".parse();
+        assert!(matches!(result, Err(WavefrontError::InvalidLineData(_))));
+    }
+}
+
+#[cfg(test)]
+mod test_gltf {
+    use gltf::mesh::Mode;
+
+    use crate::{
+        file_parser::{gltf_parser::triangle_list, GltfAlphaMode, GltfError, GltfScene},
+        object_traits::GetableSceneObject,
+        utils::types_util::{Arr3F32, Mat4Arr},
+    };
+
+    fn assert_close(a: &[f32], b: &[f32]) {
+        assert_eq!(a.len(), b.len());
+        for (a, b) in a.iter().zip(b) {
+            assert!((a - b).abs() < 1e-5, "{:?} != {:?}", a, b);
+        }
+    }
+
+    fn check_scene(scene: &GltfScene) {
+        assert_eq!(scene.roots(), [0]);
+        assert_eq!(scene.nodes().len(), 2);
+        let child = scene.node_by_name("child").unwrap();
+        assert_eq!(scene.nodes()[child].parent, Some(0));
+        assert_eq!(scene.nodes()[0].children, vec![child]);
+        assert_eq!(scene.nodes()[child].mesh, Some(0));
+
+        let mesh = &scene.meshes()[0];
+        assert_eq!(mesh.name.as_deref(), Some("triangle"));
+        let primitive = &mesh.primitives[0];
+        assert_eq!(primitive.indices(), [0, 1, 2]);
+        assert_eq!(primitive.material(), Some(0));
+        assert_eq!(primitive.vertices()[1].position, [1., 0., 0., 1.]);
+        assert_eq!(primitive.vertices()[1].normal, [0., 0., 1.]);
+        //the v coordinate is flipped
+        assert_eq!(primitive.vertices()[0].texture, [0., 1., 0.]);
+        assert_eq!(primitive.vertices()[2].texture, [0., 0., 0.]);
+
+        let material = &scene.materials()[0];
+        assert_eq!(material.name.as_deref(), Some("red"));
+        assert_eq!(material.base_color, [1., 0., 0., 1.]);
+        assert_eq!((material.metallic, material.roughness), (0.5, 0.25));
+        assert_eq!(material.alpha_mode, GltfAlphaMode::Mask(0.3));
+        assert!(material.double_sided);
+        assert_eq!(material.base_color_map, None);
+    }
+
+    #[test]
+    fn test_read_gltf() {
+        check_scene(&GltfScene::read_from_gltf("tests/gltf/triangle.gltf").unwrap());
+    }
+
+    #[test]
+    fn test_read_glb() {
+        check_scene(&GltfScene::read_from_gltf("tests/gltf/triangle.glb").unwrap());
+    }
+
+    #[test]
+    fn test_world_matrix() {
+        let scene = GltfScene::read_from_gltf("tests/gltf/triangle.gltf").unwrap();
+        let child = scene.node_by_name("child").unwrap();
+
+        //scaled by 2, rotated by 90° around z, then translated by the root
+        let matrix = scene.world_matrix(child);
+        assert_close(&matrix[0], &[0., 2., 0., 0.]);
+        assert_close(&matrix[1], &[-2., 0., 0., 0.]);
+        assert_close(&matrix[2], &[0., 0., 2., 0.]);
+        assert_close(&matrix[3], &[1., 2., 3., 1.]);
+    }
+
+    #[test]
+    fn test_node_meshes() {
+        let scene = GltfScene::read_from_gltf("tests/gltf/triangle.gltf").unwrap();
+        let child = scene.node_by_name("child").unwrap();
+        assert!(scene.node_meshes(0).unwrap().is_empty());
+
+        let meshes = scene.node_meshes(child).unwrap();
+        assert_eq!(meshes.len(), 1);
+        assert_eq!(meshes[0].name(), Some("triangle"));
+
+        let (vertices, indices) = meshes[0].to_buffers_data();
+        assert_eq!(indices, [0, 1, 2]);
+        assert_eq!(vertices, scene.meshes()[0].primitives[0].vertices());
+
+        //the transform of the node, without the one of the root
+        let matrix: Mat4Arr = meshes[0].get_model_matrix().into();
+        let local = scene.nodes()[child].local_matrix();
+        for (column, expected) in matrix.iter().zip(&local) {
+            assert_close(column, expected);
+        }
+    }
+
+    #[test]
+    fn test_animation() {
+        let scene = GltfScene::read_from_gltf("tests/gltf/triangle.gltf").unwrap();
+        let animation = scene.animation_by_name("move").unwrap();
+        assert_eq!(animation.duration(), 1.);
+        assert!(animation.clip(1).is_none());
+
+        let track = animation.clip(0).unwrap().translation_track().unwrap();
+        let position: Arr3F32 = track.sample(0.5).unwrap().into();
+        assert_close(&position, &[1., 3., 3.]);
+    }
+
+    #[test]
+    fn test_triangle_list() {
+        let strip = triangle_list(Mode::TriangleStrip, vec![0, 1, 2, 3]).unwrap();
+        assert_eq!(strip, [0, 1, 2, 2, 1, 3]);
+
+        let fan = triangle_list(Mode::TriangleFan, vec![0, 1, 2, 3]).unwrap();
+        assert_eq!(fan, [0, 1, 2, 0, 2, 3]);
+
+        assert!(triangle_list(Mode::Lines, vec![0, 1]).is_none());
+    }
+
+    #[test]
+    fn test_invalid_gltf() {
+        let result = GltfScene::read_from_gltf("non_existent.gltf");
+        assert!(matches!(result, Err(GltfError::IOError(_))));
+
+        let result = GltfScene::from_slice(b"{\"asset\": 2}", None);
+        assert!(matches!(result, Err(GltfError::InvalidGltfData(_))));
+    }
+}
+
+#[cfg(test)]
+mod test_stl {
+    use crate::{
+        file_parser::{StlError, StlFormat, StlModel},
+        mesh::{polygon::Triangle, vertex::Vertex},
+    };
+
+    //a square made of two facets, the second one without normal
+    const SQUARE: &str = "solid square
+  facet normal 0 0 1
+    outer loop
+      vertex 0 0 0
+      vertex 1 0 0
+      vertex 1 1 0
+    endloop
+  endfacet
+  facet normal 0 0 0
+    outer loop
+      vertex 0 0 0
+      vertex 1 1 0
+      vertex 0 1 0
+    endloop
+  endfacet
+endsolid square
+";
+
+    #[test]
+    fn test_read_ascii() {
+        assert_eq!(StlFormat::detect(SQUARE.as_bytes()), StlFormat::Ascii);
+
+        let model = StlModel::from_bytes(SQUARE.as_bytes()).unwrap();
+        assert_eq!(model.name.as_deref(), Some("square"));
+        assert_eq!(model.triangle_count(), 2);
+        //the shared vertices are welded
+        assert_eq!(model.vertices().len(), 4);
+        assert_eq!(model.indices(), [0, 1, 2, 0, 2, 3]);
+        assert_eq!(model.facet_normals(), [[0., 0., 1.], [0., 0., 1.]]);
+        assert_eq!(model.vertices()[0].normal, [0., 0., 1.]);
+
+        let triangles = model.triangles();
+        assert_eq!(triangles[1].points()[2].position, [0., 1., 0., 1.]);
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let model = StlModel::from_bytes(SQUARE.as_bytes()).unwrap();
+
+        for format in [StlFormat::Ascii, StlFormat::Binary] {
+            let mut bytes = vec![];
+            model.write(&mut bytes, format).unwrap();
+            assert_eq!(StlFormat::detect(&bytes), format);
+            assert_eq!(StlModel::from_bytes(&bytes).unwrap(), model);
+        }
+    }
+
+    #[test]
+    fn test_mesh_round_trip() {
+        let model = StlModel::from_bytes(SQUARE.as_bytes()).unwrap();
+
+        let mesh = model.to_mesh().unwrap();
+        assert_eq!(mesh.name(), Some("square"));
+        assert_eq!(mesh.to_buffers_data(), model.to_buffers_data());
+        assert_eq!(StlModel::from_mesh(&mesh), model);
+    }
+
+    #[test]
+    fn test_binary_starting_with_solid() {
+        let mut model = StlModel::from_bytes(SQUARE.as_bytes()).unwrap();
+        model.name = Some("solid square".to_string());
+
+        let mut bytes = vec![];
+        model.write(&mut bytes, StlFormat::Binary).unwrap();
+        assert_eq!(bytes.len(), 84 + 2 * 50);
+        assert_eq!(StlFormat::detect(&bytes), StlFormat::Binary);
+        assert_eq!(StlModel::from_bytes(&bytes).unwrap(), model);
+    }
+
+    #[test]
+    fn test_truncated_binary() {
+        let model = StlModel::from_bytes(SQUARE.as_bytes()).unwrap();
+        let mut bytes = vec![];
+        model.write(&mut bytes, StlFormat::Binary).unwrap();
+
+        let result = StlModel::from_bytes(&bytes[..100]);
+        assert!(matches!(result, Err(StlError::InvalidBinaryData(_))));
+    }
+
+    #[test]
+    fn test_from_triangles() {
+        let vertex = |position: [f32; 3]| {
+            let mut vertex = Vertex::from(position);
+            vertex.normal = [0., 1., 0.];
+            vertex
+        };
+        let triangle: Triangle = [
+            vertex([0., 0., 0.]),
+            vertex([0., 0., 1.]),
+            vertex([1., 0., 0.]),
+        ]
+        .try_into()
+        .unwrap();
+
+        let model = StlModel::from_triangles(None, &[triangle.clone(), triangle]);
+        assert_eq!(model.vertices().len(), 3);
+        assert_eq!(model.facet_normals(), [[0., 1., 0.]; 2]);
+    }
+
+    #[test]
+    fn test_invalid_ascii() {
+        let quad = SQUARE.replacen("vertex 1 1 0", "vertex 1 1 0\n      vertex 2 2 0", 1);
+        let result = StlModel::from_bytes(quad.as_bytes());
+        assert!(matches!(result, Err(StlError::InvalidFacetData(_))));
+
+        let result = StlModel::from_bytes(b"solid a\n  facet normal 0 0 x\n");
+        assert!(matches!(result, Err(StlError::InvalidLineData(_))));
+
+        let result = StlModel::read_from_stl("non_existent.stl");
+        assert!(matches!(result, Err(StlError::IOError(_))));
+    }
+}
+
+#[cfg(test)]
+mod test_ply {
+    use crate::{
+        file_parser::{PlyAttribute, PlyError, PlyFormat, PlyModel, PlyScalar},
+        mesh::vertex::Vertex,
+    };
+
+    //a colored point cloud, without face element
+    const POINTS: &str = "ply
+format ascii 1.0
+comment scanned
+element vertex 2
+property float x
+property float y
+property float z
+property uchar red
+property uchar green
+property uchar blue
+property float confidence
+end_header
+0 0 0 255 0 0 0.5
+1 2 3 0 128 255 1
+";
+
+    //a triangle and a quad sharing an edge, with an element that isn't read
+    const FACES: &str = "ply
+format ascii 1.0
+element vertex 5
+property float x
+property float y
+property float z
+property float nx
+property float ny
+property float nz
+property float s
+property float t
+element face 2
+property uchar flags
+property list uchar int vertex_indices
+element edge 1
+property int vertex1
+property int vertex2
+end_header
+0 0 0 0 0 1 0 0
+1 0 0 0 0 1 1 0
+1 1 0 0 0 1 1 1
+0 1 0 0 0 1 0 1
+2 0 0 0 0 1 0 0
+7 4 0 1 2 3
+0 3 1 4 2
+0 1
+";
+
+    #[test]
+    fn test_read_point_cloud() {
+        let model = PlyModel::from_bytes(POINTS.as_bytes()).unwrap();
+        assert!(model.is_point_cloud());
+        assert!(!model.has_normals());
+        assert_eq!(model.comments, ["scanned"]);
+        assert_eq!(model.vertices()[1].position, [1., 2., 3., 1.]);
+
+        let names: Vec<_> = model.attributes().iter().map(|a| a.name.as_str()).collect();
+        assert_eq!(names, ["red", "green", "blue", "confidence"]);
+        let green = model.attribute("green").unwrap();
+        assert_eq!(green.scalar_type, PlyScalar::UInt8);
+        assert_eq!(green.values, [0., 128.]);
+        assert_eq!(model.attribute("confidence").unwrap().values, [0.5, 1.]);
+        assert!(model.triangulated_indices().is_empty());
+    }
+
+    #[test]
+    fn test_read_faces() {
+        let model = PlyModel::from_bytes(FACES.as_bytes()).unwrap();
+        assert!(model.has_normals());
+        assert!(model.has_tex_coords());
+        assert!(model.attributes().is_empty());
+        assert_eq!(model.vertices()[2].normal, [0., 0., 1.]);
+        assert_eq!(model.vertices()[2].texture[..2], [1., 1.]);
+
+        assert_eq!(model.faces(), [vec![0, 1, 2, 3], vec![1, 4, 2]]);
+        assert_eq!(model.triangulated_indices(), [0, 1, 2, 0, 2, 3, 1, 4, 2]);
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let faces = PlyModel::from_bytes(FACES.as_bytes()).unwrap();
+        let points = PlyModel::from_bytes(POINTS.as_bytes()).unwrap();
+
+        for model in [faces, points] {
+            for format in [
+                PlyFormat::Ascii,
+                PlyFormat::BinaryLittleEndian,
+                PlyFormat::BinaryBigEndian,
+            ] {
+                let mut bytes = vec![];
+                model.write(&mut bytes, format).unwrap();
+                assert_eq!(PlyModel::from_bytes(&bytes).unwrap(), model);
+            }
+        }
+    }
+
+    #[test]
+    fn test_read_big_endian() {
+        let mut bytes = b"ply\r\nformat binary_big_endian 1.0\r\nelement vertex 1\r\n\
+property double x\r\nproperty double y\r\nproperty double z\r\nproperty short height\r\n\
+end_header\r\n"
+            .to_vec();
+        for value in [1f64, -2., 0.25] {
+            bytes.extend(value.to_be_bytes());
+        }
+        bytes.extend((-300i16).to_be_bytes());
+
+        let model = PlyModel::from_bytes(&bytes).unwrap();
+        assert_eq!(model.vertices()[0].position, [1., -2., 0.25, 1.]);
+        assert_eq!(model.attribute("height").unwrap().values, [-300.]);
+
+        bytes.pop();
+        let result = PlyModel::from_bytes(&bytes);
+        assert!(matches!(result, Err(PlyError::InvalidElementData(_))));
+    }
+
+    #[test]
+    fn test_with_attribute() {
+        let model = PlyModel::from_points(vec![Vertex::from([0., 1., 2.])]);
+        let intensity = PlyAttribute {
+            name: "intensity".to_string(),
+            scalar_type: PlyScalar::Float64,
+            values: vec![0.5, 0.25],
+        };
+        assert!(matches!(
+            model.clone().with_attribute(intensity.clone()),
+            Err(PlyError::InvalidElementData(_))
+        ));
+
+        let model = model
+            .with_attribute(PlyAttribute {
+                values: vec![0.5],
+                ..intensity
+            })
+            .unwrap();
+        assert_eq!(model.attribute("intensity").unwrap().values, [0.5]);
+    }
+
+    #[test]
+    fn test_invalid_files() {
+        let result = PlyModel::from_bytes(b"plx\nformat ascii 1.0\nend_header\n");
+        assert!(matches!(result, Err(PlyError::InvalidHeaderData(_))));
+
+        let result = PlyModel::from_bytes(b"ply\nelement vertex 0\nend_header\n");
+        assert!(matches!(result, Err(PlyError::InvalidHeaderData(_))));
+
+        let result = PlyModel::from_bytes(
+            b"ply\nformat ascii 1.0\nelement vertex 1\nproperty half x\nend_header\n0\n",
+        );
+        assert!(matches!(result, Err(PlyError::InvalidHeaderData(_))));
+
+        let out_of_bounds = FACES.replace("0 3 1 4 2", "0 3 1 5 2");
+        let result = PlyModel::from_bytes(out_of_bounds.as_bytes());
+        assert!(matches!(result, Err(PlyError::InvalidElementData(_))));
+
+        let result = PlyModel::read_from_ply("non_existent.ply");
+        assert!(matches!(result, Err(PlyError::IOError(_))));
+    }
+
+    #[test]
+    fn test_oversized_counts() {
+        //the counts are bigger than the data, nothing is allocated from them
+        let vertices = POINTS.replace("element vertex 2", "element vertex 99999999999");
+        let result = PlyModel::from_bytes(vertices.as_bytes());
+        assert!(matches!(result, Err(PlyError::InvalidElementData(_))));
+
+        let faces = FACES
+            .replace("property list uchar int", "property list uint int")
+            .replace("0 3 1 4 2", "0 4000000000 1 4 2");
+        let result = PlyModel::from_bytes(faces.as_bytes());
+        assert!(matches!(result, Err(PlyError::InvalidElementData(_))));
+    }
+
+    #[test]
+    fn test_zero_property_elements() {
+        //a zero-property element consumes no data, its count can't be checked against the data
+        let vertices = POINTS.replace(
+            "element vertex 2\nproperty float x\nproperty float y\nproperty float z\n\
+             property uchar red\nproperty uchar green\nproperty uchar blue\n\
+             property float confidence\n",
+            "element vertex 4000000000\n",
+        );
+        assert!(vertices.contains("element vertex 4000000000\nend_header"));
+        let result = PlyModel::from_bytes(vertices.as_bytes());
+        assert!(matches!(result, Err(PlyError::InvalidElementData(_))));
+
+        let unknown = POINTS.replace("end_header", "element unknown 4000000000\nend_header");
+        let result = PlyModel::from_bytes(unknown.as_bytes());
+        assert!(matches!(result, Err(PlyError::InvalidElementData(_))));
+
+        let empty = POINTS.replace("end_header", "element unknown 0\nend_header");
+        assert!(PlyModel::from_bytes(empty.as_bytes()).is_ok());
+
+        let oversized = POINTS.replace(
+            "end_header",
+            "element unknown 4000000000\nproperty uchar value\nend_header",
+        );
+        let result = PlyModel::from_bytes(oversized.as_bytes());
+        assert!(matches!(result, Err(PlyError::InvalidElementData(_))));
+    }
+}
+
+#[cfg(test)]
+mod test_registry {
+    use std::path::Path;
+
+    use crate::file_parser::{
+        FormatError, LoaderRegistry, MeshData, MeshLoader, PlyAttribute, PlyModel, PlyScalar,
+        SceneData, StlFormat, StlModel,
+    };
+
+    const QUAD: &str = "o quad
+v 0 0 0
+v 1 0 0
+v 1 1 0
+v 0 1 0
+vt 0 0
+vt 1 1
+f -4/1 -3/1 -2/2 -1/2
+";
+
+    struct EmptyLoader;
+
+    impl MeshLoader for EmptyLoader {
+        fn name(&self) -> &str {
+            "empty"
+        }
+
+        fn extensions(&self) -> &[&str] {
+            &["obj", "empty"]
+        }
+
+        fn load(&self, _bytes: &[u8], _base: Option<&Path>) -> Result<SceneData, FormatError> {
+            Ok(SceneData::default())
+        }
+    }
+
+    #[test]
+    fn test_detection() {
+        let registry = LoaderRegistry::new();
+        assert_eq!(registry.loader_names(), ["wavefront", "gltf", "stl", "ply"]);
+
+        let glb = std::fs::read("tests/gltf/triangle.glb").unwrap();
+        let name = |loader: Option<&dyn MeshLoader>| loader.map(|l| l.name().to_string());
+        assert_eq!(
+            name(registry.loader_for(&glb, Some("obj"))).unwrap(),
+            "gltf"
+        );
+        assert_eq!(name(registry.loader_for(b"ply\n", None)).unwrap(), "ply");
+        assert_eq!(
+            name(registry.loader_for(b"solid a\n", None)).unwrap(),
+            "stl"
+        );
+        assert_eq!(
+            name(registry.loader_for(b"v 0 0 0", Some("OBJ"))).unwrap(),
+            "wavefront"
+        );
+        assert_eq!(name(registry.loader_for(b"v 0 0 0", None)), None);
+        assert_eq!(name(registry.loader_for_extension(".glb")).unwrap(), "gltf");
+    }
+
+    #[test]
+    fn test_load_files() {
+        let registry = LoaderRegistry::default();
+
+        let cube = registry.load("tests/obj/valid/cube.obj").unwrap();
+        assert_eq!(cube.meshes.len(), 1);
+        assert_eq!(cube.meshes[0].name.as_deref(), Some("Cube"));
+        assert_eq!(cube.meshes[0].indices.len(), 36);
+
+        for path in ["tests/gltf/triangle.gltf", "tests/gltf/triangle.glb"] {
+            let scene = registry.load(path).unwrap();
+            assert_eq!(scene.meshes.len(), 1);
+            let mesh = &scene.meshes[0];
+            assert_eq!(mesh.name.as_deref(), Some("triangle"));
+            assert_eq!(mesh.material.as_deref(), Some("red"));
+            assert_eq!(mesh.indices, [0, 1, 2]);
+            assert_eq!(mesh.transform[3], [1., 2., 3., 1.]);
+        }
+
+        let result = registry.load("non_existent.obj");
+        assert!(matches!(result, Err(FormatError::IOError(_))));
+    }
+
+    #[test]
+    fn test_load_bytes() {
+        let registry = LoaderRegistry::default();
+
+        let scene = registry.load_bytes(QUAD.as_bytes(), Some("obj")).unwrap();
+        let mesh = &scene.meshes[0];
+        assert_eq!(mesh.name.as_deref(), Some("quad"));
+        assert_eq!(mesh.vertices.len(), 4);
+        assert_eq!(mesh.indices, [0, 1, 2, 0, 2, 3]);
+        assert_eq!(mesh.vertices[2].texture, [1., 1., 0.]);
+
+        let mut stl = vec![];
+        StlModel::from_bytes(b"solid\nendsolid\n")
+            .unwrap()
+            .write(&mut stl, StlFormat::Binary)
+            .unwrap();
+        let scene = registry.load_bytes(&stl, Some("ply")).unwrap();
+        assert!(scene.meshes[0].indices.is_empty());
+
+        let mut ply = vec![];
+        PlyModel::from_points(vec![[0., 1., 2.].into()])
+            .write(&mut ply, crate::file_parser::PlyFormat::Ascii)
+            .unwrap();
+        let scene = registry.load_bytes(&ply, None).unwrap();
+        assert_eq!(scene.meshes[0].vertices[0].position, [0., 1., 2., 1.]);
+
+        //the channels that aren't in the vertices are kept
+        let mut ply = vec![];
+        PlyModel::from_points(vec![[0., 1., 2.].into()])
+            .with_attribute(PlyAttribute {
+                name: "confidence".to_string(),
+                scalar_type: PlyScalar::Float32,
+                values: vec![0.5],
+            })
+            .unwrap()
+            .write(&mut ply, crate::file_parser::PlyFormat::BinaryLittleEndian)
+            .unwrap();
+        let scene = registry.load_bytes(&ply, None).unwrap();
+        let attributes = &scene.meshes[0].attributes;
+        assert_eq!(attributes.len(), 1);
+        assert_eq!(attributes[0].name, "confidence");
+        assert_eq!(attributes[0].scalar_type, PlyScalar::Float32);
+        assert_eq!(attributes[0].values, [0.5]);
+
+        let result = registry.load_bytes(b"f 1 2 9", Some("obj"));
+        assert!(matches!(result, Err(FormatError::WavefrontError(_))));
+
+        let result = registry.load_bytes(b"f 1 2 3", Some("txt"));
+        assert!(matches!(result, Err(FormatError::UnknownFormat(_))));
+    }
+
+    #[test]
+    fn test_register() {
+        let mut registry = LoaderRegistry::empty();
+        let result = registry.load_bytes(QUAD.as_bytes(), Some("obj"));
+        assert!(matches!(result, Err(FormatError::UnknownFormat(_))));
+
+        registry = LoaderRegistry::default().with_loader(EmptyLoader);
+        let scene = registry.load_bytes(QUAD.as_bytes(), Some("obj")).unwrap();
+        assert_eq!(scene, SceneData::default());
+
+        //the signature is checked before the extension
+        let scene = registry
+            .load_bytes(b"solid\nendsolid\n", Some("empty"))
+            .unwrap();
+        let expected = MeshData::new(None, (vec![], vec![]));
+        assert_eq!(scene.meshes, [expected]);
+    }
+}
@@ -0,0 +1,142 @@
+use std::{fs::read_to_string, path::Path, str::FromStr};
+
+use super::{wavefront_parser::parse_array_with_default, WavefrontError};
+
+/*------------------*\
+|   Wavefront MTL    |
+\*------------------*/
+
+///a material library, referenced by the `mtllib` statement of an obj file
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct WavefrontMtl {
+    pub(crate) materials: Vec<WavefrontMaterial>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct WavefrontMaterial {
+    pub name: String,
+
+    ///`Ka`
+    pub ambient: [f32; 3],
+    ///`Kd`
+    pub diffuse: [f32; 3],
+    ///`Ks`
+    pub specular: [f32; 3],
+    ///`Ke`
+    pub emissive: [f32; 3],
+    ///`Ns`
+    pub shininess: f32,
+    ///`d`, or `1 - Tr`
+    pub opacity: f32,
+    ///`illum`, 0 is a constant color, 1 is diffuse only and 2 adds the specular highlights
+    pub illumination: u32,
+
+    ///`map_Kd`, relative to the mtl file
+    pub diffuse_map: Option<String>,
+    ///`map_Ks`
+    pub specular_map: Option<String>,
+    ///`map_Bump` or `bump`
+    pub normal_map: Option<String>,
+}
+
+impl WavefrontMaterial {
+    pub fn new(name: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            ambient: [0.; 3],
+            diffuse: [0.8; 3],
+            specular: [0.; 3],
+            emissive: [0.; 3],
+            shininess: 32.,
+            opacity: 1.,
+            illumination: 2,
+            diffuse_map: None,
+            specular_map: None,
+            normal_map: None,
+        }
+    }
+}
+
+impl WavefrontMtl {
+    pub fn read_from_mtl<P: AsRef<Path>>(path: P) -> Result<Self, WavefrontError> {
+        read_to_string(path)
+            .map_err(WavefrontError::IOError)?
+            .parse()
+    }
+
+    pub fn materials(&self) -> &[WavefrontMaterial] {
+        &self.materials
+    }
+
+    pub fn get(&self, name: &str) -> Option<&WavefrontMaterial> {
+        self.materials.iter().find(|material| material.name == name)
+    }
+}
+
+impl FromStr for WavefrontMtl {
+    type Err = WavefrontError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut mtl = WavefrontMtl::default();
+
+        for line in s.lines() {
+            let line = line.split_once('#').map_or(line, |(line, _)| line).trim();
+            let Some((keyword, data)) = line.split_once(char::is_whitespace) else {
+                if line.is_empty() {
+                    continue;
+                }
+                return Err(WavefrontError::InvalidLineData(line.to_string()));
+            };
+            let data = data.trim();
+
+            if keyword == "newmtl" {
+                mtl.materials.push(WavefrontMaterial::new(data));
+                continue;
+            }
+
+            let material = mtl
+                .materials
+                .last_mut()
+                .ok_or(WavefrontError::InvalidLineData(line.to_string()))?;
+            match keyword {
+                "Ka" => material.ambient = parse_color(line)?,
+                "Kd" => material.diffuse = parse_color(line)?,
+                "Ks" => material.specular = parse_color(line)?,
+                "Ke" => material.emissive = parse_color(line)?,
+                "Ns" => material.shininess = parse_value(line, data)?,
+                "d" => material.opacity = parse_value(line, data)?,
+                "Tr" => material.opacity = 1. - parse_value::<f32>(line, data)?,
+                "illum" => material.illumination = parse_value(line, data)?,
+                "map_Kd" => material.diffuse_map = Some(map_path(data)),
+                "map_Ks" => material.specular_map = Some(map_path(data)),
+                "map_Bump" | "map_bump" | "bump" => material.normal_map = Some(map_path(data)),
+                _ => (),
+            }
+        }
+
+        Ok(mtl)
+    }
+}
+
+fn parse_color(line: &str) -> Result<[f32; 3], WavefrontError> {
+    let [r, g, b]: [f32; 3] = parse_array_with_default(line, f32::NAN);
+    if r.is_nan() {
+        return Err(WavefrontError::InvalidLineData(line.to_string()));
+    }
+    //a single value is used for the three channels
+    Ok(match (g.is_nan(), b.is_nan()) {
+        (true, true) => [r; 3],
+        (false, false) => [r, g, b],
+        _ => return Err(WavefrontError::InvalidLineData(line.to_string())),
+    })
+}
+
+fn parse_value<T: FromStr>(line: &str, data: &str) -> Result<T, WavefrontError> {
+    data.parse()
+        .map_err(|_| WavefrontError::InvalidLineData(line.to_string()))
+}
+
+///the texture options (`-bm 1.0`...) are not supported, only the file name is kept
+fn map_path(data: &str) -> String {
+    data.split_whitespace().last().unwrap_or(data).to_string()
+}
@@ -7,24 +7,27 @@ use super::{
 impl WavefrontParsable for WavefrontObj {
     fn read_from_obj<P: AsRef<Path>>(path: P) -> Result<WavefrontObj, WavefrontError> {
         let data = read_to_string(path).map_err(WavefrontError::IOError)?;
+        parse_obj(&data)
+    }
+}
 
-        let mut obj = WavefrontObj::empty();
+pub(crate) fn parse_obj(data: &str) -> Result<WavefrontObj, WavefrontError> {
+    let mut obj = WavefrontObj::empty();
 
-        let lines = data.lines();
-        let mut current_line = String::new();
+    let lines = data.lines();
+    let mut current_line = String::new();
 
-        for line in lines {
-            current_line.push_str(line.trim_end());
-            if !line.ends_with('\\') {
-                load_line_into_wavefront_obj(&mut obj, &current_line)?;
-                current_line.clear();
-            } else {
-                current_line.pop(); // Remove trailing '\'
-            }
+    for line in lines {
+        current_line.push_str(line.trim_end());
+        if !line.ends_with('\\') {
+            load_line_into_wavefront_obj(&mut obj, &current_line)?;
+            current_line.clear();
+        } else {
+            current_line.pop(); // Remove trailing '\'
         }
-
-        Ok(obj)
     }
+
+    Ok(obj)
 }
 
 fn load_line_into_wavefront_obj(obj: &mut WavefrontObj, line: &str) -> Result<(), WavefrontError> {
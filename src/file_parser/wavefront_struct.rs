@@ -1,5 +1,11 @@
 #![allow(dead_code)]
 
+use std::collections::HashMap;
+
+use crate::mesh::vertex::Vertex;
+
+use super::WavefrontError;
+
 pub type NotImpl = ();
 
 /*------------------*\
@@ -81,6 +87,63 @@ impl WavefrontObj {
             merging_group: (),
         }
     }
+
+    ///the faces split in triangles around their first vertex, like `Mesh::to_buffers_data`.
+    ///The corners with the same position, texture and normal indices share their vertex
+    pub fn to_buffers_data(&self) -> Result<(Vec<Vertex>, Vec<u32>), WavefrontError> {
+        let mut vertices = vec![];
+        let mut indices = vec![];
+        let mut known_vertices = HashMap::new();
+
+        for face in &self.face {
+            let invalid = || WavefrontError::InvalidFaceData(format!("{:?}", face.vertex_indices));
+            let mut corners = Vec::with_capacity(face.vertex_indices.len());
+
+            for (i, index) in face.vertex_indices.iter().enumerate() {
+                let texture = face.texture_vertex_indices.as_ref().map(|t| t[i]);
+                let normal = face.normal_vertex_indices.as_ref().map(|n| n[i]);
+
+                let key = (*index, texture, normal);
+                if let Some(corner) = known_vertices.get(&key) {
+                    corners.push(*corner);
+                    continue;
+                }
+
+                let mut vertex: Vertex =
+                    (*element(&self.geometric_vertices, *index).ok_or_else(invalid)?).into();
+                if let Some(texture) = texture {
+                    vertex.texture =
+                        *element(&self.texture_vertices, texture).ok_or_else(invalid)?;
+                }
+                if let Some(normal) = normal {
+                    vertex.normal = *element(&self.vertex_normals, normal).ok_or_else(invalid)?;
+                }
+
+                vertices.push(vertex);
+                let corner = vertices.len() as u32 - 1;
+                known_vertices.insert(key, corner);
+                corners.push(corner);
+            }
+
+            for i in 1..corners.len().saturating_sub(1) {
+                indices.extend([corners[0], corners[i], corners[i + 1]]);
+            }
+        }
+
+        Ok((vertices, indices))
+    }
+}
+
+///the indices start at 1, the negative ones are relative to the end
+fn element<T>(elements: &[T], index: i32) -> Option<&T> {
+    match index {
+        1.. => elements.get(index as usize - 1),
+        ..=-1 => elements
+            .len()
+            .checked_sub(index.unsigned_abs() as usize)
+            .and_then(|i| elements.get(i)),
+        0 => None,
+    }
 }
 /*
 pub(crate) struct WavefrontMesh{
@@ -2,11 +2,14 @@
 
 //Just a lib to use glim
 
+pub mod animation;
 pub mod app;
+pub mod assets;
 pub mod camera;
 pub mod file_parser;
 pub mod mesh;
 pub mod object_traits;
+pub mod render;
 pub mod utils;
 
 pub use my_rust_matrix_lib;
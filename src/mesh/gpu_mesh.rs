@@ -0,0 +1,116 @@
+use glium::{
+    backend::Facade, index::PrimitiveType, uniforms::Uniforms, vertex::PerInstance, DrawParameters,
+    IndexBuffer, Program, Surface, VertexBuffer,
+};
+
+use super::{vertex::Vertex, Mesh, MeshRenderError};
+
+///the GPU buffers of a `Mesh`, uploaded once and only re-uploaded when the mesh changes.
+///The transform of the mesh is not applied to the vertices, it is given to the shader
+///as the `model` uniform
+pub struct GpuMesh {
+    vertex_buffer: VertexBuffer<Vertex>,
+    index_buffer: IndexBuffer<u32>,
+
+    ///the `Mesh::revision` of the uploaded data
+    revision: u64,
+}
+
+impl std::fmt::Debug for GpuMesh {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("GpuMesh")
+            .field("vertices", &self.vertex_buffer.len())
+            .field("indices", &self.index_buffer.len())
+            .field("revision", &self.revision)
+            .finish()
+    }
+}
+
+impl GpuMesh {
+    pub fn new<F: Facade>(facade: &F, mesh: &Mesh) -> Result<Self, MeshRenderError> {
+        let (vertices, indices) = mesh.to_buffers_data();
+
+        let vertex_buffer = VertexBuffer::new(facade, &vertices)
+            .map_err(MeshRenderError::VertexBufferCreationError)?;
+        let index_buffer = IndexBuffer::new(facade, PrimitiveType::TrianglesList, &indices)
+            .map_err(MeshRenderError::IndiceBufferCreationError)?;
+
+        Ok(Self {
+            vertex_buffer,
+            index_buffer,
+            revision: mesh.revision(),
+        })
+    }
+
+    ///re-upload the mesh if it changed since the last upload, returns true if it did.
+    ///The buffers are written in place when their size does not change
+    pub fn update<F: Facade>(&mut self, facade: &F, mesh: &Mesh) -> Result<bool, MeshRenderError> {
+        if self.revision == mesh.revision() {
+            return Ok(false);
+        }
+
+        let (vertices, indices) = mesh.to_buffers_data();
+        if vertices.len() == self.vertex_buffer.len() {
+            self.vertex_buffer.write(&vertices);
+        } else {
+            self.vertex_buffer = VertexBuffer::new(facade, &vertices)
+                .map_err(MeshRenderError::VertexBufferCreationError)?;
+        }
+
+        if indices.len() == self.index_buffer.len() {
+            self.index_buffer.write(&indices);
+        } else {
+            self.index_buffer = IndexBuffer::new(facade, PrimitiveType::TrianglesList, &indices)
+                .map_err(MeshRenderError::IndiceBufferCreationError)?;
+        }
+
+        self.revision = mesh.revision();
+        Ok(true)
+    }
+
+    pub fn vertex_buffer(&self) -> &VertexBuffer<Vertex> {
+        &self.vertex_buffer
+    }
+
+    pub fn index_buffer(&self) -> &IndexBuffer<u32> {
+        &self.index_buffer
+    }
+
+    pub fn draw<S: Surface, U: Uniforms>(
+        &self,
+        target: &mut S,
+        program: &Program,
+        uniforms: &U,
+        draw_parameters: &DrawParameters,
+    ) -> Result<(), MeshRenderError> {
+        target
+            .draw(
+                &self.vertex_buffer,
+                &self.index_buffer,
+                program,
+                uniforms,
+                draw_parameters,
+            )
+            .map_err(MeshRenderError::DrawError)
+    }
+
+    ///draw the mesh once per element of `instances`, see `InstancedMesh`
+    pub fn draw_instanced<S: Surface, U: Uniforms>(
+        &self,
+        target: &mut S,
+        instances: PerInstance,
+        program: &Program,
+        uniforms: &U,
+        draw_parameters: &DrawParameters,
+    ) -> Result<(), MeshRenderError> {
+        target
+            .draw(
+                (&self.vertex_buffer, instances),
+                &self.index_buffer,
+                program,
+                uniforms,
+                draw_parameters,
+            )
+            .map_err(MeshRenderError::DrawError)
+    }
+}
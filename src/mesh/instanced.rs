@@ -0,0 +1,446 @@
+use std::{cell::RefCell, ops::Range};
+
+use glium::{backend::Facade, implement_vertex, uniforms::Uniforms, VertexBuffer};
+
+use crate::{
+    camera::Frustum,
+    object_traits::{GetableSceneObject, Renderable, Rotation, Scale, SceneObject, Translation},
+    utils::types_util::Arr4F32,
+};
+
+use super::{gpu_mesh::GpuMesh, Mesh, MeshRenderError};
+
+/******************\
+*                 *
+*-----Instance----*
+*                 *
+\******************/
+
+///the per instance attributes, `instance_model` replaces the `model` uniform
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct InstanceData {
+    pub instance_model: [[f32; 4]; 4],
+    pub instance_color: Arr4F32,
+    ///free for the shaders
+    pub instance_data: Arr4F32,
+}
+
+implement_vertex!(InstanceData, instance_model, instance_color, instance_data);
+
+///one copy of an `InstancedMesh`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Instance {
+    position: Translation,
+    scale: Scale,
+    rotation: Rotation,
+
+    pub color: Arr4F32,
+    pub data: Arr4F32,
+}
+
+impl Instance {
+    ///a white instance, not scaled nor rotated
+    pub fn new(position: Translation) -> Self {
+        Self {
+            position,
+            scale: Scale::zero(),
+            rotation: Rotation::zero(),
+            color: [1.; 4],
+            data: [0.; 4],
+        }
+    }
+
+    pub fn with_scale(mut self, scale: Scale) -> Self {
+        self.scale = scale;
+        self
+    }
+
+    pub fn with_rotation(mut self, rotation: Rotation) -> Self {
+        self.rotation = rotation;
+        self
+    }
+
+    pub fn with_color(mut self, color: Arr4F32) -> Self {
+        self.color = color;
+        self
+    }
+
+    pub fn with_data(mut self, data: Arr4F32) -> Self {
+        self.data = data;
+        self
+    }
+
+    pub fn to_instance_data(&self) -> InstanceData {
+        InstanceData {
+            instance_model: self.get_model_matrix().into(),
+            instance_color: self.color,
+            instance_data: self.data,
+        }
+    }
+}
+
+impl SceneObject for Instance {
+    #[inline]
+    fn translate(&mut self, trans: Translation) {
+        self.position += trans;
+    }
+
+    #[inline]
+    fn set_position(&mut self, pos: Translation) {
+        self.position = pos;
+    }
+
+    #[inline]
+    fn scale(&mut self, scale: Scale) {
+        self.scale += scale;
+    }
+
+    #[inline]
+    fn set_scale(&mut self, scale: Scale) {
+        self.scale = scale;
+    }
+
+    #[inline]
+    fn rotate(&mut self, rotation: Rotation) {
+        self.rotation += rotation;
+    }
+
+    #[inline]
+    fn set_rotation(&mut self, rotation: Rotation) {
+        self.rotation = rotation;
+    }
+}
+
+impl GetableSceneObject for Instance {
+    #[inline]
+    fn get_position(&self) -> Translation {
+        self.position
+    }
+
+    #[inline]
+    fn get_scale(&self) -> Scale {
+        self.scale
+    }
+
+    #[inline]
+    fn get_rotation(&self) -> Rotation {
+        self.rotation
+    }
+}
+
+/******************\
+*                 *
+*--InstancedMesh--*
+*                 *
+\******************/
+
+#[derive(Default)]
+pub(crate) struct InstanceBuffers {
+    ///every instance, in order
+    instances: Option<VertexBuffer<InstanceData>>,
+    ///the instances left after the frustum culling
+    visible: Option<VertexBuffer<InstanceData>>,
+    ///the instances changed since the last upload
+    dirty: Option<Range<usize>>,
+    visible_count: usize,
+}
+
+impl InstanceBuffers {
+    fn mark_dirty(&mut self, range: Range<usize>) {
+        self.dirty = Some(match self.dirty.take() {
+            Some(dirty) => dirty.start.min(range.start)..dirty.end.max(range.end),
+            None => range,
+        });
+    }
+
+    ///`len` is the number of instances after the push
+    pub(crate) fn pushed(&mut self, len: usize) {
+        self.mark_dirty(len - 1..len);
+    }
+
+    ///the last instance moved to `index`, `len` is the number of instances after the removal
+    pub(crate) fn swap_removed(&mut self, index: usize, len: usize) {
+        if index < len {
+            self.mark_dirty(index..len);
+        }
+    }
+
+    ///the dirty range inside of the `len` instances, it is cleared
+    pub(crate) fn take_dirty(&mut self, len: usize) -> Option<Range<usize>> {
+        let dirty = self.dirty.take()?;
+        let dirty = dirty.start.min(len)..dirty.end.min(len);
+        (!dirty.is_empty()).then_some(dirty)
+    }
+}
+
+///a mesh uploaded once and drawn many times with a single draw call.
+///The instance buffer is uploaded when rendering, only the changed instances are re-uploaded
+pub struct InstancedMesh {
+    gpu_mesh: GpuMesh,
+    ///the distance from the origin of the mesh to its farthest vertex
+    bounding_radius: f32,
+
+    instances: Vec<Instance>,
+    frustum: Option<Frustum>,
+    buffers: RefCell<InstanceBuffers>,
+}
+
+impl InstancedMesh {
+    ///the transform of `mesh` is ignored, only the transforms of the instances are used
+    pub fn new<F: Facade>(
+        facade: &F,
+        mesh: &Mesh,
+        instances: Vec<Instance>,
+    ) -> Result<Self, MeshRenderError> {
+        Ok(Self {
+            gpu_mesh: GpuMesh::new(facade, mesh)?,
+            bounding_radius: bounding_radius(mesh),
+            instances,
+            frustum: None,
+            buffers: RefCell::default(),
+        })
+    }
+
+    ///re-upload the mesh if it changed
+    pub fn update_mesh<F: Facade>(
+        &mut self,
+        facade: &F,
+        mesh: &Mesh,
+    ) -> Result<(), MeshRenderError> {
+        if self.gpu_mesh.update(facade, mesh)? {
+            self.bounding_radius = bounding_radius(mesh);
+        }
+        Ok(())
+    }
+
+    pub fn gpu_mesh(&self) -> &GpuMesh {
+        &self.gpu_mesh
+    }
+
+    pub fn bounding_radius(&self) -> f32 {
+        self.bounding_radius
+    }
+
+    pub fn instances(&self) -> &[Instance] {
+        &self.instances
+    }
+
+    pub fn len(&self) -> usize {
+        self.instances.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.instances.is_empty()
+    }
+
+    pub fn instance(&self, index: usize) -> Option<&Instance> {
+        self.instances.get(index)
+    }
+
+    ///the instance is re-uploaded on the next render
+    pub fn instance_mut(&mut self, index: usize) -> Option<&mut Instance> {
+        let instance = self.instances.get_mut(index)?;
+        self.buffers.get_mut().mark_dirty(index..index + 1);
+        Some(instance)
+    }
+
+    pub fn set_instance(&mut self, index: usize, instance: Instance) {
+        if let Some(old) = self.instance_mut(index) {
+            *old = instance;
+        }
+    }
+
+    ///update the instances from `start`, only this range is re-uploaded
+    pub fn update_instances<I: IntoIterator<Item = Instance>>(
+        &mut self,
+        start: usize,
+        instances: I,
+    ) {
+        let mut end = start;
+        for (old, instance) in self.instances.iter_mut().skip(start).zip(instances) {
+            *old = instance;
+            end += 1;
+        }
+        if end > start {
+            self.buffers.get_mut().mark_dirty(start..end);
+        }
+    }
+
+    ///the new instance is uploaded on the next render
+    pub fn push(&mut self, instance: Instance) {
+        self.instances.push(instance);
+        self.buffers.get_mut().pushed(self.instances.len());
+    }
+
+    ///the last instance takes the place of the removed one, it is re-uploaded on the next render
+    pub fn swap_remove(&mut self, index: usize) -> Instance {
+        let instance = self.instances.swap_remove(index);
+        self.buffers
+            .get_mut()
+            .swap_removed(index, self.instances.len());
+        instance
+    }
+
+    ///with a frustum, the instances whose bounding sphere is outside of it are not drawn
+    pub fn set_frustum(&mut self, frustum: Option<Frustum>) {
+        self.frustum = frustum;
+    }
+
+    pub fn frustum(&self) -> Option<&Frustum> {
+        self.frustum.as_ref()
+    }
+
+    ///the number of instances drawn by the last render
+    pub fn visible_count(&self) -> usize {
+        self.buffers.borrow().visible_count
+    }
+
+    ///the instance buffer read back from the GPU, `None` before the first render without frustum
+    pub fn uploaded_instances(&self) -> Option<Vec<InstanceData>> {
+        self.buffers.borrow().instances.as_ref()?.read().ok()
+    }
+
+    ///the indices of the instances inside of `frustum`
+    pub fn visible_instances<'a>(
+        &'a self,
+        frustum: &'a Frustum,
+    ) -> impl Iterator<Item = usize> + 'a {
+        self.instances
+            .iter()
+            .enumerate()
+            .filter(|(_, instance)| {
+                let scale: [f32; 3] = instance.scale.into();
+                let scale = scale.iter().fold(0f32, |max, s| max.max(s.abs()));
+                frustum.intersects_sphere(instance.position.into(), self.bounding_radius * scale)
+            })
+            .map(|(i, _)| i)
+    }
+
+    fn upload<F: Facade>(
+        &self,
+        facade: &F,
+        buffers: &mut InstanceBuffers,
+    ) -> Result<(), MeshRenderError> {
+        let dirty = buffers.take_dirty(self.instances.len());
+        match &buffers.instances {
+            Some(buffer) if buffer.len() == self.instances.len() => {
+                if let Some(dirty) = dirty {
+                    let data: Vec<_> = self.instances[dirty.clone()]
+                        .iter()
+                        .map(Instance::to_instance_data)
+                        .collect();
+                    buffer
+                        .slice(dirty)
+                        .expect("the dirty range is inside of the instances")
+                        .write(&data);
+                }
+            }
+            _ => {
+                let data: Vec<_> = self
+                    .instances
+                    .iter()
+                    .map(Instance::to_instance_data)
+                    .collect();
+                buffers.instances = Some(
+                    VertexBuffer::dynamic(facade, &data)
+                        .map_err(MeshRenderError::VertexBufferCreationError)?,
+                );
+            }
+        }
+        Ok(())
+    }
+
+    fn upload_visible<F: Facade>(
+        &self,
+        facade: &F,
+        buffers: &mut InstanceBuffers,
+        frustum: &Frustum,
+    ) -> Result<(), MeshRenderError> {
+        let data: Vec<_> = self
+            .visible_instances(frustum)
+            .map(|i| self.instances[i].to_instance_data())
+            .collect();
+        buffers.visible_count = data.len();
+        if data.is_empty() {
+            return Ok(());
+        }
+
+        match &buffers.visible {
+            Some(buffer) if buffer.len() >= data.len() => {
+                buffer
+                    .slice(0..data.len())
+                    .expect("the visible instances fit in the buffer")
+                    .write(&data);
+            }
+            _ => {
+                let mut capacity = data.clone();
+                capacity.resize(self.instances.len(), data[0]);
+                let buffer = VertexBuffer::dynamic(facade, &capacity)
+                    .map_err(MeshRenderError::VertexBufferCreationError)?;
+                buffers.visible = Some(buffer);
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Renderable for InstancedMesh {
+    type RenderError = MeshRenderError;
+
+    ///one instanced draw call, the shader has to read the `InstanceData` attributes
+    fn render<F: Facade, S: glium::Surface, U: Uniforms>(
+        &self,
+        facade: &F,
+        program: &glium::Program,
+        target: &mut S,
+        uniforms: &U,
+        draw_parameters: &glium::DrawParameters,
+    ) -> Result<(), Self::RenderError> {
+        let mut buffers = self.buffers.borrow_mut();
+        if self.instances.is_empty() {
+            buffers.visible_count = 0;
+            return Ok(());
+        }
+
+        let buffer = match &self.frustum {
+            Some(frustum) => {
+                self.upload_visible(facade, &mut buffers, frustum)?;
+                if buffers.visible_count == 0 {
+                    return Ok(());
+                }
+                buffers
+                    .visible
+                    .as_ref()
+                    .and_then(|buffer| buffer.slice(0..buffers.visible_count))
+                    .expect("the visible instances have just been uploaded")
+            }
+            None => {
+                self.upload(facade, &mut buffers)?;
+                buffers.visible_count = self.instances.len();
+                buffers
+                    .instances
+                    .as_ref()
+                    .and_then(|buffer| buffer.slice(..))
+                    .expect("the instances have just been uploaded")
+            }
+        };
+
+        let instances = buffer
+            .per_instance()
+            .map_err(|_| MeshRenderError::InstancingNotSupported)?;
+        self.gpu_mesh
+            .draw_instanced(target, instances, program, uniforms, draw_parameters)
+    }
+}
+
+fn bounding_radius(mesh: &Mesh) -> f32 {
+    let (vertices, _) = mesh.to_buffers_data();
+    vertices
+        .iter()
+        .map(|vertex| {
+            let [x, y, z, w] = vertex.position;
+            let w = if w == 0. { 1. } else { w };
+            ((x * x + y * y + z * z).sqrt() / w).abs()
+        })
+        .fold(0., f32::max)
+}
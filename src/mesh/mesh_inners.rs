@@ -1,14 +1,18 @@
-use std::{cell::RefCell, rc::Rc};
+use std::{cell::RefCell, rc::{Rc, Weak}};
 
 
 use super::{vertex::Vertex, Mesh};
 
 #[derive(Debug)]
 pub(crate) struct InnerMesh{
-    pub(crate) mesh : Rc<RefCell<Mesh>>,
+    ///not set for the meshes that aren't shared in a `RefCell`
+    pub(crate) mesh : Option<Weak<RefCell<Mesh>>>,
 
-    pub(crate) vertex_list: Vec<MeshVertex>,
-    pub(crate) triangle_list: Vec<TriangleMesh>
+    pub(crate) vertex_list: Vec<MeshVertexRef>,
+    pub(crate) triangle_list: Vec<TriangleMeshRef>,
+
+    ///incremented each time the geometry changes, shared by every `Mesh` of the geometry
+    pub(crate) revision: u64,
 }
 
 
@@ -74,9 +78,10 @@ impl MeshHalfEdge {
             result.edge = Some(EdgeMesh::new(mesh.as_deref(), result.origin.clone(), target));
         }
 
+        //the sibling is linked through `result_ref`, so `result` must be released before
+        drop(result);
         m_vert_ref_link_sibling(origin, result_ref.clone());
 
-        drop(result);
         Ok(result_ref)
     }
 
@@ -198,6 +203,10 @@ pub(crate) type TriangleMeshRef = Rc<RefCell<TriangleMesh>>;
 impl TriangleMesh {
     const ERR_MSH:&str = "something went wrong in TriangleMesh::new";
 
+    pub(crate) fn get_vertices(&self)->&[MeshVertexRef;3]{
+        &self.vertices
+    }
+
     pub(crate) fn new(mut mesh: Option<&mut Mesh>, vertices:[Vertex;3])-> Result<TriangleMeshRef,NonManifoldMesh>{
 
 
@@ -227,6 +236,31 @@ impl TriangleMesh {
 
 
     }
+
+    ///a triangle on vertices that can be shared with other triangles, the half edges are linked
+    ///to the opposite half edges of the neighbour triangles
+    pub(crate) fn from_shared_vertices(vertices:[MeshVertexRef;3])-> Result<TriangleMeshRef,NonManifoldMesh>{
+        let mut half_edges = Vec::with_capacity(3);
+        for i in 0..3{
+            half_edges.push(MeshHalfEdge::new(None, vertices[i].clone(), vertices[(i+1)%3].clone(), None)?);
+        }
+
+        let result = Rc::new(RefCell::new(
+        Self{
+            mesh: None,
+            vertices,
+            half_edge: half_edges[0].clone()
+        }));
+
+        for i in 0..3{
+            let mut half_edge = half_edges[i].borrow_mut();
+            half_edge.triangle = Some(result.clone());
+            half_edge.next = Some(half_edges[(i+1)%3].clone());
+            half_edge.prev = Some(half_edges[(i+2)%3].clone());
+        }
+
+        Ok(result)
+    }
 }
 
 impl Drop for TriangleMesh{
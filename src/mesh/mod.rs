@@ -1,4 +1,6 @@
 #![allow(dead_code)] //toremove
+pub mod gpu_mesh;
+pub mod instanced;
 pub mod mesh_inners;
 pub mod polygon;
 pub mod vertex;
@@ -6,12 +8,15 @@ pub mod vertex;
 #[cfg(test)]
 mod test;
 
-use mesh_inners::{InnerMeshRef, MeshHalfEdgeRef, MeshVertex, MeshVertexRef, TriangleMeshRef};
+use std::{cell::RefCell, collections::HashMap, fmt::Display, rc::Rc};
+
+use gpu_mesh::GpuMesh;
+use mesh_inners::{InnerMesh, InnerMeshRef, MeshHalfEdgeRef, MeshVertex, MeshVertexRef, TriangleMesh, TriangleMeshRef};
 use vertex::Vertex;
 
-use crate::object_traits::{
-    GetableSceneObject, Rotation, Scale, SceneObject,
-    Translation,
+use crate::{
+    object_traits::{GetableSceneObject, Renderable, Rotation, Scale, SceneObject, Translation},
+    render::{ModelTransform, RenderUniforms},
 };
 
 #[derive(Debug)]
@@ -23,16 +28,61 @@ pub struct Mesh {
     pub(crate) position: Translation,
     pub(crate) scale: Scale,
     pub(crate) rotation: Rotation,
+
+    ///used by the `Renderable` impl
+    pub(crate) gpu_mesh: RefCell<Option<GpuMesh>>,
 }
 
 //? https://github.com/D-BookeR/Synthese-d-images-avec-OpenGL
 impl Mesh {
+    ///a mesh made of the triangles of `indices`, like the result of `Mesh::to_buffers_data`.
+    ///The vertices used by several triangles are shared between them
+    pub fn from_buffers_data(vertices:Vec<Vertex>, indices:&[u32])->Result<Self,MeshCreationError>{
+        if indices.len()%3 != 0{
+            return Err(MeshCreationError::InvalidIndexCount(indices.len()));
+        }
+
+        let vertex_list:Vec<MeshVertexRef> = vertices.into_iter()
+            .map(|vertex|Rc::new(RefCell::new(MeshVertex::new(None, vertex))))
+            .collect();
+
+        let mut triangle_list = Vec::with_capacity(indices.len()/3);
+        for (i,triangle) in indices.chunks_exact(3).enumerate(){
+            let mut corners = Vec::with_capacity(3);
+            for index in triangle{
+                let vertex = vertex_list.get(*index as usize).ok_or(MeshCreationError::InvalidIndex(*index))?;
+                corners.push(vertex.clone());
+            }
+            let corners:[MeshVertexRef;3] = corners.try_into().expect("a triangle has three corners");
+            triangle_list.push(TriangleMesh::from_shared_vertices(corners).map_err(|_|MeshCreationError::NonManifoldMesh(i))?);
+        }
+
+        Ok(Self{
+            name: None,
+            inner_mesh: Rc::new(RefCell::new(InnerMesh{mesh: None, vertex_list, triangle_list, revision: 0})),
+            position: Translation::zero(),
+            scale: Scale::zero(),
+            rotation: Rotation::zero(),
+            gpu_mesh: RefCell::new(None),
+        })
+    }
+
+    pub fn with_name(mut self, name:&str)->Self{
+        self.name = Some(name.to_string());
+        self
+    }
+
+    pub fn name(&self)->Option<&str>{
+        self.name.as_deref()
+    }
+
     fn add_vertex(&mut self, vertex:Vertex)->Result<(),std::cell::BorrowMutError>{
         self.push_vertex(MeshVertex::new(Some(self), vertex))
     }
 
     fn push_vertex(&mut self,vertex:MeshVertex)->Result<(),std::cell::BorrowMutError>{
-        self.inner_mesh.try_borrow_mut()?.vertex_list.push(vertex);
+        self.inner_mesh.try_borrow_mut()?.vertex_list.push(Rc::new(RefCell::new(vertex)));
+        self.mark_changed();
         Ok(())
     }
 
@@ -47,6 +97,80 @@ impl Mesh {
     fn pop_vertex(&mut self, _vertex:MeshVertexRef){
         todo!()
     }
+
+    ///the `GpuMesh` of this mesh and of the meshes sharing its geometry will re-upload it
+    ///before the next draw
+    pub fn mark_changed(&mut self){
+        self.inner_mesh.borrow_mut().revision += 1;
+    }
+
+    ///incremented each time the geometry changes, so the `GpuMesh` knows when to re-upload it
+    pub fn revision(&self)->u64{
+        self.inner_mesh.borrow().revision
+    }
+
+    pub fn triangle_count(&self)->usize{
+        self.inner_mesh.borrow().triangle_list.len()
+    }
+
+    ///a new mesh with the same geometry and transform, the two meshes can be drawn
+    ///in a single instanced batch by the `RenderQueue`.
+    ///Each mesh keeps its own GPU buffers, they are all re-uploaded after `mark_changed`
+    pub fn share(&self)->Self{
+        Self{
+            name: self.name.clone(),
+            inner_mesh: self.inner_mesh.clone(),
+            position: self.position,
+            scale: self.scale,
+            rotation: self.rotation,
+            gpu_mesh: RefCell::new(None),
+        }
+    }
+
+    ///true if the two meshes have the same geometry, see `Mesh::share`
+    pub fn shares_geometry(&self,other:&Mesh)->bool{
+        Rc::ptr_eq(&self.inner_mesh,&other.inner_mesh)
+    }
+
+    pub(crate) fn geometry_key(&self)->usize{
+        Rc::as_ptr(&self.inner_mesh) as usize
+    }
+
+    ///the buffers of the mesh, uploaded or re-uploaded if needed
+    pub(crate) fn gpu_mesh<F: glium::backend::Facade>(&self,facade:&F)->Result<std::cell::Ref<'_,GpuMesh>,MeshRenderError>{
+        {
+            let mut gpu_mesh = self.gpu_mesh.borrow_mut();
+            match gpu_mesh.as_mut() {
+                Some(gpu_mesh) => {
+                    gpu_mesh.update(facade, self)?;
+                }
+                None => *gpu_mesh = Some(GpuMesh::new(facade, self)?),
+            }
+        }
+        Ok(std::cell::Ref::map(self.gpu_mesh.borrow(), |gpu_mesh| {
+            gpu_mesh.as_ref().expect("the gpu mesh has just been created")
+        }))
+    }
+
+    ///the vertices of the triangles and their indices, shared vertices are only given once
+    pub fn to_buffers_data(&self)->(Vec<Vertex>,Vec<u32>){
+        let inner_mesh = self.inner_mesh.borrow();
+
+        let mut vertices = vec![];
+        let mut indices = vec![];
+        let mut known_vertices = HashMap::new();
+        for triangle in &inner_mesh.triangle_list {
+            for vertex in triangle.borrow().get_vertices() {
+                let index = *known_vertices.entry(Rc::as_ptr(vertex)).or_insert_with(|| {
+                    vertices.push(*vertex.borrow().get_vertex());
+                    (vertices.len() - 1) as u32
+                });
+                indices.push(index);
+            }
+        }
+
+        (vertices, indices)
+    }
 }
 
 impl SceneObject for Mesh {
@@ -203,43 +327,73 @@ impl GetableSceneObject for Mesh {
 //     }
 // }
 
+#[derive(Debug, Clone, PartialEq)]
+pub enum MeshCreationError {
+    ///the number of indices is not a multiple of 3
+    InvalidIndexCount(usize),
+    InvalidIndex(u32),
+    ///an edge is shared by more than two triangles, the value is the index of the triangle
+    NonManifoldMesh(usize),
+}
+
+impl Display for MeshCreationError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MeshCreationError::InvalidIndexCount(count) => {
+                write!(f, "the index count is not a multiple of 3 :{}", count)
+            }
+            MeshCreationError::InvalidIndex(index) => write!(f, "invalid vertex index :{}", index),
+            MeshCreationError::NonManifoldMesh(triangle) => {
+                write!(f, "non manifold mesh at the triangle :{}", triangle)
+            }
+        }
+    }
+}
+
+impl std::error::Error for MeshCreationError {}
+
+#[derive(Debug)]
 pub enum MeshRenderError {
     VertexBufferCreationError(glium::vertex::BufferCreationError),
     DrawError(glium::DrawError),
     IndiceBufferCreationError(glium::index::BufferCreationError),
+    InstancingNotSupported,
 }
 
-// impl Renderable for Mesh {
-//     type RenderError = MeshRenderError;
+impl Display for MeshRenderError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MeshRenderError::VertexBufferCreationError(e) => {
+                write!(f, "vertex buffer creation error :{}", e)
+            }
+            MeshRenderError::DrawError(e) => write!(f, "draw error :{}", e),
+            MeshRenderError::IndiceBufferCreationError(e) => {
+                write!(f, "index buffer creation error :{}", e)
+            }
+            MeshRenderError::InstancingNotSupported => write!(f, "instancing is not supported"),
+        }
+    }
+}
 
-//     fn render<F: glium::backend::Facade>(
-//         &self,
-//         facade: &F,
-//         program: &glium::Program,
-//         target: &mut glium::Frame,
-//         uniforms: &glium::uniforms::UniformsStorage<
-//             impl glium::uniforms::AsUniformValue,
-//             impl glium::uniforms::Uniforms,
-//         >,
-//         draw_parameters: &glium::DrawParameters,
-//     ) -> Result<(), Self::RenderError> {
-//         let mut vertex_buffer: VertexBuffer<Vertex> =
-//             glium::VertexBuffer::empty(facade, self.vertecies_number())
-//                 .map_err(MeshRenderError::VertexBufferCreationError)?;
-//         self.load_into_vertex_buffer(&mut vertex_buffer);
-
-//         let index_buffer = self
-//             .to_index_buffer(facade)
-//             .map_err(MeshRenderError::IndiceBufferCreationError)?;
-
-//         target
-//             .draw(
-//                 &vertex_buffer,
-//                 &index_buffer,
-//                 program,
-//                 uniforms,
-//                 draw_parameters,
-//             )
-//             .map_err(MeshRenderError::DrawError)
-//     }
-// }
+impl std::error::Error for MeshRenderError {}
+
+impl Renderable for Mesh {
+    type RenderError = MeshRenderError;
+
+    ///the buffers are uploaded on the first render, and re-uploaded when the mesh changes.
+    ///The transform of the mesh is given to the shader as the `model` uniform
+    fn render<F: glium::backend::Facade, S: glium::Surface, U: glium::uniforms::Uniforms>(
+        &self,
+        facade: &F,
+        program: &glium::Program,
+        target: &mut S,
+        uniforms: &U,
+        draw_parameters: &glium::DrawParameters,
+    ) -> Result<(), Self::RenderError> {
+        let gpu_mesh = self.gpu_mesh(facade)?;
+
+        let model = ModelTransform::of(self);
+        let uniforms = RenderUniforms::new().merge(uniforms).with(&model);
+        gpu_mesh.draw(target, program, &uniforms, draw_parameters)
+    }
+}
@@ -126,4 +126,191 @@ mod tests_half_edges {
         );
     }
 }
-*/
\ No newline at end of file
+*/
+mod test_mesh {
+    use crate::{
+        mesh::{vertex::Vertex, Mesh, MeshCreationError},
+        object_traits::{GetableSceneObject, Scale, SceneObject},
+    };
+
+    fn vertices(count: usize) -> Vec<Vertex> {
+        (0..count).map(|i| [i as f32, 0., 0.].into()).collect()
+    }
+
+    #[test]
+    fn test_to_buffers_data() {
+        let mesh = Mesh::from_buffers_data(vertices(4), &[0, 1, 2, 0, 2, 3]).unwrap();
+        assert_eq!(mesh.triangle_count(), 2);
+
+        let (buffer_vertices, indices) = mesh.to_buffers_data();
+        assert_eq!(buffer_vertices, vertices(4));
+        assert_eq!(indices, [0, 1, 2, 0, 2, 3]);
+    }
+
+    #[test]
+    fn test_to_buffers_data_order() {
+        //the vertices are given in the order of their first use, the unused ones are dropped
+        let mesh = Mesh::from_buffers_data(vertices(5), &[2, 0, 1, 2, 1, 3]).unwrap();
+        let (buffer_vertices, indices) = mesh.to_buffers_data();
+
+        let expected: Vec<Vertex> = [2., 0., 1., 3.].map(|x| [x, 0., 0.].into()).to_vec();
+        assert_eq!(buffer_vertices, expected);
+        assert_eq!(indices, [0, 1, 2, 0, 2, 3]);
+    }
+
+    #[test]
+    fn test_invalid_buffers_data() {
+        let result = Mesh::from_buffers_data(vertices(3), &[0, 1]);
+        assert_eq!(result.err(), Some(MeshCreationError::InvalidIndexCount(2)));
+
+        let result = Mesh::from_buffers_data(vertices(3), &[0, 1, 3]);
+        assert_eq!(result.err(), Some(MeshCreationError::InvalidIndex(3)));
+
+        //the edge 0-1 is shared by three triangles
+        let result = Mesh::from_buffers_data(vertices(5), &[0, 1, 2, 1, 0, 3, 1, 0, 4]);
+        assert_eq!(result.err(), Some(MeshCreationError::NonManifoldMesh(2)));
+    }
+
+    #[test]
+    fn test_model_matrix() {
+        let mut mesh = Mesh::from_buffers_data(vertices(3), &[0, 1, 2])
+            .unwrap()
+            .with_name("triangle");
+        assert_eq!(mesh.name(), Some("triangle"));
+
+        let identity: [[f32; 4]; 4] = mesh.get_model_matrix().into();
+        assert_eq!(
+            identity,
+            [
+                [1., 0., 0., 0.],
+                [0., 1., 0., 0.],
+                [0., 0., 1., 0.],
+                [0., 0., 0., 1.],
+            ]
+        );
+
+        mesh.set_position([1., 2., 3.].into());
+        mesh.set_scale(Scale::from(2.));
+        let model: [[f32; 4]; 4] = mesh.get_model_matrix().into();
+        assert_eq!(
+            model,
+            [
+                [2., 0., 0., 0.],
+                [0., 2., 0., 0.],
+                [0., 0., 2., 0.],
+                [1., 2., 3., 1.],
+            ]
+        );
+        assert_eq!(mesh.get_position(), [1., 2., 3.].into());
+    }
+
+    #[test]
+    fn test_shared_revision() {
+        let mut mesh = Mesh::from_buffers_data(vertices(3), &[0, 1, 2]).unwrap();
+        let shared = mesh.share();
+        assert!(mesh.shares_geometry(&shared));
+        assert_eq!(shared.revision(), 0);
+
+        //an edit through one mesh is seen by the other one
+        mesh.mark_changed();
+        assert_eq!(mesh.revision(), 1);
+        assert_eq!(shared.revision(), 1);
+    }
+}
+
+mod test_instancing {
+    use glium::{framebuffer::SimpleFrameBuffer, texture::Texture2d, uniforms::EmptyUniforms};
+
+    use crate::{
+        mesh::{
+            instanced::{Instance, InstanceBuffers, InstancedMesh},
+            vertex::Vertex,
+            Mesh,
+        },
+        object_traits::{Renderable, Rotation, Scale},
+        render::{BuiltinShader, ProgramCache},
+        utils::test_util::headless_facade,
+    };
+
+    #[test]
+    fn test_instance_data() {
+        let instance = Instance::new([1., 2., 3.].into())
+            .with_color([1., 0., 0., 1.])
+            .with_data([4.; 4]);
+        let data = instance.to_instance_data();
+
+        assert_eq!(data.instance_model[3], [1., 2., 3., 1.]);
+        assert_eq!(data.instance_color, [1., 0., 0., 1.]);
+        assert_eq!(data.instance_data, [4.; 4]);
+
+        let scaled = Instance::new([0.; 3].into())
+            .with_scale(Scale::from(2.))
+            .with_rotation(Rotation::zero())
+            .to_instance_data();
+        assert_eq!(scaled.instance_model[0][0], 2.);
+        assert_eq!(scaled.instance_model[3], [0., 0., 0., 1.]);
+    }
+
+    #[test]
+    fn test_push_swap_remove_dirty_range() {
+        let mut buffers = InstanceBuffers::default();
+        //a push then a removal keep the 4 instances, the buffer is not re-created
+        buffers.pushed(5);
+        buffers.swap_removed(1, 4);
+        assert_eq!(buffers.take_dirty(4), Some(1..4));
+        assert_eq!(buffers.take_dirty(4), None);
+
+        //removing the last instance moves nothing
+        buffers.swap_removed(3, 3);
+        assert_eq!(buffers.take_dirty(3), None);
+
+        buffers.pushed(4);
+        assert_eq!(buffers.take_dirty(4), Some(3..4));
+    }
+
+    #[test]
+    fn test_push_swap_remove_upload() {
+        let Some(facade) = headless_facade() else {
+            return;
+        };
+        let program = ProgramCache::new()
+            .instanced_program(&facade, BuiltinShader::Unlit)
+            .unwrap();
+        let texture = Texture2d::empty(&facade, 4, 4).unwrap();
+        let mut target = SimpleFrameBuffer::new(&facade, &texture).unwrap();
+
+        let vertices: Vec<Vertex> = [[0., 0., 0.], [1., 0., 0.], [0., 1., 0.]]
+            .map(Vertex::from)
+            .to_vec();
+        let mesh = Mesh::from_buffers_data(vertices, &[0, 1, 2]).unwrap();
+        let instances = (0..3)
+            .map(|x| Instance::new([x as f32, 0., 0.].into()))
+            .collect();
+        let mut instanced = InstancedMesh::new(&facade, &mesh, instances).unwrap();
+
+        let mut render = |instanced: &InstancedMesh| {
+            instanced
+                .render(
+                    &facade,
+                    &program,
+                    &mut target,
+                    &EmptyUniforms,
+                    &Default::default(),
+                )
+                .unwrap();
+        };
+        render(&instanced);
+
+        //the number of instances is the same, only the moved instance is re-uploaded
+        instanced.push(Instance::new([3., 0., 0.].into()));
+        instanced.swap_remove(0);
+        render(&instanced);
+
+        let uploaded = instanced.uploaded_instances().unwrap();
+        let positions: Vec<f32> = uploaded
+            .iter()
+            .map(|data| data.instance_model[3][0])
+            .collect();
+        assert_eq!(positions, [3., 1., 2.]);
+    }
+}
@@ -9,6 +9,8 @@ pub use translation::Translation;
 mod scale;
 mod translation;
 
+use crate::utils::types_util::Mat4;
+
 mod test;
 
 pub trait SceneObject {
@@ -29,6 +31,27 @@ where
     fn get_position(&self) -> Translation;
     fn get_scale(&self) -> Scale;
     fn get_rotation(&self) -> Rotation;
+
+    ///`translation * rotation * scale`, with the same layout as `Camera::view_matrix`
+    fn get_model_matrix(&self) -> Mat4 {
+        let [x, y, z] = self.get_position().into();
+        let [sx, sy, sz] = self.get_scale().into();
+
+        let translation = Mat4::from([
+            [1.0, 0.0, 0.0, 0.0],
+            [0.0, 1.0, 0.0, 0.0],
+            [0.0, 0.0, 1.0, 0.0],
+            [x, y, z, 1.0],
+        ]);
+        let scale = Mat4::from([
+            [sx, 0.0, 0.0, 0.0],
+            [0.0, sy, 0.0, 0.0],
+            [0.0, 0.0, sz, 0.0],
+            [0.0, 0.0, 0.0, 1.0],
+        ]);
+
+        translation * self.get_rotation().to_mat4() * scale
+    }
 }
 
 pub trait ApplicableSceneObject
@@ -46,18 +69,17 @@ where
     }
 }
 
+///can be rendered into any `glium::Surface` (a `Frame`, a `SimpleFrameBuffer`...).
+///The uniforms can be merged from several sources with a `RenderUniforms`
 pub trait Renderable {
     type RenderError;
 
-    fn render<F: glium::backend::Facade>(
+    fn render<F: glium::backend::Facade, S: glium::Surface, U: glium::uniforms::Uniforms>(
         &self,
         facade: &F,
         program: &glium::Program,
-        target: &mut glium::Frame,
-        uniform: &glium::uniforms::UniformsStorage<
-            impl glium::uniforms::AsUniformValue,
-            impl glium::uniforms::Uniforms,
-        >,
+        target: &mut S,
+        uniforms: &U,
         draw_parameters: &glium::DrawParameters,
     ) -> Result<(), Self::RenderError>;
 }
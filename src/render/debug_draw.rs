@@ -0,0 +1,417 @@
+use std::fmt::Display;
+
+use glium::{
+    backend::Facade,
+    draw_parameters::DepthTest,
+    implement_vertex,
+    index::{NoIndices, PrimitiveType},
+    uniforms::Uniforms,
+    Depth, DrawError, DrawParameters, Program, ProgramCreationError, Surface, VertexBuffer,
+};
+
+use crate::{
+    camera::Camera,
+    mesh::{polygon::Polygon, Mesh},
+    object_traits::GetableSceneObject,
+    utils::{
+        matrix_util::{
+            add, invert, normalize, scale, sub, transform_point, transform_vector, transpose,
+        },
+        types_util::{Arr3F32, Arr4F32, Mat4Arr},
+    },
+};
+
+use super::ModelTransform;
+
+///the number of segments of the circles of `DebugDraw::sphere`
+pub const DEBUG_CIRCLE_SEGMENTS: usize = 24;
+
+pub const DEBUG_RED: Arr4F32 = [1., 0.2, 0.2, 1.];
+pub const DEBUG_GREEN: Arr4F32 = [0.2, 1., 0.2, 1.];
+pub const DEBUG_BLUE: Arr4F32 = [0.3, 0.4, 1., 1.];
+pub const DEBUG_YELLOW: Arr4F32 = [1., 1., 0.2, 1.];
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DebugVertex {
+    pub position: Arr3F32,
+    pub color: Arr4F32,
+}
+
+implement_vertex!(DebugVertex, position, color);
+
+/******************\
+*                 *
+*----DebugDraw----*
+*                 *
+\******************/
+
+///lines accumulated during a frame, and drawn in a single draw call by `DebugDraw::flush`.
+///The positions are in world space
+pub struct DebugDraw {
+    lines: Vec<DebugVertex>,
+    ///when false, the lines are drawn over everything
+    pub depth_test: bool,
+
+    program: Option<Program>,
+    buffer: Option<VertexBuffer<DebugVertex>>,
+}
+
+impl std::fmt::Debug for DebugDraw {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("DebugDraw")
+            .field("lines", &self.len())
+            .field("depth_test", &self.depth_test)
+            .finish()
+    }
+}
+
+impl Default for DebugDraw {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl DebugDraw {
+    pub fn new() -> Self {
+        Self {
+            lines: vec![],
+            depth_test: true,
+            program: None,
+            buffer: None,
+        }
+    }
+
+    ///the ends of the lines, two vertices per line
+    pub fn vertices(&self) -> &[DebugVertex] {
+        &self.lines
+    }
+
+    ///the number of lines
+    pub fn len(&self) -> usize {
+        self.lines.len() / 2
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.lines.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.lines.clear();
+    }
+
+    pub fn line(&mut self, from: Arr3F32, to: Arr3F32, color: Arr4F32) {
+        self.lines.push(DebugVertex {
+            position: from,
+            color,
+        });
+        self.lines.push(DebugVertex {
+            position: to,
+            color,
+        });
+    }
+
+    ///the twelve edges of the box
+    pub fn aabb(&mut self, min: Arr3F32, max: Arr3F32, color: Arr4F32) {
+        let corner = |i: usize| {
+            [
+                if i & 1 == 0 { min[0] } else { max[0] },
+                if i & 2 == 0 { min[1] } else { max[1] },
+                if i & 4 == 0 { min[2] } else { max[2] },
+            ]
+        };
+
+        for i in 0..8 {
+            for axis in [1, 2, 4] {
+                if i & axis == 0 {
+                    self.line(corner(i), corner(i | axis), color);
+                }
+            }
+        }
+    }
+
+    ///three circles, one around each axis
+    pub fn sphere(&mut self, center: Arr3F32, radius: f32, color: Arr4F32) {
+        let point = |angle: f32, axis: usize| {
+            let (sin, cos) = angle.sin_cos();
+            let mut point = center;
+            point[(axis + 1) % 3] += cos * radius;
+            point[(axis + 2) % 3] += sin * radius;
+            point
+        };
+
+        let step = std::f32::consts::TAU / DEBUG_CIRCLE_SEGMENTS as f32;
+        for axis in 0..3 {
+            for i in 0..DEBUG_CIRCLE_SEGMENTS {
+                self.line(
+                    point(i as f32 * step, axis),
+                    point((i + 1) as f32 * step, axis),
+                    color,
+                );
+            }
+        }
+    }
+
+    ///the x, y and z axes of `model` in red, green and blue
+    pub fn axes(&mut self, model: Mat4Arr, size: f32) {
+        let origin = transform_point(&model, [0.; 3]);
+        for (axis, color) in [DEBUG_RED, DEBUG_GREEN, DEBUG_BLUE].into_iter().enumerate() {
+            let mut end = [0.; 3];
+            end[axis] = size;
+            self.line(origin, transform_point(&model, end), color);
+        }
+    }
+
+    pub fn object_axes<O: GetableSceneObject + ?Sized>(&mut self, object: &O, size: f32) {
+        self.axes(ModelTransform::of(object).model, size);
+    }
+
+    ///the edges of the volume seen through `view_projection`, nothing is drawn if
+    ///the matrix can't be inverted
+    pub fn frustum(&mut self, view_projection: Mat4Arr, color: Arr4F32) {
+        let Some(inverse) = invert(&view_projection) else {
+            return;
+        };
+
+        let corner = |i: usize| {
+            let ndc = [
+                if i & 1 == 0 { -1. } else { 1. },
+                if i & 2 == 0 { -1. } else { 1. },
+                if i & 4 == 0 { -1. } else { 1. },
+            ];
+            transform_point(&inverse, ndc)
+        };
+
+        for i in 0..8 {
+            for axis in [1, 2, 4] {
+                if i & axis == 0 {
+                    self.line(corner(i), corner(i | axis), color);
+                }
+            }
+        }
+    }
+
+    pub fn camera_frustum<C: Camera + ?Sized>(&mut self, camera: &C, color: Arr4F32) {
+        self.frustum(camera.view_projection_matrix().into(), color);
+    }
+
+    pub fn polygon<const N: usize>(&mut self, polygon: &Polygon<N>, color: Arr4F32) {
+        for [from, to] in polygon.edges() {
+            self.line(xyz(from.position), xyz(to.position), color);
+        }
+    }
+
+    ///the normal of each vertex of the triangles, transformed like in the built-in shaders by
+    ///the inverse transpose of the model matrix of the mesh
+    pub fn mesh_normals(&mut self, mesh: &Mesh, length: f32, color: Arr4F32) {
+        let model = ModelTransform::of(mesh).model;
+        let normal_matrix = invert(&model).map_or(model, |inverse| transpose(&inverse));
+        let inner_mesh = mesh.inner_mesh.borrow();
+
+        for triangle in &inner_mesh.triangle_list {
+            for vertex in triangle.borrow().get_vertices() {
+                let vertex = *vertex.borrow().get_vertex();
+                let position = transform_point(&model, xyz(vertex.position));
+                let normal = normalize(transform_vector(&normal_matrix, vertex.normal));
+                self.line(position, add(position, scale(normal, length)), color);
+            }
+        }
+    }
+
+    ///the tangent of each triangle (the direction of its increasing `u` texture coordinate),
+    ///from the center of the triangle. The triangles without texture coordinates are skipped
+    pub fn mesh_tangents(&mut self, mesh: &Mesh, length: f32, color: Arr4F32) {
+        let model = ModelTransform::of(mesh).model;
+        let inner_mesh = mesh.inner_mesh.borrow();
+
+        for triangle in &inner_mesh.triangle_list {
+            let [a, b, c] = triangle
+                .borrow()
+                .get_vertices()
+                .each_ref()
+                .map(|vertex| *vertex.borrow().get_vertex());
+            let Some(tangent) = triangle_tangent(
+                [xyz(a.position), xyz(b.position), xyz(c.position)],
+                [a.texture, b.texture, c.texture],
+            ) else {
+                continue;
+            };
+
+            let center = scale(
+                add(add(xyz(a.position), xyz(b.position)), xyz(c.position)),
+                1. / 3.,
+            );
+            let center = transform_point(&model, center);
+            let tangent = normalize(transform_vector(&model, tangent));
+            self.line(center, add(center, scale(tangent, length)), color);
+        }
+    }
+
+    ///each half-edge, pulled toward the center of its triangle so the two half-edges of an
+    ///edge are both visible. The half-edges without opposite (the border of the mesh) are
+    ///drawn with `border_color`
+    pub fn mesh_wireframe(&mut self, mesh: &Mesh, color: Arr4F32, border_color: Arr4F32) {
+        let model = ModelTransform::of(mesh).model;
+        let inner_mesh = mesh.inner_mesh.borrow();
+
+        for triangle in &inner_mesh.triangle_list {
+            let vertices = triangle.borrow().get_vertices().clone();
+            let positions = vertices
+                .each_ref()
+                .map(|vertex| transform_point(&model, xyz(vertex.borrow().get_vertex().position)));
+            let center = scale(add(add(positions[0], positions[1]), positions[2]), 1. / 3.);
+            let inset = |point: Arr3F32| add(scale(point, 0.9), scale(center, 0.1));
+
+            for i in 0..3 {
+                let next = (i + 1) % 3;
+                let border = vertices[i]
+                    .borrow()
+                    .get_half_edge_to(vertices[next].clone())
+                    .is_none_or(|half_edge| half_edge.borrow().oposite.is_none());
+                let color = if border { border_color } else { color };
+                self.line(inset(positions[i]), inset(positions[next]), color);
+            }
+        }
+    }
+
+    ///draw every line and clear them, `uniforms` has to give the `view_projection` matrix
+    pub fn flush<F: Facade, S: Surface, U: Uniforms>(
+        &mut self,
+        facade: &F,
+        target: &mut S,
+        uniforms: &U,
+    ) -> Result<(), DebugDrawError> {
+        if self.lines.is_empty() {
+            return Ok(());
+        }
+
+        if self.program.is_none() {
+            self.program = Some(
+                Program::from_source(facade, DEBUG_VERTEX_SHADER, DEBUG_FRAGMENT_SHADER, None)
+                    .map_err(DebugDrawError::ProgramCreationError)?,
+            );
+        }
+
+        match &self.buffer {
+            Some(buffer) if buffer.len() >= self.lines.len() => {
+                let slice = buffer
+                    .slice(0..self.lines.len())
+                    .expect("the lines fit in the buffer");
+                slice.write(&self.lines);
+            }
+            _ => {
+                self.buffer = Some(
+                    VertexBuffer::dynamic(facade, &self.lines)
+                        .map_err(DebugDrawError::VertexBufferCreationError)?,
+                )
+            }
+        }
+
+        let draw_parameters = DrawParameters {
+            depth: Depth {
+                test: match self.depth_test {
+                    true => DepthTest::IfLessOrEqual,
+                    false => DepthTest::Overwrite,
+                },
+                write: false,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        let (Some(program), Some(buffer)) = (&self.program, &self.buffer) else {
+            unreachable!("the program and the buffer have just been created")
+        };
+        let result = target
+            .draw(
+                buffer
+                    .slice(0..self.lines.len())
+                    .expect("the lines fit in the buffer"),
+                NoIndices(PrimitiveType::LinesList),
+                program,
+                uniforms,
+                &draw_parameters,
+            )
+            .map_err(DebugDrawError::DrawError);
+
+        self.lines.clear();
+        result
+    }
+}
+
+#[derive(Debug)]
+pub enum DebugDrawError {
+    ProgramCreationError(ProgramCreationError),
+    VertexBufferCreationError(glium::vertex::BufferCreationError),
+    DrawError(DrawError),
+}
+
+impl Display for DebugDrawError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DebugDrawError::ProgramCreationError(e) => write!(f, "program creation error :{}", e),
+            DebugDrawError::VertexBufferCreationError(e) => {
+                write!(f, "vertex buffer creation error :{}", e)
+            }
+            DebugDrawError::DrawError(e) => write!(f, "draw error :{}", e),
+        }
+    }
+}
+
+impl std::error::Error for DebugDrawError {}
+
+const DEBUG_VERTEX_SHADER: &str = r#"
+#version 140
+
+in vec3 position;
+in vec4 color;
+
+uniform mat4 view_projection;
+
+out vec4 v_color;
+
+void main() {
+    v_color = color;
+    gl_Position = view_projection * vec4(position, 1.0);
+}
+"#;
+
+const DEBUG_FRAGMENT_SHADER: &str = r#"
+#version 140
+
+in vec4 v_color;
+
+out vec4 f_color;
+
+void main() {
+    f_color = v_color;
+}
+"#;
+
+/******************\
+*                 *
+*------Utils------*
+*                 *
+\******************/
+
+fn xyz(position: Arr4F32) -> Arr3F32 {
+    let w = if position[3] == 0. { 1. } else { position[3] };
+    [position[0] / w, position[1] / w, position[2] / w]
+}
+
+///the direction of the increasing `u` on the triangle, `None` if the texture coordinates
+///are degenerate
+pub(crate) fn triangle_tangent(positions: [Arr3F32; 3], uvs: [Arr3F32; 3]) -> Option<Arr3F32> {
+    let edge_1 = sub(positions[1], positions[0]);
+    let edge_2 = sub(positions[2], positions[0]);
+    let (du_1, dv_1) = (uvs[1][0] - uvs[0][0], uvs[1][1] - uvs[0][1]);
+    let (du_2, dv_2) = (uvs[2][0] - uvs[0][0], uvs[2][1] - uvs[0][1]);
+
+    let determinant = du_1 * dv_2 - du_2 * dv_1;
+    if determinant.abs() < f32::EPSILON {
+        return None;
+    }
+    Some(normalize(scale(
+        sub(scale(edge_1, dv_2), scale(edge_2, dv_1)),
+        1. / determinant,
+    )))
+}
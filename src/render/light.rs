@@ -0,0 +1,467 @@
+use glium::{
+    backend::Facade,
+    buffer::BufferCreationError,
+    implement_uniform_block,
+    uniforms::{LayoutMismatchError, UniformBlock, UniformBuffer, UniformValue},
+};
+
+use crate::{
+    object_traits::{GetableSceneObject, Rotation, Scale, SceneObject, Translation},
+    utils::{
+        matrix_util::{add, cross, dot, length, normalize, scale},
+        types_util::{Arr3F32, QuatF32},
+    },
+};
+
+use super::{RenderUniforms, UniformProvider};
+
+///the maximum number of lights in the `Lights` uniform block,
+///it has to match `MAX_LIGHTS` in the built-in shaders
+pub const MAX_LIGHTS: usize = 16;
+
+///the direction of a light with no rotation
+pub const LIGHT_FORWARD: Arr3F32 = [0., 0., -1.];
+
+/******************\
+*                 *
+*---Light params--*
+*                 *
+\******************/
+
+///`1 / (constant + linear * d + quadratic * d²)`, the light is cut after `range` if it's positive
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Attenuation {
+    pub constant: f32,
+    pub linear: f32,
+    pub quadratic: f32,
+    pub range: f32,
+}
+
+impl Attenuation {
+    pub fn none() -> Self {
+        Self {
+            constant: 1.,
+            linear: 0.,
+            quadratic: 0.,
+            range: 0.,
+        }
+    }
+
+    ///a smooth attenuation reaching about 1% of the intensity at `range`
+    pub fn with_range(range: f32) -> Self {
+        Self {
+            constant: 1.,
+            linear: 4.5 / range,
+            quadratic: 75. / (range * range),
+            range,
+        }
+    }
+}
+
+impl Default for Attenuation {
+    fn default() -> Self {
+        Self::with_range(50.)
+    }
+}
+
+///the angles are in radians, from the axis of the spot.
+///The light fades between the inner and the outer angle
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SpotCone {
+    pub inner_angle: f32,
+    pub outer_angle: f32,
+}
+
+impl Default for SpotCone {
+    fn default() -> Self {
+        Self {
+            inner_angle: 0.35,
+            outer_angle: 0.5,
+        }
+    }
+}
+
+/******************\
+*                 *
+*-----GpuLight----*
+*                 *
+\******************/
+
+///a light, as stored in the `Lights` uniform block (std140)
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct GpuLight {
+    ///xyz, w is the kind of light: 0 directional, 1 point, 2 spot
+    pub position: [f32; 4],
+    pub direction: [f32; 4],
+    ///rgb multiplied by the intensity
+    pub color: [f32; 4],
+    ///constant, linear, quadratic and range
+    pub attenuation: [f32; 4],
+    ///cosines of the inner and the outer angle
+    pub cone: [f32; 4],
+}
+
+implement_uniform_block!(GpuLight, position, direction, color, attenuation, cone);
+
+///the `Lights` uniform block
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct LightBlock {
+    pub lights: [GpuLight; MAX_LIGHTS],
+    pub ambient_light: [f32; 4],
+    pub light_count: i32,
+}
+
+implement_uniform_block!(LightBlock, lights, ambient_light, light_count);
+
+///something that can be given to a `LightList`
+pub trait Light: GetableSceneObject {
+    fn to_gpu_light(&self) -> GpuLight;
+
+    ///the direction of the light, `LIGHT_FORWARD` rotated by the light rotation
+    fn direction(&self) -> Arr3F32 {
+        rotate_vector(self.get_rotation(), LIGHT_FORWARD)
+    }
+}
+
+/******************\
+*                 *
+*------Lights-----*
+*                 *
+\******************/
+
+macro_rules! impl_light_scene_object {
+    ($light:ty) => {
+        impl SceneObject for $light {
+            #[inline]
+            fn translate(&mut self, trans: Translation) {
+                self.position += trans;
+            }
+
+            #[inline]
+            fn set_position(&mut self, pos: Translation) {
+                self.position = pos;
+            }
+
+            #[inline]
+            fn scale(&mut self, scale: Scale) {
+                self.scale += scale;
+            }
+
+            #[inline]
+            fn set_scale(&mut self, scale: Scale) {
+                self.scale = scale;
+            }
+
+            #[inline]
+            fn rotate(&mut self, rotation: Rotation) {
+                self.rotation += rotation;
+            }
+
+            #[inline]
+            fn set_rotation(&mut self, rotation: Rotation) {
+                self.rotation = rotation;
+            }
+        }
+
+        impl GetableSceneObject for $light {
+            #[inline]
+            fn get_position(&self) -> Translation {
+                self.position
+            }
+
+            #[inline]
+            fn get_scale(&self) -> Scale {
+                self.scale
+            }
+
+            #[inline]
+            fn get_rotation(&self) -> Rotation {
+                self.rotation
+            }
+        }
+    };
+}
+
+///a light coming from infinitely far away in one direction, like the sun.
+///Only its rotation is used
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DirectionalLight {
+    pub color: Arr3F32,
+    pub intensity: f32,
+
+    position: Translation,
+    scale: Scale,
+    rotation: Rotation,
+}
+
+impl DirectionalLight {
+    pub fn new(direction: Arr3F32, color: Arr3F32, intensity: f32) -> Self {
+        Self {
+            color,
+            intensity,
+            position: Translation::zero(),
+            scale: Scale::zero(),
+            rotation: rotation_towards(direction),
+        }
+    }
+}
+
+impl Light for DirectionalLight {
+    fn to_gpu_light(&self) -> GpuLight {
+        let [x, y, z] = self.direction();
+        GpuLight {
+            position: [0., 0., 0., 0.],
+            direction: [x, y, z, 0.],
+            color: gpu_color(self.color, self.intensity),
+            attenuation: [1., 0., 0., 0.],
+            cone: [0.; 4],
+        }
+    }
+}
+
+impl_light_scene_object!(DirectionalLight);
+
+///a light emitting in every direction from a point.
+///Only its position is used
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PointLight {
+    pub color: Arr3F32,
+    pub intensity: f32,
+    pub attenuation: Attenuation,
+
+    position: Translation,
+    scale: Scale,
+    rotation: Rotation,
+}
+
+impl PointLight {
+    pub fn new(position: Translation, color: Arr3F32, intensity: f32) -> Self {
+        Self {
+            color,
+            intensity,
+            attenuation: Attenuation::default(),
+            position,
+            scale: Scale::zero(),
+            rotation: Rotation::zero(),
+        }
+    }
+
+    pub fn with_attenuation(mut self, attenuation: Attenuation) -> Self {
+        self.attenuation = attenuation;
+        self
+    }
+}
+
+impl Light for PointLight {
+    fn to_gpu_light(&self) -> GpuLight {
+        let [x, y, z] = self.position.into();
+        GpuLight {
+            position: [x, y, z, 1.],
+            direction: [0.; 4],
+            color: gpu_color(self.color, self.intensity),
+            attenuation: gpu_attenuation(self.attenuation),
+            cone: [0.; 4],
+        }
+    }
+}
+
+impl_light_scene_object!(PointLight);
+
+///a light emitting in a cone from a point
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SpotLight {
+    pub color: Arr3F32,
+    pub intensity: f32,
+    pub attenuation: Attenuation,
+    pub cone: SpotCone,
+
+    position: Translation,
+    scale: Scale,
+    rotation: Rotation,
+}
+
+impl SpotLight {
+    pub fn new(position: Translation, direction: Arr3F32, color: Arr3F32, intensity: f32) -> Self {
+        Self {
+            color,
+            intensity,
+            attenuation: Attenuation::default(),
+            cone: SpotCone::default(),
+            position,
+            scale: Scale::zero(),
+            rotation: rotation_towards(direction),
+        }
+    }
+
+    pub fn with_attenuation(mut self, attenuation: Attenuation) -> Self {
+        self.attenuation = attenuation;
+        self
+    }
+
+    pub fn with_cone(mut self, cone: SpotCone) -> Self {
+        self.cone = cone;
+        self
+    }
+}
+
+impl Light for SpotLight {
+    fn to_gpu_light(&self) -> GpuLight {
+        let [x, y, z] = self.position.into();
+        let [dx, dy, dz] = self.direction();
+        GpuLight {
+            position: [x, y, z, 2.],
+            direction: [dx, dy, dz, 0.],
+            color: gpu_color(self.color, self.intensity),
+            attenuation: gpu_attenuation(self.attenuation),
+            cone: [
+                self.cone.inner_angle.cos(),
+                self.cone.outer_angle.cos(),
+                0.,
+                0.,
+            ],
+        }
+    }
+}
+
+impl_light_scene_object!(SpotLight);
+
+/******************\
+*                 *
+*----LightList----*
+*                 *
+\******************/
+
+///the lights of a frame, only the first `MAX_LIGHTS` are used
+#[derive(Debug, Clone, Default)]
+pub struct LightList {
+    lights: Vec<GpuLight>,
+    ambient_light: Arr3F32,
+}
+
+impl LightList {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_ambient_light(mut self, ambient_light: Arr3F32) -> Self {
+        self.ambient_light = ambient_light;
+        self
+    }
+
+    pub fn set_ambient_light(&mut self, ambient_light: Arr3F32) {
+        self.ambient_light = ambient_light;
+    }
+
+    pub fn push<L: Light + ?Sized>(&mut self, light: &L) {
+        self.lights.push(light.to_gpu_light());
+    }
+
+    pub fn clear(&mut self) {
+        self.lights.clear();
+    }
+
+    pub fn len(&self) -> usize {
+        self.lights.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.lights.is_empty()
+    }
+
+    pub fn lights(&self) -> &[GpuLight] {
+        &self.lights
+    }
+
+    pub fn to_block(&self) -> LightBlock {
+        let mut block = LightBlock::default();
+        let count = self.lights.len().min(MAX_LIGHTS);
+        block.lights[..count].copy_from_slice(&self.lights[..count]);
+
+        let [r, g, b] = self.ambient_light;
+        block.ambient_light = [r, g, b, 1.];
+        block.light_count = count as i32;
+        block
+    }
+}
+
+///the `Lights` uniform block on the GPU, to be updated each frame from a `LightList`
+pub struct LightBuffer {
+    buffer: UniformBuffer<LightBlock>,
+}
+
+impl LightBuffer {
+    pub fn new<F: Facade>(facade: &F) -> Result<Self, BufferCreationError> {
+        Ok(Self {
+            buffer: UniformBuffer::new(facade, LightBlock::default())?,
+        })
+    }
+
+    pub fn update(&mut self, lights: &LightList) {
+        self.buffer.write(&lights.to_block());
+    }
+}
+
+///`Lights`
+impl UniformProvider for LightBuffer {
+    fn provide_uniforms<'a>(&'a self, uniforms: &mut RenderUniforms<'a>) {
+        fn matches_layout(block: &glium::program::UniformBlock) -> Result<(), LayoutMismatchError> {
+            LightBlock::matches(&block.layout, 0)
+        }
+        uniforms.set(
+            "Lights",
+            UniformValue::Block(self.buffer.as_slice_any(), matches_layout),
+        );
+    }
+}
+
+/******************\
+*                 *
+*------Utils------*
+*                 *
+\******************/
+
+fn gpu_color(color: Arr3F32, intensity: f32) -> [f32; 4] {
+    let [r, g, b] = color;
+    [r * intensity, g * intensity, b * intensity, 1.]
+}
+
+fn gpu_attenuation(attenuation: Attenuation) -> [f32; 4] {
+    [
+        attenuation.constant,
+        attenuation.linear,
+        attenuation.quadratic,
+        attenuation.range,
+    ]
+}
+
+///rotate `vector` by the quaternion of `rotation`
+pub(crate) fn rotate_vector(rotation: Rotation, vector: Arr3F32) -> Arr3F32 {
+    let (w, x, y, z): (f32, f32, f32, f32) = QuatF32::from(rotation).into();
+
+    //v + w * t + q × t, with t = 2 * q × v
+    let t = scale(cross([x, y, z], vector), 2.);
+    add(add(vector, scale(t, w)), cross([x, y, z], t))
+}
+
+///the rotation turning `LIGHT_FORWARD` towards `direction`
+pub(crate) fn rotation_towards(direction: Arr3F32) -> Rotation {
+    if length(direction) == 0. {
+        return Rotation::zero();
+    }
+    let direction = normalize(direction);
+
+    let cos = dot(LIGHT_FORWARD, direction);
+    let axis = cross(LIGHT_FORWARD, direction);
+
+    if length(axis) < 1e-6 {
+        return if cos > 0. {
+            Rotation::zero()
+        } else {
+            Rotation::from_axis(std::f32::consts::PI, (0., 1., 0.))
+        };
+    }
+    let [x, y, z] = normalize(axis);
+    Rotation::from_axis(cos.clamp(-1., 1.).acos(), (x, y, z))
+}
@@ -0,0 +1,509 @@
+use std::{fmt::Display, path::Path, rc::Rc};
+
+use glium::{
+    backend::Facade,
+    draw_parameters::{BackfaceCullingMode, Blend, BlendingFunction, DepthTest},
+    texture::{Cubemap, SrgbTexture2d, Texture2d},
+    uniforms::{AsUniformValue, SamplerBehavior, UniformValue, Uniforms},
+    Depth, DrawParameters, Program, ProgramCreationError, Surface,
+};
+
+use crate::{file_parser::WavefrontMaterial, object_traits::Renderable};
+
+use super::{
+    environment_sampler, linear_texture, resolve_map_path, BuiltinShader, ImageData, ProgramCache,
+    RenderUniforms, Skybox, TextureCache, TextureError, TextureSampler, UniformProvider,
+};
+
+/******************\
+*                 *
+*--MaterialParam--*
+*                 *
+\******************/
+
+///a typed uniform of a `Material`
+#[derive(Clone)]
+pub enum MaterialParam {
+    Float(f32),
+    Int(i32),
+    Bool(bool),
+    Vec2([f32; 2]),
+    Vec3([f32; 3]),
+    Vec4([f32; 4]),
+    ///an rgba color, given to the shader as a `vec4`
+    Color([f32; 4]),
+    Mat4([[f32; 4]; 4]),
+    Texture(Rc<Texture2d>, SamplerBehavior),
+    ///a color texture, see `srgb_texture`
+    SrgbTexture(Rc<SrgbTexture2d>, SamplerBehavior),
+    ///an environment map, like the cubemap of a `Skybox`
+    Cubemap(Rc<Cubemap>, SamplerBehavior),
+}
+
+impl AsUniformValue for MaterialParam {
+    fn as_uniform_value(&self) -> UniformValue<'_> {
+        match self {
+            MaterialParam::Float(value) => UniformValue::Float(*value),
+            MaterialParam::Int(value) => UniformValue::SignedInt(*value),
+            MaterialParam::Bool(value) => UniformValue::Bool(*value),
+            MaterialParam::Vec2(value) => UniformValue::Vec2(*value),
+            MaterialParam::Vec3(value) => UniformValue::Vec3(*value),
+            MaterialParam::Vec4(value) | MaterialParam::Color(value) => UniformValue::Vec4(*value),
+            MaterialParam::Mat4(value) => UniformValue::Mat4(*value),
+            MaterialParam::Texture(texture, sampler) => {
+                UniformValue::Texture2d(texture, Some(*sampler))
+            }
+            MaterialParam::SrgbTexture(texture, sampler) => {
+                UniformValue::SrgbTexture2d(texture, Some(*sampler))
+            }
+            MaterialParam::Cubemap(cubemap, sampler) => {
+                UniformValue::Cubemap(cubemap, Some(*sampler))
+            }
+        }
+    }
+}
+
+impl From<f32> for MaterialParam {
+    fn from(value: f32) -> Self {
+        MaterialParam::Float(value)
+    }
+}
+
+impl From<i32> for MaterialParam {
+    fn from(value: i32) -> Self {
+        MaterialParam::Int(value)
+    }
+}
+
+impl From<bool> for MaterialParam {
+    fn from(value: bool) -> Self {
+        MaterialParam::Bool(value)
+    }
+}
+
+impl From<[f32; 2]> for MaterialParam {
+    fn from(value: [f32; 2]) -> Self {
+        MaterialParam::Vec2(value)
+    }
+}
+
+impl From<[f32; 3]> for MaterialParam {
+    fn from(value: [f32; 3]) -> Self {
+        MaterialParam::Vec3(value)
+    }
+}
+
+impl From<[f32; 4]> for MaterialParam {
+    fn from(value: [f32; 4]) -> Self {
+        MaterialParam::Vec4(value)
+    }
+}
+
+impl From<[[f32; 4]; 4]> for MaterialParam {
+    fn from(value: [[f32; 4]; 4]) -> Self {
+        MaterialParam::Mat4(value)
+    }
+}
+
+impl From<Rc<Texture2d>> for MaterialParam {
+    fn from(value: Rc<Texture2d>) -> Self {
+        MaterialParam::Texture(value, SamplerBehavior::default())
+    }
+}
+
+impl From<Rc<SrgbTexture2d>> for MaterialParam {
+    fn from(value: Rc<SrgbTexture2d>) -> Self {
+        MaterialParam::SrgbTexture(value, TextureSampler::default().into())
+    }
+}
+
+impl From<Rc<Cubemap>> for MaterialParam {
+    fn from(value: Rc<Cubemap>) -> Self {
+        MaterialParam::Cubemap(value, environment_sampler())
+    }
+}
+
+/******************\
+*                 *
+*-----Material----*
+*                 *
+\******************/
+
+///a shader program, its parameters and the draw parameters.
+///Cloning a material shares its program
+#[derive(Clone)]
+pub struct Material {
+    name: String,
+    program: Rc<Program>,
+    ///the same shader reading the `InstanceData` attributes, used by the `RenderQueue` batches
+    instanced_program: Option<Rc<Program>>,
+    params: Vec<(String, MaterialParam)>,
+    draw_parameters: DrawParameters<'static>,
+}
+
+impl Material {
+    ///the depth test is enabled, and nothing is culled
+    pub fn new(name: &str, program: Rc<Program>) -> Self {
+        Self {
+            name: name.to_string(),
+            program,
+            instanced_program: None,
+            params: vec![],
+            draw_parameters: DrawParameters {
+                depth: Depth {
+                    test: DepthTest::IfLess,
+                    write: true,
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+        }
+    }
+
+    pub fn from_source<F: Facade>(
+        facade: &F,
+        name: &str,
+        vertex_shader: &str,
+        fragment_shader: &str,
+    ) -> Result<Self, MaterialError> {
+        let program = Program::from_source(facade, vertex_shader, fragment_shader, None)
+            .map_err(MaterialError::ProgramCreationError)?;
+        Ok(Self::new(name, Rc::new(program)))
+    }
+
+    ///the instanced program is compiled too, so the material can be batched.
+    ///The programs are not shared with the other materials, see `Material::from_builtin_cached`
+    pub fn from_builtin<F: Facade>(
+        facade: &F,
+        name: &str,
+        shader: BuiltinShader,
+    ) -> Result<Self, MaterialError> {
+        Self::from_builtin_cached(facade, &mut ProgramCache::new(), name, shader)
+    }
+
+    ///the programs come from `programs`, the instanced one too so the material can be batched
+    pub fn from_builtin_cached<F: Facade>(
+        facade: &F,
+        programs: &mut ProgramCache,
+        name: &str,
+        shader: BuiltinShader,
+    ) -> Result<Self, MaterialError> {
+        Ok(Self::new(name, programs.program(facade, shader)?)
+            .with_instanced_program(programs.instanced_program(facade, shader)?))
+    }
+
+    ///the same material, drawn with `BuiltinShader::instanced_vertex_source`
+    pub fn from_builtin_instanced<F: Facade>(
+        facade: &F,
+        name: &str,
+        shader: BuiltinShader,
+    ) -> Result<Self, MaterialError> {
+        Self::from_builtin_instanced_cached(facade, &mut ProgramCache::new(), name, shader)
+    }
+
+    pub fn from_builtin_instanced_cached<F: Facade>(
+        facade: &F,
+        programs: &mut ProgramCache,
+        name: &str,
+        shader: BuiltinShader,
+    ) -> Result<Self, MaterialError> {
+        Ok(Self::new(name, programs.instanced_program(facade, shader)?))
+    }
+
+    pub fn unlit<F: Facade>(facade: &F, color: [f32; 4]) -> Result<Self, MaterialError> {
+        Self::unlit_cached(facade, &mut ProgramCache::new(), color)
+    }
+
+    pub fn unlit_cached<F: Facade>(
+        facade: &F,
+        programs: &mut ProgramCache,
+        color: [f32; 4],
+    ) -> Result<Self, MaterialError> {
+        Ok(
+            Self::from_builtin_cached(facade, programs, "unlit", BuiltinShader::Unlit)?
+                .with_param("color", MaterialParam::Color(color))
+                .with_param("has_diffuse_map", false),
+        )
+    }
+
+    ///lit by the `Lights` uniform block, a `LightBuffer` has to be given when drawing
+    pub fn lambert<F: Facade>(facade: &F, diffuse: [f32; 4]) -> Result<Self, MaterialError> {
+        Self::lambert_cached(facade, &mut ProgramCache::new(), diffuse)
+    }
+
+    pub fn lambert_cached<F: Facade>(
+        facade: &F,
+        programs: &mut ProgramCache,
+        diffuse: [f32; 4],
+    ) -> Result<Self, MaterialError> {
+        Ok(
+            Self::from_builtin_cached(facade, programs, "lambert", BuiltinShader::Lambert)?
+                .with_param("ambient", [1.; 3])
+                .with_param("diffuse", MaterialParam::Color(diffuse))
+                .with_param("has_diffuse_map", false),
+        )
+    }
+
+    ///lit by the `Lights` uniform block, a `LightBuffer` has to be given when drawing
+    pub fn blinn_phong<F: Facade>(
+        facade: &F,
+        diffuse: [f32; 4],
+        specular: [f32; 3],
+        shininess: f32,
+    ) -> Result<Self, MaterialError> {
+        Self::blinn_phong_cached(
+            facade,
+            &mut ProgramCache::new(),
+            diffuse,
+            specular,
+            shininess,
+        )
+    }
+
+    pub fn blinn_phong_cached<F: Facade>(
+        facade: &F,
+        programs: &mut ProgramCache,
+        diffuse: [f32; 4],
+        specular: [f32; 3],
+        shininess: f32,
+    ) -> Result<Self, MaterialError> {
+        Ok(
+            Self::from_builtin_cached(facade, programs, "blinn_phong", BuiltinShader::BlinnPhong)?
+                .with_param("ambient", [1.; 3])
+                .with_param("diffuse", MaterialParam::Color(diffuse))
+                .with_param("specular", specular)
+                .with_param("shininess", shininess)
+                .with_param("camera_position", [0.; 3])
+                .with_param("has_diffuse_map", false),
+        )
+    }
+
+    ///`illum 0` gives an unlit material, `illum 1` a Lambert one and the others a Blinn-Phong one.
+    ///The texture maps are not loaded, see `Material::from_wavefront_textured`
+    pub fn from_wavefront<F: Facade>(
+        facade: &F,
+        material: &WavefrontMaterial,
+    ) -> Result<Self, MaterialError> {
+        Self::from_wavefront_cached(facade, &mut ProgramCache::new(), material)
+    }
+
+    ///the materials of a `.mtl` file can share their programs through `programs`
+    pub fn from_wavefront_cached<F: Facade>(
+        facade: &F,
+        programs: &mut ProgramCache,
+        material: &WavefrontMaterial,
+    ) -> Result<Self, MaterialError> {
+        let [r, g, b] = material.diffuse;
+        let diffuse = [r, g, b, material.opacity];
+
+        let mut result = match material.illumination {
+            0 => Self::unlit_cached(facade, programs, diffuse)?,
+            1 => Self::lambert_cached(facade, programs, diffuse)?
+                .with_param("ambient", material.ambient),
+            _ => Self::blinn_phong_cached(
+                facade,
+                programs,
+                diffuse,
+                material.specular,
+                material.shininess,
+            )?
+            .with_param("ambient", material.ambient),
+        };
+        result.name = material.name.clone();
+
+        if material.opacity < 1. {
+            result = result.with_blending(Blend::alpha_blending());
+            result.draw_parameters.depth.write = false;
+        }
+        Ok(result)
+    }
+
+    ///like `Material::from_wavefront`, the `map_Kd` of the material is loaded as the
+    ///`diffuse_map` of the built-in shaders. `map_Ks` and `map_Bump` are loaded as
+    ///`specular_map` and `normal_map`, for the custom shaders.
+    ///The maps are relative to `mtl_directory`
+    pub fn from_wavefront_textured<F: Facade, P: AsRef<Path>>(
+        facade: &F,
+        material: &WavefrontMaterial,
+        mtl_directory: P,
+        textures: &mut TextureCache,
+    ) -> Result<Self, TextureError> {
+        Self::from_wavefront_textured_cached(
+            facade,
+            &mut ProgramCache::new(),
+            material,
+            mtl_directory,
+            textures,
+        )
+    }
+
+    pub fn from_wavefront_textured_cached<F: Facade, P: AsRef<Path>>(
+        facade: &F,
+        programs: &mut ProgramCache,
+        material: &WavefrontMaterial,
+        mtl_directory: P,
+        textures: &mut TextureCache,
+    ) -> Result<Self, TextureError> {
+        let mut result = Self::from_wavefront_cached(facade, programs, material)
+            .map_err(TextureError::MaterialError)?;
+
+        for (name, map) in [
+            ("diffuse_map", &material.diffuse_map),
+            ("specular_map", &material.specular_map),
+        ] {
+            if let Some(map) = map {
+                let path = resolve_map_path(mtl_directory.as_ref(), map);
+                result.set_param(name, textures.load(facade, path)?);
+            }
+        }
+
+        //the normals are not colors, so the normal map is not in the sRGB color space
+        if let Some(map) = &material.normal_map {
+            let image = ImageData::from_path(resolve_map_path(mtl_directory.as_ref(), map))?;
+            result.set_param("normal_map", Rc::new(linear_texture(facade, &image, true)?));
+        }
+
+        if material.diffuse_map.is_some() {
+            result.set_param("has_diffuse_map", true);
+        }
+        Ok(result)
+    }
+}
+
+impl Material {
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn program(&self) -> &Program {
+        &self.program
+    }
+
+    pub fn instanced_program(&self) -> Option<&Program> {
+        self.instanced_program.as_deref()
+    }
+
+    pub fn with_instanced_program(mut self, program: Rc<Program>) -> Self {
+        self.instanced_program = Some(program);
+        self
+    }
+
+    ///true if the material is blended, the `RenderQueue` draws it after the opaque ones
+    pub fn is_transparent(&self) -> bool {
+        self.draw_parameters.blend.color != BlendingFunction::AlwaysReplace
+            || self.draw_parameters.blend.alpha != BlendingFunction::AlwaysReplace
+    }
+
+    pub fn draw_parameters(&self) -> &DrawParameters<'static> {
+        &self.draw_parameters
+    }
+
+    pub fn draw_parameters_mut(&mut self) -> &mut DrawParameters<'static> {
+        &mut self.draw_parameters
+    }
+
+    pub fn with_draw_parameters(mut self, draw_parameters: DrawParameters<'static>) -> Self {
+        self.draw_parameters = draw_parameters;
+        self
+    }
+
+    pub fn with_blending(mut self, blend: Blend) -> Self {
+        self.draw_parameters.blend = blend;
+        self
+    }
+
+    pub fn with_depth_test(mut self, test: DepthTest, write: bool) -> Self {
+        self.draw_parameters.depth.test = test;
+        self.draw_parameters.depth.write = write;
+        self
+    }
+
+    pub fn with_culling(mut self, culling: BackfaceCullingMode) -> Self {
+        self.draw_parameters.backface_culling = culling;
+        self
+    }
+
+    ///the uniforms of `ENVIRONMENT_GLSL`, the cubemap of the skybox is shared.
+    ///The skybox can also be given as uniforms when drawing
+    pub fn with_environment(mut self, skybox: &Skybox) -> Self {
+        self.set_param("environment_map", skybox.cubemap().clone());
+        self.set_param("environment_intensity", skybox.intensity);
+        for (i, ambient) in skybox.ambient().iter().enumerate() {
+            self.set_param(&format!("environment_ambient_{}", i), *ambient);
+        }
+        self
+    }
+
+    pub fn with_param<P: Into<MaterialParam>>(mut self, name: &str, param: P) -> Self {
+        self.set_param(name, param);
+        self
+    }
+
+    pub fn set_param<P: Into<MaterialParam>>(&mut self, name: &str, param: P) {
+        let param = param.into();
+        match self.params.iter_mut().find(|(n, _)| n == name) {
+            Some((_, old)) => *old = param,
+            None => self.params.push((name.to_string(), param)),
+        }
+    }
+
+    pub fn param(&self, name: &str) -> Option<&MaterialParam> {
+        self.params
+            .iter()
+            .find(|(n, _)| n == name)
+            .map(|(_, param)| param)
+    }
+
+    pub fn remove_param(&mut self, name: &str) -> Option<MaterialParam> {
+        let index = self.params.iter().position(|(n, _)| n == name)?;
+        Some(self.params.remove(index).1)
+    }
+
+    ///render `renderable` with this material, `uniforms` (the camera, the lights...)
+    ///are given after the parameters of the material and replace them
+    pub fn draw<R, F, S, U>(
+        &self,
+        renderable: &R,
+        facade: &F,
+        target: &mut S,
+        uniforms: &U,
+    ) -> Result<(), R::RenderError>
+    where
+        R: Renderable + ?Sized,
+        F: Facade,
+        S: Surface,
+        U: Uniforms,
+    {
+        let uniforms = RenderUniforms::new().with(self).merge(uniforms);
+        renderable.render(
+            facade,
+            &self.program,
+            target,
+            &uniforms,
+            &self.draw_parameters,
+        )
+    }
+}
+
+impl UniformProvider for Material {
+    fn provide_uniforms<'a>(&'a self, uniforms: &mut RenderUniforms<'a>) {
+        for (name, param) in &self.params {
+            uniforms.set(name, param.as_uniform_value());
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum MaterialError {
+    ProgramCreationError(ProgramCreationError),
+}
+
+impl Display for MaterialError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MaterialError::ProgramCreationError(e) => write!(f, "program creation error :{}", e),
+        }
+    }
+}
+
+impl std::error::Error for MaterialError {}
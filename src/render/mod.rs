@@ -0,0 +1,28 @@
+mod debug_draw;
+mod light;
+mod material;
+mod post_process;
+mod queue;
+mod shaders;
+mod shadow;
+mod skybox;
+mod target;
+mod text;
+mod texture;
+mod uniforms;
+
+#[cfg(test)]
+mod test;
+
+pub use debug_draw::*;
+pub use light::*;
+pub use material::*;
+pub use post_process::*;
+pub use queue::*;
+pub use shaders::*;
+pub use shadow::*;
+pub use skybox::*;
+pub use target::*;
+pub use text::*;
+pub use texture::*;
+pub use uniforms::*;
@@ -0,0 +1,458 @@
+use glium::{
+    backend::{glutin::Display, Facade},
+    draw_parameters::DepthTest,
+    framebuffer::SimpleFrameBuffer,
+    index::{NoIndices, PrimitiveType},
+    uniforms::{
+        MagnifySamplerFilter, MinifySamplerFilter, SamplerBehavior, SamplerWrapFunction,
+        UniformValue,
+    },
+    vertex::EmptyVertexAttributes,
+    DrawError, Surface, Texture2d,
+};
+use glutin::surface::WindowSurface;
+
+use crate::app::Graphics;
+
+use super::{Material, MaterialError, RenderTarget, RenderTargetError, RenderUniforms};
+
+/******************\
+*                 *
+*----PostEffect---*
+*                 *
+\******************/
+
+///the built-in full screen passes.
+///Each pass reads the output of the previous one as `source`, and the scene as `scene`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PostEffect {
+    ///copy `source`
+    Copy,
+    ///Reinhard tone mapping and gamma correction, with an `exposure` parameter
+    Tonemap,
+    ///fast approximate anti-aliasing
+    Fxaa,
+    ///keep the pixels brighter than `threshold`
+    BloomThreshold,
+    ///gaussian blur along `direction` (`[1, 0]` or `[0, 1]`)
+    Blur,
+    ///add `source` to `scene`, scaled by `intensity`
+    BloomCombine,
+}
+
+impl PostEffect {
+    pub fn fragment_source(&self) -> &'static str {
+        match self {
+            PostEffect::Copy => COPY_FRAGMENT_SHADER,
+            PostEffect::Tonemap => TONEMAP_FRAGMENT_SHADER,
+            PostEffect::Fxaa => FXAA_FRAGMENT_SHADER,
+            PostEffect::BloomThreshold => BLOOM_THRESHOLD_FRAGMENT_SHADER,
+            PostEffect::Blur => BLUR_FRAGMENT_SHADER,
+            PostEffect::BloomCombine => BLOOM_COMBINE_FRAGMENT_SHADER,
+        }
+    }
+
+    ///a pass with the default parameters of the effect
+    pub fn pass<F: Facade>(&self, facade: &F) -> Result<Material, MaterialError> {
+        let pass = post_process_pass(facade, &format!("{:?}", self), self.fragment_source())?;
+        Ok(match self {
+            PostEffect::Tonemap => pass.with_param("exposure", 1.),
+            PostEffect::BloomThreshold => pass.with_param("threshold", 1.),
+            PostEffect::Blur => pass.with_param("direction", [1., 0.]),
+            PostEffect::BloomCombine => pass.with_param("intensity", 0.6),
+            PostEffect::Copy | PostEffect::Fxaa => pass,
+        })
+    }
+}
+
+///a full screen pass running `fragment_shader`, without depth test.
+///It gets the `source`, `scene`, `texel_size` and `resolution` uniforms
+pub fn post_process_pass<F: Facade>(
+    facade: &F,
+    name: &str,
+    fragment_shader: &str,
+) -> Result<Material, MaterialError> {
+    Ok(
+        Material::from_source(facade, name, FULLSCREEN_VERTEX_SHADER, fragment_shader)?
+            .with_depth_test(DepthTest::Overwrite, false),
+    )
+}
+
+/******************\
+*                 *
+*-PostProcessChain*
+*                 *
+\******************/
+
+///where a pass of the chain draws
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum PassOutput {
+    Ping,
+    Pong,
+    Frame,
+}
+
+///the passes draw into the two intermediate targets in turn, the last one draws into the frame
+pub(crate) fn pass_outputs(pass_count: usize) -> impl Iterator<Item = PassOutput> {
+    (0..pass_count).map(move |i| match i {
+        i if i + 1 == pass_count => PassOutput::Frame,
+        i if i % 2 == 0 => PassOutput::Ping,
+        _ => PassOutput::Pong,
+    })
+}
+
+///the scene is drawn into an offscreen HDR target, then the passes run one after the other.
+///The last pass draws into the window `Frame`, the scene is only copied without passes
+pub struct PostProcessChain {
+    scene: RenderTarget,
+    ping: RenderTarget,
+    pong: RenderTarget,
+    passes: Vec<Material>,
+    copy: Material,
+}
+
+impl PostProcessChain {
+    pub fn new<F: Facade>(facade: &F, width: u32, height: u32) -> Result<Self, RenderTargetError> {
+        let target = |depth| {
+            RenderTarget::with_format(
+                facade,
+                width,
+                height,
+                glium::texture::UncompressedFloatFormat::F16F16F16F16,
+                depth,
+            )
+        };
+
+        Ok(Self {
+            scene: target(true)?,
+            ping: target(false)?,
+            pong: target(false)?,
+            passes: vec![],
+            copy: PostEffect::Copy
+                .pass(facade)
+                .map_err(RenderTargetError::MaterialError)?,
+        })
+    }
+
+    pub fn with_pass(mut self, pass: Material) -> Self {
+        self.push_pass(pass);
+        self
+    }
+
+    pub fn push_pass(&mut self, pass: Material) {
+        self.passes.push(pass);
+    }
+
+    ///threshold, horizontal and vertical blur then combine
+    pub fn with_bloom<F: Facade>(self, facade: &F) -> Result<Self, RenderTargetError> {
+        let pass = |effect: PostEffect| {
+            effect
+                .pass(facade)
+                .map_err(RenderTargetError::MaterialError)
+        };
+        Ok(self
+            .with_pass(pass(PostEffect::BloomThreshold)?)
+            .with_pass(pass(PostEffect::Blur)?)
+            .with_pass(pass(PostEffect::Blur)?.with_param("direction", [0., 1.]))
+            .with_pass(pass(PostEffect::BloomCombine)?))
+    }
+
+    pub fn passes(&self) -> &[Material] {
+        &self.passes
+    }
+
+    ///to change the parameters of the passes, like the exposure
+    pub fn passes_mut(&mut self) -> &mut Vec<Material> {
+        &mut self.passes
+    }
+
+    ///the target the scene is drawn into
+    pub fn scene_target(&self) -> &RenderTarget {
+        &self.scene
+    }
+
+    pub fn resize<F: Facade>(
+        &mut self,
+        facade: &F,
+        width: u32,
+        height: u32,
+    ) -> Result<(), RenderTargetError> {
+        self.scene.resize(facade, width, height)?;
+        self.ping.resize(facade, width, height)?;
+        self.pong.resize(facade, width, height)?;
+        Ok(())
+    }
+
+    ///run the passes on the scene target, the output is drawn into `frame`
+    pub fn apply<F: Facade, S: Surface>(
+        &self,
+        facade: &F,
+        frame: &mut S,
+    ) -> Result<(), RenderTargetError> {
+        let passes = match self.passes.is_empty() {
+            true => std::slice::from_ref(&self.copy),
+            false => self.passes.as_slice(),
+        };
+
+        let mut source = self.scene.color();
+        for (pass, output) in passes.iter().zip(pass_outputs(passes.len())) {
+            match output {
+                PassOutput::Frame => self.draw_pass(pass, source, frame)?,
+                PassOutput::Ping | PassOutput::Pong => {
+                    let target = match output {
+                        PassOutput::Ping => &self.ping,
+                        _ => &self.pong,
+                    };
+                    self.draw_pass(pass, source, &mut target.framebuffer(facade)?)?;
+                    source = target.color();
+                }
+            }
+        }
+        Ok(())
+    }
+
+    ///the hook for `Scene::draw_frame`: the targets follow the dimensions of `graphics`,
+    ///`draw_scene` draws into the scene target then the passes draw into the frame.
+    ///Nothing is drawn by the graphics without display
+    pub fn render<D>(
+        &mut self,
+        graphics: &mut dyn Graphics,
+        mut draw_scene: D,
+    ) -> Result<(), RenderTargetError>
+    where
+        D: FnMut(&Display<WindowSurface>, &mut SimpleFrameBuffer) -> Result<(), DrawError>,
+    {
+        let display = graphics.display().cloned();
+        if let Some(display) = &display {
+            let (width, height) = graphics.dimensions();
+            self.resize(display, width, height)?;
+        }
+
+        let mut error = None;
+        let this = &*self;
+        graphics
+            .draw("post_process", &mut |frame| {
+                let Some(display) = &display else {
+                    return Ok(());
+                };
+
+                let result = this.scene.framebuffer(display).and_then(|mut target| {
+                    target.clear_color_and_depth((0., 0., 0., 1.), 1.);
+                    draw_scene(display, &mut target).map_err(RenderTargetError::DrawError)
+                });
+                match result.and_then(|_| this.apply(display, frame)) {
+                    Err(RenderTargetError::DrawError(e)) => Err(e),
+                    Err(e) => {
+                        error = Some(e);
+                        Ok(())
+                    }
+                    Ok(()) => Ok(()),
+                }
+            })
+            .map_err(RenderTargetError::DrawError)?;
+
+        match error {
+            Some(e) => Err(e),
+            None => Ok(()),
+        }
+    }
+
+    fn draw_pass<S: Surface>(
+        &self,
+        pass: &Material,
+        source: &Texture2d,
+        target: &mut S,
+    ) -> Result<(), RenderTargetError> {
+        let (width, height) = source.dimensions();
+        let sampler = SamplerBehavior {
+            wrap_function: (
+                SamplerWrapFunction::Clamp,
+                SamplerWrapFunction::Clamp,
+                SamplerWrapFunction::Clamp,
+            ),
+            minify_filter: MinifySamplerFilter::Linear,
+            magnify_filter: MagnifySamplerFilter::Linear,
+            ..Default::default()
+        };
+
+        let mut uniforms = RenderUniforms::new().with(pass);
+        uniforms.set("source", UniformValue::Texture2d(source, Some(sampler)));
+        uniforms.set(
+            "scene",
+            UniformValue::Texture2d(self.scene.color(), Some(sampler)),
+        );
+        uniforms.set(
+            "texel_size",
+            UniformValue::Vec2([1. / width as f32, 1. / height as f32]),
+        );
+        uniforms.set(
+            "resolution",
+            UniformValue::Vec2([width as f32, height as f32]),
+        );
+
+        target
+            .draw(
+                EmptyVertexAttributes { len: 3 },
+                NoIndices(PrimitiveType::TrianglesList),
+                pass.program(),
+                &uniforms,
+                pass.draw_parameters(),
+            )
+            .map_err(RenderTargetError::DrawError)
+    }
+}
+
+/******************\
+*                 *
+*-----Shaders-----*
+*                 *
+\******************/
+
+///a triangle covering the screen, without vertex buffer
+pub(crate) const FULLSCREEN_VERTEX_SHADER: &str = r#"
+#version 140
+
+out vec2 v_tex_coords;
+
+void main() {
+    vec2 position = vec2((gl_VertexID << 1) & 2, gl_VertexID & 2);
+    v_tex_coords = position;
+    gl_Position = vec4(position * 2.0 - 1.0, 0.0, 1.0);
+}
+"#;
+
+const COPY_FRAGMENT_SHADER: &str = r#"
+#version 140
+
+in vec2 v_tex_coords;
+
+uniform sampler2D source;
+
+out vec4 f_color;
+
+void main() {
+    f_color = texture(source, v_tex_coords);
+}
+"#;
+
+const TONEMAP_FRAGMENT_SHADER: &str = r#"
+#version 140
+
+in vec2 v_tex_coords;
+
+uniform sampler2D source;
+uniform float exposure;
+
+out vec4 f_color;
+
+void main() {
+    vec3 color = texture(source, v_tex_coords).rgb * exposure;
+    color = color / (color + vec3(1.0));
+    f_color = vec4(pow(color, vec3(1.0 / 2.2)), 1.0);
+}
+"#;
+
+const FXAA_FRAGMENT_SHADER: &str = r#"
+#version 140
+
+in vec2 v_tex_coords;
+
+uniform sampler2D source;
+uniform vec2 texel_size;
+
+out vec4 f_color;
+
+float luma(vec3 color) {
+    return dot(color, vec3(0.299, 0.587, 0.114));
+}
+
+void main() {
+    vec3 center = texture(source, v_tex_coords).rgb;
+    float luma_nw = luma(texture(source, v_tex_coords + vec2(-1.0, -1.0) * texel_size).rgb);
+    float luma_ne = luma(texture(source, v_tex_coords + vec2(1.0, -1.0) * texel_size).rgb);
+    float luma_sw = luma(texture(source, v_tex_coords + vec2(-1.0, 1.0) * texel_size).rgb);
+    float luma_se = luma(texture(source, v_tex_coords + vec2(1.0, 1.0) * texel_size).rgb);
+    float luma_m = luma(center);
+
+    float luma_min = min(luma_m, min(min(luma_nw, luma_ne), min(luma_sw, luma_se)));
+    float luma_max = max(luma_m, max(max(luma_nw, luma_ne), max(luma_sw, luma_se)));
+
+    vec2 direction = vec2(
+        -((luma_nw + luma_ne) - (luma_sw + luma_se)),
+        (luma_nw + luma_sw) - (luma_ne + luma_se)
+    );
+    float reduce = max((luma_nw + luma_ne + luma_sw + luma_se) * 0.03125, 1.0 / 128.0);
+    float scale = 1.0 / (min(abs(direction.x), abs(direction.y)) + reduce);
+    direction = clamp(direction * scale, vec2(-8.0), vec2(8.0)) * texel_size;
+
+    vec3 color_a = 0.5 * (
+        texture(source, v_tex_coords + direction * (1.0 / 3.0 - 0.5)).rgb
+        + texture(source, v_tex_coords + direction * (2.0 / 3.0 - 0.5)).rgb
+    );
+    vec3 color_b = color_a * 0.5 + 0.25 * (
+        texture(source, v_tex_coords - direction * 0.5).rgb
+        + texture(source, v_tex_coords + direction * 0.5).rgb
+    );
+
+    float luma_b = luma(color_b);
+    f_color = vec4(luma_b < luma_min || luma_b > luma_max ? color_a : color_b, 1.0);
+}
+"#;
+
+const BLOOM_THRESHOLD_FRAGMENT_SHADER: &str = r#"
+#version 140
+
+in vec2 v_tex_coords;
+
+uniform sampler2D source;
+uniform float threshold;
+
+out vec4 f_color;
+
+void main() {
+    vec3 color = texture(source, v_tex_coords).rgb;
+    float brightness = max(color.r, max(color.g, color.b));
+    f_color = vec4(brightness > threshold ? color : vec3(0.0), 1.0);
+}
+"#;
+
+const BLUR_FRAGMENT_SHADER: &str = r#"
+#version 140
+
+in vec2 v_tex_coords;
+
+uniform sampler2D source;
+uniform vec2 texel_size;
+uniform vec2 direction;
+
+out vec4 f_color;
+
+const float WEIGHTS[5] = float[](0.227027, 0.1945946, 0.1216216, 0.054054, 0.016216);
+
+void main() {
+    vec2 step = direction * texel_size;
+    vec3 color = texture(source, v_tex_coords).rgb * WEIGHTS[0];
+    for (int i = 1; i < 5; i++) {
+        color += texture(source, v_tex_coords + step * float(i)).rgb * WEIGHTS[i];
+        color += texture(source, v_tex_coords - step * float(i)).rgb * WEIGHTS[i];
+    }
+    f_color = vec4(color, 1.0);
+}
+"#;
+
+const BLOOM_COMBINE_FRAGMENT_SHADER: &str = r#"
+#version 140
+
+in vec2 v_tex_coords;
+
+uniform sampler2D source;
+uniform sampler2D scene;
+uniform float intensity;
+
+out vec4 f_color;
+
+void main() {
+    vec3 color = texture(scene, v_tex_coords).rgb + texture(source, v_tex_coords).rgb * intensity;
+    f_color = vec4(color, 1.0);
+}
+"#;
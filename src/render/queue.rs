@@ -0,0 +1,337 @@
+use std::{error::Error, fmt::Display, rc::Rc};
+
+use glium::{
+    backend::{Context, Facade},
+    uniforms::Uniforms,
+    DrawParameters, Frame, Program, Surface, VertexBuffer,
+};
+
+use crate::{
+    camera::Camera,
+    mesh::{instanced::InstanceData, Mesh, MeshRenderError},
+    object_traits::{GetableSceneObject, Renderable},
+    utils::types_util::Arr3F32,
+};
+
+use super::{Material, ModelTransform, RenderUniforms};
+
+type DrawFn<'a, S> = Box<
+    dyn Fn(
+            &Rc<Context>,
+            &Program,
+            &mut S,
+            &RenderUniforms,
+            &DrawParameters,
+        ) -> Result<(), RenderQueueError>
+        + 'a,
+>;
+
+/******************\
+*                 *
+*---RenderStats---*
+*                 *
+\******************/
+
+///what the last `RenderQueue::flush` did
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct RenderStats {
+    pub draw_calls: usize,
+    ///only counted for the meshes and the draws submitted with a triangle count
+    pub triangles: usize,
+    ///the number of times the program or the material changed between two draw calls
+    pub state_switches: usize,
+    ///the number of instanced draw calls made from several meshes
+    pub instanced_batches: usize,
+}
+
+/******************\
+*                 *
+*---RenderQueue---*
+*                 *
+\******************/
+
+enum DrawSource<'a, S> {
+    Mesh(&'a Mesh),
+    Renderable(DrawFn<'a, S>),
+}
+
+struct QueuedDraw<'a, S> {
+    material: &'a Material,
+    position: Arr3F32,
+    triangles: usize,
+    buffer: usize,
+    source: DrawSource<'a, S>,
+}
+
+///the draws of a frame, sorted and batched when the queue is flushed.
+///The opaque draws are sorted by program, material and buffer, then the meshes sharing their
+///geometry and their material are merged into instanced draws.
+///The transparent draws are drawn last, from back to front
+pub struct RenderQueue<'a, S: Surface = Frame> {
+    draws: Vec<QueuedDraw<'a, S>>,
+    stats: RenderStats,
+}
+
+impl<S: Surface> Default for RenderQueue<'_, S> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<'a, S: Surface> RenderQueue<'a, S> {
+    pub fn new() -> Self {
+        Self {
+            draws: vec![],
+            stats: RenderStats::default(),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.draws.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.draws.is_empty()
+    }
+
+    ///the stats of the last flush
+    pub fn stats(&self) -> RenderStats {
+        self.stats
+    }
+
+    pub fn clear(&mut self) {
+        self.draws.clear();
+    }
+
+    ///a mesh can be batched with the other meshes sharing its geometry, see `Mesh::share`
+    pub fn submit_mesh(&mut self, mesh: &'a Mesh, material: &'a Material) {
+        self.draws.push(QueuedDraw {
+            material,
+            position: mesh.get_position().into(),
+            triangles: mesh.triangle_count(),
+            buffer: mesh.geometry_key(),
+            source: DrawSource::Mesh(mesh),
+        });
+    }
+
+    ///any renderable, drawn on its own
+    pub fn submit<R>(&mut self, renderable: &'a R, material: &'a Material)
+    where
+        R: Renderable + GetableSceneObject,
+        R::RenderError: Error + 'static,
+    {
+        self.submit_with_triangles(renderable, material, 0);
+    }
+
+    pub fn submit_with_triangles<R>(
+        &mut self,
+        renderable: &'a R,
+        material: &'a Material,
+        triangles: usize,
+    ) where
+        R: Renderable + GetableSceneObject,
+        R::RenderError: Error + 'static,
+    {
+        let draw: DrawFn<'a, S> =
+            Box::new(move |facade, program, target, uniforms, draw_parameters| {
+                renderable
+                    .render(facade, program, target, uniforms, draw_parameters)
+                    .map_err(|e| RenderQueueError::RenderError(Box::new(e)))
+            });
+
+        self.draws.push(QueuedDraw {
+            material,
+            position: renderable.get_position().into(),
+            triangles,
+            buffer: renderable as *const R as *const () as usize,
+            source: DrawSource::Renderable(draw),
+        });
+    }
+
+    ///draw everything and empty the queue, `uniforms` (the camera, the lights...) are given
+    ///to every draw after the parameters of the materials.
+    ///`camera` is used to sort the transparent draws
+    pub fn flush<F, C, U>(
+        &mut self,
+        facade: &F,
+        target: &mut S,
+        camera: &C,
+        uniforms: &U,
+    ) -> Result<RenderStats, RenderQueueError>
+    where
+        F: Facade,
+        C: Camera + ?Sized,
+        U: Uniforms,
+    {
+        let view: [[f32; 4]; 4] = camera.view_matrix().into();
+        let keys: Vec<_> = self
+            .draws
+            .iter()
+            .map(|draw| DrawKey {
+                program: draw.material.program() as *const Program as usize,
+                material: draw.material as *const Material as usize,
+                buffer: draw.buffer,
+                transparent: draw.material.is_transparent(),
+                batchable: matches!(draw.source, DrawSource::Mesh(_))
+                    && draw.material.instanced_program().is_some(),
+                depth: view_depth(&view, draw.position),
+            })
+            .collect();
+
+        let draws = std::mem::take(&mut self.draws);
+        let facade = facade.get_context();
+        let mut stats = RenderStats::default();
+        let mut last_state = None;
+
+        for step in plan_draws(&keys) {
+            let first = &draws[step[0]];
+            let state = (
+                keys[step[0]].program,
+                keys[step[0]].material,
+                step.len() > 1,
+            );
+            if last_state.is_some_and(|last| last != state) {
+                stats.state_switches += 1;
+            }
+            last_state = Some(state);
+
+            let uniforms = RenderUniforms::new().with(first.material).merge(uniforms);
+            let draw_parameters = first.material.draw_parameters();
+
+            if step.len() > 1 {
+                let meshes: Vec<_> = step
+                    .iter()
+                    .filter_map(|i| match draws[*i].source {
+                        DrawSource::Mesh(mesh) => Some(mesh),
+                        DrawSource::Renderable(_) => None,
+                    })
+                    .collect();
+                draw_batch(facade, first.material, target, &meshes, &uniforms)?;
+                stats.instanced_batches += 1;
+            } else {
+                let program = first.material.program();
+                match &first.source {
+                    DrawSource::Mesh(mesh) => mesh
+                        .render(facade, program, target, &uniforms, draw_parameters)
+                        .map_err(RenderQueueError::MeshRenderError)?,
+                    DrawSource::Renderable(draw) => {
+                        draw(facade, program, target, &uniforms, draw_parameters)?
+                    }
+                }
+            }
+
+            stats.draw_calls += 1;
+            stats.triangles += step.iter().map(|i| draws[*i].triangles).sum::<usize>();
+        }
+
+        self.stats = stats;
+        Ok(stats)
+    }
+}
+
+fn draw_batch<S: Surface>(
+    facade: &Rc<Context>,
+    material: &Material,
+    target: &mut S,
+    meshes: &[&Mesh],
+    uniforms: &RenderUniforms,
+) -> Result<(), RenderQueueError> {
+    let program = material
+        .instanced_program()
+        .expect("only the materials with an instanced program are batched");
+    let instances: Vec<_> = meshes
+        .iter()
+        .map(|mesh| InstanceData {
+            instance_model: ModelTransform::of(*mesh).model,
+            instance_color: [1.; 4],
+            instance_data: [0.; 4],
+        })
+        .collect();
+
+    let instance_buffer = VertexBuffer::new(facade, &instances).map_err(|e| {
+        RenderQueueError::MeshRenderError(MeshRenderError::VertexBufferCreationError(e))
+    })?;
+    let per_instance = instance_buffer
+        .per_instance()
+        .map_err(|_| RenderQueueError::MeshRenderError(MeshRenderError::InstancingNotSupported))?;
+
+    meshes[0]
+        .gpu_mesh(facade)
+        .and_then(|gpu_mesh| {
+            gpu_mesh.draw_instanced(
+                target,
+                per_instance,
+                program,
+                uniforms,
+                material.draw_parameters(),
+            )
+        })
+        .map_err(RenderQueueError::MeshRenderError)
+}
+
+/******************\
+*                 *
+*-----Sorting-----*
+*                 *
+\******************/
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct DrawKey {
+    pub(crate) program: usize,
+    pub(crate) material: usize,
+    pub(crate) buffer: usize,
+    pub(crate) transparent: bool,
+    ///only the meshes with an instanced material can be merged
+    pub(crate) batchable: bool,
+    ///the z coordinate in the view space, the farthest draws have the lowest depth
+    pub(crate) depth: f32,
+}
+
+///the indices of the draws, in the order they are drawn, merged in batches
+pub(crate) fn plan_draws(keys: &[DrawKey]) -> Vec<Vec<usize>> {
+    let (mut opaque, mut transparent): (Vec<_>, Vec<_>) =
+        (0..keys.len()).partition(|i| !keys[*i].transparent);
+
+    opaque.sort_by_key(|i| (keys[*i].program, keys[*i].material, keys[*i].buffer));
+    transparent.sort_by(|a, b| keys[*a].depth.total_cmp(&keys[*b].depth));
+
+    let mut steps: Vec<Vec<usize>> = vec![];
+    for i in opaque {
+        let key = &keys[i];
+        match steps.last_mut() {
+            Some(step)
+                if key.batchable
+                    && keys[step[0]].batchable
+                    && keys[step[0]].material == key.material
+                    && keys[step[0]].buffer == key.buffer =>
+            {
+                step.push(i)
+            }
+            _ => steps.push(vec![i]),
+        }
+    }
+    steps.extend(transparent.into_iter().map(|i| vec![i]));
+    steps
+}
+
+///`view` is column major
+pub(crate) fn view_depth(view: &[[f32; 4]; 4], position: Arr3F32) -> f32 {
+    view[0][2] * position[0] + view[1][2] * position[1] + view[2][2] * position[2] + view[3][2]
+}
+
+#[derive(Debug)]
+pub enum RenderQueueError {
+    MeshRenderError(MeshRenderError),
+    RenderError(Box<dyn Error>),
+}
+
+impl Display for RenderQueueError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RenderQueueError::MeshRenderError(e) => write!(f, "mesh render error :{}", e),
+            RenderQueueError::RenderError(e) => write!(f, "render error :{}", e),
+        }
+    }
+}
+
+impl std::error::Error for RenderQueueError {}
@@ -0,0 +1,350 @@
+//the built-in shaders, their attributes match `Vertex`
+
+use std::{collections::HashMap, rc::Rc};
+
+use glium::{backend::Facade, Program};
+
+use super::{shadow::shadow_glsl, MaterialError};
+
+///a material of the built-in shaders.
+///The shadowed shaders take the parameters of their unshadowed shader, and sample the
+///`ShadowMap` for the light at `ShadowSettings::light_index`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum BuiltinShader {
+    ///a constant `color`
+    Unlit,
+    ///diffuse lighting
+    Lambert,
+    ///diffuse lighting and specular highlights
+    BlinnPhong,
+    ///`Lambert`, a `ShadowMap` has to be given when drawing
+    ShadowedLambert,
+    ///`BlinnPhong`, a `ShadowMap` has to be given when drawing
+    ShadowedBlinnPhong,
+}
+
+impl BuiltinShader {
+    pub fn vertex_source(&self) -> &'static str {
+        VERTEX_SHADER
+    }
+
+    ///the model matrix and the color come from the `InstanceData` attributes
+    pub fn instanced_vertex_source(&self) -> &'static str {
+        INSTANCED_VERTEX_SHADER
+    }
+
+    pub fn fragment_source(&self) -> &'static str {
+        match self {
+            BuiltinShader::Unlit => UNLIT_FRAGMENT_SHADER,
+            BuiltinShader::Lambert => LAMBERT_FRAGMENT_SHADER,
+            BuiltinShader::BlinnPhong => BLINN_PHONG_FRAGMENT_SHADER,
+            BuiltinShader::ShadowedLambert => SHADOWED_LAMBERT_FRAGMENT_SHADER,
+            BuiltinShader::ShadowedBlinnPhong => SHADOWED_BLINN_PHONG_FRAGMENT_SHADER,
+        }
+    }
+}
+
+///the compiled built-in shaders, so the materials of a shader share its programs,
+///see the `_cached` constructors of `Material`.
+///The programs belong to the context of the facade they were compiled with
+#[derive(Default)]
+pub struct ProgramCache {
+    programs: HashMap<BuiltinShader, Rc<Program>>,
+    instanced_programs: HashMap<BuiltinShader, Rc<Program>>,
+}
+
+impl ProgramCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn program<F: Facade>(
+        &mut self,
+        facade: &F,
+        shader: BuiltinShader,
+    ) -> Result<Rc<Program>, MaterialError> {
+        Self::load(&mut self.programs, facade, shader, shader.vertex_source())
+    }
+
+    ///the program reading the `InstanceData` attributes
+    pub fn instanced_program<F: Facade>(
+        &mut self,
+        facade: &F,
+        shader: BuiltinShader,
+    ) -> Result<Rc<Program>, MaterialError> {
+        Self::load(
+            &mut self.instanced_programs,
+            facade,
+            shader,
+            shader.instanced_vertex_source(),
+        )
+    }
+
+    ///the number of compiled programs
+    pub fn len(&self) -> usize {
+        self.programs.len() + self.instanced_programs.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn clear(&mut self) {
+        self.programs.clear();
+        self.instanced_programs.clear();
+    }
+
+    fn load<F: Facade>(
+        programs: &mut HashMap<BuiltinShader, Rc<Program>>,
+        facade: &F,
+        shader: BuiltinShader,
+        vertex_source: &str,
+    ) -> Result<Rc<Program>, MaterialError> {
+        if let Some(program) = programs.get(&shader) {
+            return Ok(program.clone());
+        }
+
+        let program = Program::from_source(facade, vertex_source, shader.fragment_source(), None)
+            .map_err(MaterialError::ProgramCreationError)?;
+        let program = Rc::new(program);
+        programs.insert(shader, program.clone());
+        Ok(program)
+    }
+}
+
+pub(crate) const VERTEX_SHADER: &str = r#"
+#version 140
+
+in vec4 position;
+in vec3 normal;
+in vec3 texture;
+
+uniform mat4 model;
+uniform mat4 view_projection;
+
+out vec3 v_position;
+out vec3 v_normal;
+out vec2 v_tex_coords;
+out vec4 v_color;
+
+void main() {
+    vec4 world_position = model * position;
+    v_position = world_position.xyz / world_position.w;
+    v_normal = mat3(transpose(inverse(model))) * normal;
+    v_tex_coords = texture.xy;
+    v_color = vec4(1.0);
+    gl_Position = view_projection * world_position;
+}
+"#;
+
+pub(crate) const INSTANCED_VERTEX_SHADER: &str = r#"
+#version 140
+
+in vec4 position;
+in vec3 normal;
+in vec3 texture;
+
+in mat4 instance_model;
+in vec4 instance_color;
+in vec4 instance_data;
+
+uniform mat4 view_projection;
+
+out vec3 v_position;
+out vec3 v_normal;
+out vec2 v_tex_coords;
+out vec4 v_color;
+out vec4 v_data;
+
+void main() {
+    vec4 world_position = instance_model * position;
+    v_position = world_position.xyz / world_position.w;
+    v_normal = mat3(transpose(inverse(instance_model))) * normal;
+    v_tex_coords = texture.xy;
+    v_color = instance_color;
+    v_data = instance_data;
+    gl_Position = view_projection * world_position;
+}
+"#;
+
+pub(crate) const UNLIT_FRAGMENT_SHADER: &str = r#"
+#version 140
+
+in vec2 v_tex_coords;
+in vec4 v_color;
+
+uniform vec4 color;
+uniform sampler2D diffuse_map;
+uniform bool has_diffuse_map;
+
+out vec4 f_color;
+
+void main() {
+    vec4 texel = has_diffuse_map ? texture(diffuse_map, v_tex_coords) : vec4(1.0);
+    f_color = color * v_color * texel;
+}
+"#;
+
+///the `Lights` uniform block and the `light_radiance` function, see `LightBlock`
+macro_rules! lights_glsl {
+    () => {
+        r#"
+#define MAX_LIGHTS 16
+
+struct Light {
+    vec4 position;
+    vec4 direction;
+    vec4 color;
+    vec4 attenuation;
+    vec4 cone;
+};
+
+layout(std140) uniform Lights {
+    Light lights[MAX_LIGHTS];
+    vec4 ambient_light;
+    int light_count;
+};
+
+//the light reaching `position`, `to_light` is the direction from `position` to the light
+vec3 light_radiance(Light light, vec3 position, out vec3 to_light) {
+    if (light.position.w == 0.0) {
+        to_light = -normalize(light.direction.xyz);
+        return light.color.rgb;
+    }
+
+    vec3 offset = light.position.xyz - position;
+    float distance = length(offset);
+    to_light = offset / max(distance, 0.0001);
+    if (light.attenuation.w > 0.0 && distance > light.attenuation.w) {
+        return vec3(0.0);
+    }
+
+    float attenuation = 1.0 / (light.attenuation.x
+        + light.attenuation.y * distance
+        + light.attenuation.z * distance * distance);
+    if (light.position.w == 2.0) {
+        float cos_angle = dot(-to_light, normalize(light.direction.xyz));
+        float cone = max(light.cone.x - light.cone.y, 0.0001);
+        attenuation *= clamp((cos_angle - light.cone.y) / cone, 0.0, 1.0);
+    }
+    return light.color.rgb * attenuation;
+}
+"#
+    };
+}
+
+///the body of the Lambert shaders, `SHADOWED` applies `shadow_factor` to `shadow_light`
+macro_rules! lambert_glsl {
+    () => {
+        r#"
+in vec3 v_position;
+in vec3 v_normal;
+in vec2 v_tex_coords;
+in vec4 v_color;
+
+uniform vec3 ambient;
+uniform vec4 diffuse;
+uniform sampler2D diffuse_map;
+uniform bool has_diffuse_map;
+
+out vec4 f_color;
+
+void main() {
+    vec3 normal = normalize(v_normal);
+    vec4 albedo = diffuse * v_color;
+    if (has_diffuse_map) {
+        albedo *= texture(diffuse_map, v_tex_coords);
+    }
+
+#ifdef SHADOWED
+    float shadow = shadow_factor(v_position, shadow_view_depth(v_position));
+#endif
+
+    vec3 color = ambient * ambient_light.rgb * albedo.rgb;
+    for (int i = 0; i < light_count && i < MAX_LIGHTS; i++) {
+        vec3 to_light;
+        vec3 radiance = light_radiance(lights[i], v_position, to_light);
+#ifdef SHADOWED
+        if (i == shadow_light) {
+            radiance *= shadow;
+        }
+#endif
+        color += max(dot(normal, to_light), 0.0) * radiance * albedo.rgb;
+    }
+    f_color = vec4(color, albedo.a);
+}
+"#
+    };
+}
+
+pub(crate) const LAMBERT_FRAGMENT_SHADER: &str =
+    concat!("#version 140\n", lights_glsl!(), lambert_glsl!());
+
+pub(crate) const SHADOWED_LAMBERT_FRAGMENT_SHADER: &str = concat!(
+    "#version 140\n#define SHADOWED\n",
+    lights_glsl!(),
+    shadow_glsl!(),
+    lambert_glsl!()
+);
+
+///the body of the Blinn-Phong shaders, `SHADOWED` applies `shadow_factor` to `shadow_light`
+macro_rules! blinn_phong_glsl {
+    () => {
+        r#"
+in vec3 v_position;
+in vec3 v_normal;
+in vec2 v_tex_coords;
+in vec4 v_color;
+
+uniform vec3 ambient;
+uniform vec4 diffuse;
+uniform sampler2D diffuse_map;
+uniform bool has_diffuse_map;
+uniform vec3 specular;
+uniform float shininess;
+uniform vec3 camera_position;
+
+out vec4 f_color;
+
+void main() {
+    vec3 normal = normalize(v_normal);
+    vec4 albedo = diffuse * v_color;
+    if (has_diffuse_map) {
+        albedo *= texture(diffuse_map, v_tex_coords);
+    }
+    vec3 to_camera = normalize(camera_position - v_position);
+#ifdef SHADOWED
+    float shadow = shadow_factor(v_position, shadow_view_depth(v_position));
+#endif
+
+    vec3 color = ambient * ambient_light.rgb * albedo.rgb;
+    for (int i = 0; i < light_count && i < MAX_LIGHTS; i++) {
+        vec3 to_light;
+        vec3 radiance = light_radiance(lights[i], v_position, to_light);
+#ifdef SHADOWED
+        if (i == shadow_light) {
+            radiance *= shadow;
+        }
+#endif
+
+        float lambert = max(dot(normal, to_light), 0.0);
+        vec3 half_direction = normalize(to_light + to_camera);
+        float highlight = lambert > 0.0 ? pow(max(dot(normal, half_direction), 0.0), shininess) : 0.0;
+
+        color += lambert * radiance * albedo.rgb + highlight * radiance * specular;
+    }
+    f_color = vec4(color, albedo.a);
+}
+"#
+    };
+}
+
+pub(crate) const BLINN_PHONG_FRAGMENT_SHADER: &str =
+    concat!("#version 140\n", lights_glsl!(), blinn_phong_glsl!());
+
+pub(crate) const SHADOWED_BLINN_PHONG_FRAGMENT_SHADER: &str = concat!(
+    "#version 140\n#define SHADOWED\n",
+    lights_glsl!(),
+    shadow_glsl!(),
+    blinn_phong_glsl!()
+);
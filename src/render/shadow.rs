@@ -0,0 +1,535 @@
+use std::fmt::Display;
+
+use glium::{
+    backend::Facade,
+    draw_parameters::{DepthTest, PolygonOffset},
+    framebuffer::{SimpleFrameBuffer, ValidationError},
+    texture::{DepthTexture2d, TextureCreationError},
+    uniforms::{
+        DepthTextureComparison, MagnifySamplerFilter, MinifySamplerFilter, SamplerBehavior,
+        SamplerWrapFunction, UniformValue,
+    },
+    Depth, DrawParameters, Program, ProgramCreationError, Surface,
+};
+
+use crate::{
+    camera::Camera,
+    object_traits::{GetableSceneObject, Renderable},
+    utils::{
+        matrix_util::{look_at, mul, normalize, orthographic, perspective},
+        types_util::{Arr3F32, Mat4, Mat4Arr},
+    },
+};
+
+use super::{DirectionalLight, Light, RenderUniforms, SpotLight, UniformProvider};
+
+///the maximum number of cascades of a `ShadowMap`, it has to match `SHADOW_GLSL`
+pub const MAX_SHADOW_CASCADES: usize = 4;
+
+/******************\
+*                 *
+*---LightCamera---*
+*                 *
+\******************/
+
+///the point of view of a light, used to render the shadow maps.
+///The matrices are column major, like glium uniforms
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LightCamera {
+    view: Mat4Arr,
+    projection: Mat4Arr,
+}
+
+impl LightCamera {
+    ///an orthographic projection containing the sphere of center `center` and radius `radius`
+    pub fn directional(light: &DirectionalLight, center: Arr3F32, radius: f32) -> Self {
+        let direction = light.direction();
+        let eye = [
+            center[0] - direction[0] * radius * 2.,
+            center[1] - direction[1] * radius * 2.,
+            center[2] - direction[2] * radius * 2.,
+        ];
+
+        Self {
+            view: look_at(eye, direction),
+            projection: orthographic(radius, radius, radius, radius * 3.),
+        }
+    }
+
+    ///a perspective projection covering the cone of the spot, until `far`
+    pub fn spot(light: &SpotLight, near: f32, far: f32) -> Self {
+        Self {
+            view: look_at(light.get_position().into(), light.direction()),
+            projection: perspective(light.cone.outer_angle * 2., 1., near, far),
+        }
+    }
+
+    pub fn view(&self) -> Mat4Arr {
+        self.view
+    }
+
+    pub fn projection(&self) -> Mat4Arr {
+        self.projection
+    }
+
+    pub fn view_projection(&self) -> Mat4Arr {
+        mul(self.projection, self.view)
+    }
+}
+
+impl Camera for LightCamera {
+    fn zoom(&mut self, factor: f32) {
+        self.projection[0][0] *= factor;
+        self.projection[1][1] *= factor;
+    }
+
+    fn projection_matrix(&self) -> Mat4 {
+        self.projection.into()
+    }
+
+    fn view_matrix(&self) -> Mat4 {
+        self.view.into()
+    }
+
+    fn view_projection_matrix(&self) -> Mat4 {
+        self.view_projection().into()
+    }
+}
+
+///`view_projection`
+impl UniformProvider for LightCamera {
+    fn provide_uniforms<'a>(&'a self, uniforms: &mut RenderUniforms<'a>) {
+        uniforms.set(
+            "view_projection",
+            UniformValue::Mat4(self.view_projection()),
+        );
+    }
+}
+
+///the camera seeing the scene, used to split the cascades of a directional light
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ViewFrustum {
+    pub eye: Arr3F32,
+    pub direction: Arr3F32,
+    ///vertical field of view, in radians
+    pub fov_y: f32,
+    pub aspect: f32,
+}
+
+/******************\
+*                 *
+*----ShadowMap----*
+*                 *
+\******************/
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ShadowSettings {
+    ///width and height of each depth texture
+    pub resolution: u32,
+    ///the far distance of each cascade from the view, only the first one is used by spot lights
+    pub cascade_splits: Vec<f32>,
+    ///subtracted from the depth before the comparison, against the shadow acne
+    pub depth_bias: f32,
+    ///polygon offset applied while rendering the depth, scaled by the slope of the polygons
+    pub slope_bias: f32,
+    ///the PCF kernel is `(2 * pcf_radius + 1)²` samples, 0 gives hard shadows
+    pub pcf_radius: u32,
+    ///the index of the shadow casting light in the `LightList`, used by the shadowed built-in shaders
+    pub light_index: usize,
+}
+
+impl Default for ShadowSettings {
+    fn default() -> Self {
+        Self {
+            resolution: 2048,
+            cascade_splits: vec![10., 30., 100.],
+            depth_bias: 0.002,
+            slope_bias: 1.5,
+            pcf_radius: 1,
+            light_index: 0,
+        }
+    }
+}
+
+impl ShadowSettings {
+    ///at least one cascade split is needed, the cameras are built from them
+    pub fn validate(&self) -> Result<(), ShadowError> {
+        if self.cascade_splits.is_empty() {
+            return Err(ShadowError::NoCascadeSplit);
+        }
+        Ok(())
+    }
+}
+
+///depth textures rendered from a light, one per cascade.
+///The shadow maps are given to the main pass through its `UniformProvider` impl,
+///and read with the `shadow_factor` function of `SHADOW_GLSL`.
+///Only the shadowed built-in shaders and the custom shaders including `SHADOW_GLSL` read them
+pub struct ShadowMap {
+    settings: ShadowSettings,
+    textures: Vec<DepthTexture2d>,
+    cameras: Vec<LightCamera>,
+    ///the view the cascades were split from, `None` for a spot light
+    view: Option<ViewFrustum>,
+    program: Program,
+}
+
+impl ShadowMap {
+    pub fn new<F: Facade>(facade: &F, settings: ShadowSettings) -> Result<Self, ShadowError> {
+        settings.validate()?;
+        let cascades = settings.cascade_splits.len().min(MAX_SHADOW_CASCADES);
+        let textures = (0..cascades)
+            .map(|_| DepthTexture2d::empty(facade, settings.resolution, settings.resolution))
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(ShadowError::TextureCreationError)?;
+
+        let program =
+            Program::from_source(facade, DEPTH_VERTEX_SHADER, DEPTH_FRAGMENT_SHADER, None)
+                .map_err(ShadowError::ProgramCreationError)?;
+
+        Ok(Self {
+            settings,
+            textures,
+            cameras: vec![],
+            view: None,
+            program,
+        })
+    }
+
+    pub fn settings(&self) -> &ShadowSettings {
+        &self.settings
+    }
+
+    ///the resolution and the number of cascades can't be changed once the map is created
+    pub fn set_filtering(&mut self, pcf_radius: u32, depth_bias: f32, slope_bias: f32) {
+        self.settings.pcf_radius = pcf_radius;
+        self.settings.depth_bias = depth_bias;
+        self.settings.slope_bias = slope_bias;
+    }
+
+    pub fn set_light_index(&mut self, light_index: usize) {
+        self.settings.light_index = light_index;
+    }
+
+    pub fn cascade_count(&self) -> usize {
+        self.textures.len()
+    }
+
+    pub fn cameras(&self) -> &[LightCamera] {
+        &self.cameras
+    }
+
+    pub fn texture(&self, cascade: usize) -> Option<&DepthTexture2d> {
+        self.textures.get(cascade)
+    }
+
+    ///one camera per cascade, each one containing a slice of the view frustum
+    pub fn update_directional(&mut self, light: &DirectionalLight, view: &ViewFrustum) {
+        self.cameras = cascade_spheres(&self.settings.cascade_splits, view)
+            .take(self.textures.len())
+            .map(|(center, radius)| LightCamera::directional(light, center, radius))
+            .collect();
+        self.view = Some(*view);
+    }
+
+    ///a single camera, until the range of the spot (or the first cascade split without range)
+    pub fn update_spot(&mut self, light: &SpotLight, near: f32) {
+        let far = match light.attenuation.range {
+            range if range > 0. => range,
+            _ => self
+                .settings
+                .cascade_splits
+                .first()
+                .copied()
+                .unwrap_or(100.),
+        };
+        self.cameras = vec![LightCamera::spot(light, near, far)];
+        self.view = None;
+    }
+
+    ///start the depth pass of a cascade, the depth texture is cleared
+    pub fn pass<'a, F: Facade>(
+        &'a self,
+        facade: &'a F,
+        cascade: usize,
+    ) -> Result<ShadowPass<'a, F>, ShadowError> {
+        let (Some(texture), Some(camera)) = (self.textures.get(cascade), self.cameras.get(cascade))
+        else {
+            return Err(ShadowError::InvalidCascade(cascade));
+        };
+
+        let mut target = SimpleFrameBuffer::depth_only(facade, texture)
+            .map_err(ShadowError::FrameBufferValidationError)?;
+        target.clear_depth(1.);
+
+        let draw_parameters = DrawParameters {
+            depth: Depth {
+                test: DepthTest::IfLess,
+                write: true,
+                ..Default::default()
+            },
+            polygon_offset: PolygonOffset {
+                factor: self.settings.slope_bias,
+                units: 1.,
+                fill: true,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        Ok(ShadowPass {
+            facade,
+            target,
+            program: &self.program,
+            camera,
+            draw_parameters,
+        })
+    }
+}
+
+///`shadow_map_<i>`, `shadow_matrix_<i>`, `shadow_splits`, `shadow_cascade_count`,
+///`shadow_bias`, `shadow_pcf_radius`, `shadow_texel_size`, `shadow_view_position`,
+///`shadow_view_direction` and `shadow_light`
+impl UniformProvider for ShadowMap {
+    fn provide_uniforms<'a>(&'a self, uniforms: &mut RenderUniforms<'a>) {
+        let sampler = SamplerBehavior {
+            wrap_function: (
+                SamplerWrapFunction::Clamp,
+                SamplerWrapFunction::Clamp,
+                SamplerWrapFunction::Clamp,
+            ),
+            minify_filter: MinifySamplerFilter::Linear,
+            magnify_filter: MagnifySamplerFilter::Linear,
+            depth_texture_comparison: Some(DepthTextureComparison::LessOrEqual),
+            ..Default::default()
+        };
+
+        for (i, (texture, camera)) in self.textures.iter().zip(&self.cameras).enumerate() {
+            uniforms.set(
+                &format!("shadow_map_{}", i),
+                UniformValue::DepthTexture2d(texture, Some(sampler)),
+            );
+            uniforms.set(
+                &format!("shadow_matrix_{}", i),
+                UniformValue::Mat4(camera.view_projection()),
+            );
+        }
+
+        let mut splits = [f32::MAX; MAX_SHADOW_CASCADES];
+        for (split, value) in splits.iter_mut().zip(&self.settings.cascade_splits) {
+            *split = *value;
+        }
+        let cascades = self.textures.len().min(self.cameras.len());
+
+        uniforms.set("shadow_splits", UniformValue::Vec4(splits));
+        uniforms.set(
+            "shadow_cascade_count",
+            UniformValue::SignedInt(cascades as i32),
+        );
+        uniforms.set("shadow_bias", UniformValue::Float(self.settings.depth_bias));
+        uniforms.set(
+            "shadow_pcf_radius",
+            UniformValue::SignedInt(self.settings.pcf_radius as i32),
+        );
+        uniforms.set(
+            "shadow_texel_size",
+            UniformValue::Float(1. / self.settings.resolution as f32),
+        );
+
+        let (position, direction) = match &self.view {
+            Some(view) => (view.eye, normalize(view.direction)),
+            None => ([0.; 3], [0.; 3]),
+        };
+        uniforms.set("shadow_view_position", UniformValue::Vec3(position));
+        uniforms.set("shadow_view_direction", UniformValue::Vec3(direction));
+        uniforms.set(
+            "shadow_light",
+            UniformValue::SignedInt(self.settings.light_index as i32),
+        );
+    }
+}
+
+///the depth rendering of one cascade
+pub struct ShadowPass<'a, F: Facade> {
+    facade: &'a F,
+    target: SimpleFrameBuffer<'a>,
+    program: &'a Program,
+    camera: &'a LightCamera,
+    draw_parameters: DrawParameters<'static>,
+}
+
+impl<F: Facade> ShadowPass<'_, F> {
+    pub fn camera(&self) -> &LightCamera {
+        self.camera
+    }
+
+    ///render the depth of `renderable`, seen from the light
+    pub fn draw<R: Renderable + ?Sized>(&mut self, renderable: &R) -> Result<(), R::RenderError> {
+        let uniforms = RenderUniforms::new().with(self.camera);
+        renderable.render(
+            self.facade,
+            self.program,
+            &mut self.target,
+            &uniforms,
+            &self.draw_parameters,
+        )
+    }
+}
+
+#[derive(Debug)]
+pub enum ShadowError {
+    ProgramCreationError(ProgramCreationError),
+    TextureCreationError(TextureCreationError),
+    FrameBufferValidationError(ValidationError),
+    InvalidCascade(usize),
+    NoCascadeSplit,
+}
+
+impl Display for ShadowError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ShadowError::ProgramCreationError(e) => write!(f, "program creation error :{}", e),
+            ShadowError::TextureCreationError(e) => write!(f, "texture creation error :{}", e),
+            ShadowError::FrameBufferValidationError(e) => {
+                write!(f, "frame buffer validation error :{}", e)
+            }
+            ShadowError::InvalidCascade(cascade) => {
+                write!(f, "the cascade {} has no texture or no camera", cascade)
+            }
+            ShadowError::NoCascadeSplit => write!(f, "the shadow settings have no cascade split"),
+        }
+    }
+}
+
+impl std::error::Error for ShadowError {}
+
+/******************\
+*                 *
+*-----Shaders-----*
+*                 *
+\******************/
+
+const DEPTH_VERTEX_SHADER: &str = r#"
+#version 140
+
+in vec4 position;
+
+uniform mat4 model;
+uniform mat4 view_projection;
+
+void main() {
+    gl_Position = view_projection * model * position;
+}
+"#;
+
+const DEPTH_FRAGMENT_SHADER: &str = r#"
+#version 140
+
+void main() {}
+"#;
+
+///the source of `SHADOW_GLSL`, a literal so it can be `concat!`ed with the built-in shaders
+macro_rules! shadow_glsl {
+    () => {
+        r#"
+uniform sampler2DShadow shadow_map_0;
+uniform sampler2DShadow shadow_map_1;
+uniform sampler2DShadow shadow_map_2;
+uniform sampler2DShadow shadow_map_3;
+uniform mat4 shadow_matrix_0;
+uniform mat4 shadow_matrix_1;
+uniform mat4 shadow_matrix_2;
+uniform mat4 shadow_matrix_3;
+uniform vec4 shadow_splits;
+uniform int shadow_cascade_count;
+uniform float shadow_bias;
+uniform int shadow_pcf_radius;
+uniform float shadow_texel_size;
+uniform vec3 shadow_view_position;
+uniform vec3 shadow_view_direction;
+uniform int shadow_light;
+
+float sample_shadow_map(sampler2DShadow shadow_map, mat4 shadow_matrix, vec3 world_position) {
+    vec4 light_position = shadow_matrix * vec4(world_position, 1.0);
+    vec3 coords = light_position.xyz / light_position.w * 0.5 + 0.5;
+    if (any(lessThan(coords, vec3(0.0))) || any(greaterThan(coords, vec3(1.0)))) {
+        return 1.0;
+    }
+
+    float lit = 0.0;
+    float samples = 0.0;
+    for (int x = -shadow_pcf_radius; x <= shadow_pcf_radius; x++) {
+        for (int y = -shadow_pcf_radius; y <= shadow_pcf_radius; y++) {
+            vec2 offset = vec2(x, y) * shadow_texel_size;
+            lit += texture(shadow_map, vec3(coords.xy + offset, coords.z - shadow_bias));
+            samples += 1.0;
+        }
+    }
+    return lit / samples;
+}
+
+//`view_depth` is the distance from the camera, along its direction
+float shadow_factor(vec3 world_position, float view_depth) {
+    if (shadow_cascade_count <= 0) {
+        return 1.0;
+    }
+    if (shadow_cascade_count == 1 || view_depth < shadow_splits.x) {
+        return sample_shadow_map(shadow_map_0, shadow_matrix_0, world_position);
+    }
+    if (shadow_cascade_count == 2 || view_depth < shadow_splits.y) {
+        return sample_shadow_map(shadow_map_1, shadow_matrix_1, world_position);
+    }
+    if (shadow_cascade_count == 3 || view_depth < shadow_splits.z) {
+        return sample_shadow_map(shadow_map_2, shadow_matrix_2, world_position);
+    }
+    return sample_shadow_map(shadow_map_3, shadow_matrix_3, world_position);
+}
+
+//the `view_depth` of `shadow_factor`, along the view given to `ShadowMap::update_directional`
+float shadow_view_depth(vec3 world_position) {
+    return dot(world_position - shadow_view_position, shadow_view_direction);
+}
+"#
+    };
+}
+
+pub(crate) use shadow_glsl;
+
+///to be added to a fragment shader after the `#version` line,
+///`shadow_factor` returns 1 for a lit fragment and 0 for a fragment in the shadow.
+///`shadow_light` is the index of the light casting the shadows, see `ShadowSettings::light_index`
+pub const SHADOW_GLSL: &str = shadow_glsl!();
+
+/******************\
+*                 *
+*------Utils------*
+*                 *
+\******************/
+
+///the bounding sphere of each slice of the view frustum
+pub(crate) fn cascade_spheres<'a>(
+    splits: &'a [f32],
+    view: &'a ViewFrustum,
+) -> impl Iterator<Item = (Arr3F32, f32)> + 'a {
+    let direction = normalize(view.direction);
+    let tan_half_fov = (view.fov_y / 2.).tan();
+
+    splits.iter().enumerate().map(move |(i, &far)| {
+        let near = if i == 0 { 0. } else { splits[i - 1] };
+        let middle = (near + far) / 2.;
+        let center = [
+            view.eye[0] + direction[0] * middle,
+            view.eye[1] + direction[1] * middle,
+            view.eye[2] + direction[2] * middle,
+        ];
+
+        //the far corners are the farthest points of the slice from its center
+        let half_height = far * tan_half_fov;
+        let half_width = half_height * view.aspect;
+        let half_depth = (far - near) / 2.;
+        let radius =
+            (half_depth * half_depth + half_width * half_width + half_height * half_height).sqrt();
+
+        (center, radius)
+    })
+}
@@ -0,0 +1,451 @@
+use std::{fmt::Display, path::Path, rc::Rc};
+
+use glium::{
+    backend::Facade,
+    draw_parameters::DepthTest,
+    implement_vertex,
+    index::{NoIndices, PrimitiveType},
+    texture::Cubemap,
+    uniforms::{
+        MagnifySamplerFilter, MinifySamplerFilter, SamplerBehavior, SamplerWrapFunction,
+        UniformValue,
+    },
+    vertex::BufferCreationError,
+    Depth, DrawError, DrawParameters, Program, ProgramCreationError, Surface, VertexBuffer,
+};
+
+use crate::{
+    camera::Camera,
+    utils::types_util::{Arr3F32, Mat4Arr},
+};
+
+use super::{cubemap, ImageData, RenderUniforms, TextureError, UniformProvider};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct SkyboxVertex {
+    position: Arr3F32,
+}
+
+implement_vertex!(SkyboxVertex, position);
+
+/******************\
+*                 *
+*------Skybox-----*
+*                 *
+\******************/
+
+///a cubemap drawn behind everything, around the camera.
+///It is also the environment of the materials, see `ENVIRONMENT_GLSL`
+pub struct Skybox {
+    cubemap: Rc<Cubemap>,
+    ///the average light coming from each direction, in the order of `CUBE_LAYERS`
+    ambient: [Arr3F32; 6],
+    ///multiplies the colors of the skybox, and the light of the environment
+    pub intensity: f32,
+
+    program: Program,
+    vertices: VertexBuffer<SkyboxVertex>,
+}
+
+impl std::fmt::Debug for Skybox {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Skybox")
+            .field("size", &self.cubemap.dimensions())
+            .field("ambient", &self.ambient)
+            .field("intensity", &self.intensity)
+            .finish()
+    }
+}
+
+impl Skybox {
+    ///the ambient light can't be read back from the cubemap, so it is given
+    pub fn from_cubemap<F: Facade>(
+        facade: &F,
+        cubemap: Rc<Cubemap>,
+        ambient: [Arr3F32; 6],
+    ) -> Result<Self, SkyboxError> {
+        let program =
+            Program::from_source(facade, SKYBOX_VERTEX_SHADER, SKYBOX_FRAGMENT_SHADER, None)
+                .map_err(SkyboxError::ProgramCreationError)?;
+        let vertices = VertexBuffer::new(facade, &cube_vertices())
+            .map_err(SkyboxError::VertexBufferCreationError)?;
+
+        Ok(Self {
+            cubemap,
+            ambient,
+            intensity: 1.,
+            program,
+            vertices,
+        })
+    }
+
+    ///the faces are given in the order of `CUBE_LAYERS`
+    pub fn from_faces<F: Facade>(facade: &F, faces: &[ImageData; 6]) -> Result<Self, SkyboxError> {
+        let cubemap = cubemap(facade, faces).map_err(SkyboxError::TextureError)?;
+        Self::from_cubemap(facade, Rc::new(cubemap), ambient_cube(faces))
+    }
+
+    ///an equirectangular (latitude-longitude) panorama, converted to faces of `face_size` pixels.
+    ///The center of the image is in the -z direction
+    pub fn from_equirectangular<F: Facade>(
+        facade: &F,
+        image: &ImageData,
+        face_size: u32,
+    ) -> Result<Self, SkyboxError> {
+        Self::from_faces(facade, &equirectangular_to_faces(image, face_size))
+    }
+
+    pub fn load_faces<F: Facade, P: AsRef<Path>>(
+        facade: &F,
+        paths: [P; 6],
+    ) -> Result<Self, SkyboxError> {
+        let mut faces = vec![];
+        for path in paths {
+            faces.push(ImageData::from_path(path).map_err(SkyboxError::TextureError)?);
+        }
+        let faces: [ImageData; 6] = faces.try_into().expect("six faces were loaded");
+        Self::from_faces(facade, &faces)
+    }
+
+    ///the faces are a quarter as wide as the panorama
+    pub fn load_equirectangular<F: Facade, P: AsRef<Path>>(
+        facade: &F,
+        path: P,
+    ) -> Result<Self, SkyboxError> {
+        let image = ImageData::from_path(path).map_err(SkyboxError::TextureError)?;
+        let face_size = (image.width / 4).max(1);
+        Self::from_equirectangular(facade, &image, face_size)
+    }
+
+    pub fn cubemap(&self) -> &Rc<Cubemap> {
+        &self.cubemap
+    }
+
+    pub fn ambient(&self) -> &[Arr3F32; 6] {
+        &self.ambient
+    }
+
+    pub fn with_intensity(mut self, intensity: f32) -> Self {
+        self.intensity = intensity;
+        self
+    }
+
+    ///only the rotation of the camera is used, so the skybox never gets closer.
+    ///It is drawn on the far plane without writing the depth, so it can be drawn first or
+    ///after the opaque objects
+    pub fn draw<S, C>(&self, target: &mut S, camera: &C) -> Result<(), SkyboxError>
+    where
+        S: Surface,
+        C: Camera + ?Sized,
+    {
+        let view: Mat4Arr = camera.view_matrix().into();
+        let projection: Mat4Arr = camera.projection_matrix().into();
+
+        let mut uniforms = RenderUniforms::new().with(self);
+        uniforms.set("view_rotation", UniformValue::Mat4(view_rotation(&view)));
+        uniforms.set("projection", UniformValue::Mat4(projection));
+
+        let draw_parameters = DrawParameters {
+            depth: Depth {
+                test: DepthTest::IfLessOrEqual,
+                write: false,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        target
+            .draw(
+                &self.vertices,
+                NoIndices(PrimitiveType::TrianglesList),
+                &self.program,
+                &uniforms,
+                &draw_parameters,
+            )
+            .map_err(SkyboxError::DrawError)
+    }
+}
+
+///`environment_map`, `environment_intensity` and `environment_ambient_<i>`
+impl UniformProvider for Skybox {
+    fn provide_uniforms<'a>(&'a self, uniforms: &mut RenderUniforms<'a>) {
+        uniforms.set(
+            "environment_map",
+            UniformValue::Cubemap(&self.cubemap, Some(environment_sampler())),
+        );
+        uniforms.set("environment_intensity", UniformValue::Float(self.intensity));
+        for (i, ambient) in self.ambient.iter().enumerate() {
+            uniforms.set(
+                &format!("environment_ambient_{}", i),
+                UniformValue::Vec3(*ambient),
+            );
+        }
+    }
+}
+
+pub(crate) fn environment_sampler() -> SamplerBehavior {
+    SamplerBehavior {
+        wrap_function: (
+            SamplerWrapFunction::Clamp,
+            SamplerWrapFunction::Clamp,
+            SamplerWrapFunction::Clamp,
+        ),
+        minify_filter: MinifySamplerFilter::Linear,
+        magnify_filter: MagnifySamplerFilter::Linear,
+        ..Default::default()
+    }
+}
+
+/******************\
+*                 *
+*-----Shaders-----*
+*                 *
+\******************/
+
+const SKYBOX_VERTEX_SHADER: &str = r#"
+#version 140
+
+in vec3 position;
+
+uniform mat4 projection;
+uniform mat4 view_rotation;
+
+out vec3 v_direction;
+
+void main() {
+    v_direction = position;
+    //z = w puts the skybox on the far plane
+    gl_Position = (projection * view_rotation * vec4(position, 1.0)).xyww;
+}
+"#;
+
+const SKYBOX_FRAGMENT_SHADER: &str = r#"
+#version 140
+
+in vec3 v_direction;
+
+uniform samplerCube environment_map;
+uniform float environment_intensity;
+
+out vec4 color;
+
+void main() {
+    color = vec4(texture(environment_map, v_direction).rgb * environment_intensity, 1.0);
+}
+"#;
+
+///to be added to a fragment shader after the `#version` line, with the uniforms of a `Skybox`.
+///`environment_reflection` samples the skybox in the reflected direction, `environment_ambient`
+///returns the light coming from the skybox to a surface
+pub const ENVIRONMENT_GLSL: &str = r#"
+uniform samplerCube environment_map;
+uniform float environment_intensity;
+uniform vec3 environment_ambient_0;
+uniform vec3 environment_ambient_1;
+uniform vec3 environment_ambient_2;
+uniform vec3 environment_ambient_3;
+uniform vec3 environment_ambient_4;
+uniform vec3 environment_ambient_5;
+
+//`view_direction` goes from the camera to the fragment
+vec3 environment_reflection(vec3 normal, vec3 view_direction) {
+    vec3 direction = reflect(normalize(view_direction), normalize(normal));
+    return texture(environment_map, direction).rgb * environment_intensity;
+}
+
+vec3 environment_ambient(vec3 normal) {
+    vec3 n = normalize(normal);
+    vec3 weights = n * n;
+    vec3 x = n.x >= 0.0 ? environment_ambient_0 : environment_ambient_1;
+    vec3 y = n.y >= 0.0 ? environment_ambient_2 : environment_ambient_3;
+    vec3 z = n.z >= 0.0 ? environment_ambient_4 : environment_ambient_5;
+    return (weights.x * x + weights.y * y + weights.z * z) * environment_intensity;
+}
+"#;
+
+/******************\
+*                 *
+*------Utils------*
+*                 *
+\******************/
+
+///the view matrix without its translation, `view` is column major
+pub(crate) fn view_rotation(view: &Mat4Arr) -> Mat4Arr {
+    let mut rotation = *view;
+    rotation[3] = [0., 0., 0., 1.];
+    rotation
+}
+
+fn cube_vertices() -> Vec<SkyboxVertex> {
+    let corner = |i: usize| SkyboxVertex {
+        position: [
+            if i & 1 == 0 { -1. } else { 1. },
+            if i & 2 == 0 { -1. } else { 1. },
+            if i & 4 == 0 { -1. } else { 1. },
+        ],
+    };
+
+    //two triangles per face, nothing is culled so the winding doesn't matter
+    [
+        [0, 2, 4, 6],
+        [1, 3, 5, 7],
+        [0, 1, 4, 5],
+        [2, 3, 6, 7],
+        [0, 1, 2, 3],
+        [4, 5, 6, 7],
+    ]
+    .iter()
+    .flat_map(|[a, b, c, d]| [*a, *b, *c, *b, *d, *c])
+    .map(corner)
+    .collect()
+}
+
+///the direction of a point of a face, `s` and `t` go from -1 to 1 from the top left corner of
+///the face. The faces are in the order of `CUBE_LAYERS`, with the OpenGL orientations
+pub(crate) fn cube_direction(face: usize, s: f32, t: f32) -> Arr3F32 {
+    match face {
+        0 => [1., -t, -s],
+        1 => [-1., -t, s],
+        2 => [s, 1., t],
+        3 => [s, -1., -t],
+        4 => [s, -t, 1.],
+        _ => [-s, -t, -1.],
+    }
+}
+
+///the center of the pixel `(x, y)` of a face of `size` pixels, see `cube_direction`
+fn face_coords(x: u32, y: u32, size: u32) -> (f32, f32) {
+    (
+        (x as f32 + 0.5) / size as f32 * 2. - 1.,
+        (y as f32 + 0.5) / size as f32 * 2. - 1.,
+    )
+}
+
+///the six faces of a cubemap, sampled from an equirectangular panorama
+pub(crate) fn equirectangular_to_faces(image: &ImageData, face_size: u32) -> [ImageData; 6] {
+    let face_size = face_size.max(1);
+    std::array::from_fn(|face| {
+        let mut pixels = Vec::with_capacity((face_size * face_size * 4) as usize);
+        for y in 0..face_size {
+            for x in 0..face_size {
+                let (s, t) = face_coords(x, y, face_size);
+                let [dx, dy, dz] = cube_direction(face, s, t);
+                let length = (dx * dx + dy * dy + dz * dz).sqrt();
+
+                let u = 0.5 + dx.atan2(-dz) / std::f32::consts::TAU;
+                let v = (dy / length).clamp(-1., 1.).acos() / std::f32::consts::PI;
+                pixels.extend(sample_bilinear(image, u, v));
+            }
+        }
+
+        ImageData {
+            width: face_size,
+            height: face_size,
+            pixels,
+        }
+    })
+}
+
+///`u` wraps around and `v` is clamped, from the top left corner
+fn sample_bilinear(image: &ImageData, u: f32, v: f32) -> [u8; 4] {
+    if image.width == 0 || image.height == 0 {
+        return [0; 4];
+    }
+
+    let x = u.rem_euclid(1.) * image.width as f32 - 0.5;
+    let y = (v.clamp(0., 1.) * image.height as f32 - 0.5).clamp(0., (image.height - 1) as f32);
+    let (x0, y0) = (x.floor(), y.floor());
+    let (fx, fy) = (x - x0, y - y0);
+
+    let pixel = |x: f32, y: f32| {
+        let x = (x as i64).rem_euclid(image.width as i64) as usize;
+        let y = (y as usize).min(image.height as usize - 1);
+        let i = (y * image.width as usize + x) * 4;
+        [0, 1, 2, 3].map(|c| image.pixels[i + c] as f32)
+    };
+    let (a, b) = (pixel(x0, y0), pixel(x0 + 1., y0));
+    let (c, d) = (pixel(x0, y0 + 1.), pixel(x0 + 1., y0 + 1.));
+
+    [0, 1, 2, 3].map(|i| {
+        let top = a[i] + (b[i] - a[i]) * fx;
+        let bottom = c[i] + (d[i] - c[i]) * fx;
+        (top + (bottom - top) * fy).round() as u8
+    })
+}
+
+///the light coming from each axis direction, in the order of `CUBE_LAYERS`, weighted by the
+///cosine of the angle with the direction. The faces are sampled on at most 32x32 texels
+pub(crate) fn ambient_cube(faces: &[ImageData; 6]) -> [Arr3F32; 6] {
+    const AXES: [Arr3F32; 6] = [
+        [1., 0., 0.],
+        [-1., 0., 0.],
+        [0., 1., 0.],
+        [0., -1., 0.],
+        [0., 0., 1.],
+        [0., 0., -1.],
+    ];
+
+    let mut light = [[0f32; 3]; 6];
+    let mut weights = [0f32; 6];
+
+    for (face, image) in faces.iter().enumerate() {
+        let size = image.width.min(image.height);
+        let step = (size / 32).max(1);
+        for y in (0..size).step_by(step as usize) {
+            for x in (0..size).step_by(step as usize) {
+                let (s, t) = face_coords(x, y, size);
+                let direction = cube_direction(face, s, t);
+                let length_squared = direction.iter().map(|v| v * v).sum::<f32>();
+                //the texels far from the center of a face cover a smaller solid angle
+                let solid_angle = length_squared.powf(-1.5);
+
+                let i = ((y * image.width + x) * 4) as usize;
+                let color = [0, 1, 2].map(|c| image.pixels[i + c] as f32 / 255.);
+
+                for (axis, direction_axis) in AXES.iter().enumerate() {
+                    let cos = direction
+                        .iter()
+                        .zip(direction_axis)
+                        .map(|(a, b)| a * b)
+                        .sum::<f32>()
+                        / length_squared.sqrt();
+                    if cos > 0. {
+                        let weight = cos * solid_angle;
+                        for c in 0..3 {
+                            light[axis][c] += color[c] * weight;
+                        }
+                        weights[axis] += weight;
+                    }
+                }
+            }
+        }
+    }
+
+    std::array::from_fn(|axis| match weights[axis] > 0. {
+        true => light[axis].map(|c| c / weights[axis]),
+        false => [0.; 3],
+    })
+}
+
+#[derive(Debug)]
+pub enum SkyboxError {
+    TextureError(TextureError),
+    ProgramCreationError(ProgramCreationError),
+    VertexBufferCreationError(BufferCreationError),
+    DrawError(DrawError),
+}
+
+impl Display for SkyboxError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SkyboxError::TextureError(e) => write!(f, "texture error :{}", e),
+            SkyboxError::ProgramCreationError(e) => write!(f, "program creation error :{}", e),
+            SkyboxError::VertexBufferCreationError(e) => {
+                write!(f, "vertex buffer creation error :{}", e)
+            }
+            SkyboxError::DrawError(e) => write!(f, "draw error :{}", e),
+        }
+    }
+}
+
+impl std::error::Error for SkyboxError {}
@@ -0,0 +1,151 @@
+use std::fmt::Display;
+
+use glium::{
+    backend::Facade,
+    framebuffer::{SimpleFrameBuffer, ValidationError},
+    texture::{
+        DepthTexture2d, MipmapsOption, RawImage2d, TextureCreationError, UncompressedFloatFormat,
+    },
+    DrawError, Texture2d,
+};
+
+use crate::render::MaterialError;
+
+///a color texture and an optional depth texture to render into, instead of the window
+pub struct RenderTarget {
+    color: Texture2d,
+    depth: Option<DepthTexture2d>,
+    format: UncompressedFloatFormat,
+}
+
+impl std::fmt::Debug for RenderTarget {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RenderTarget")
+            .field("dimensions", &self.dimensions())
+            .field("depth", &self.depth.is_some())
+            .field("format", &self.format)
+            .finish()
+    }
+}
+
+impl RenderTarget {
+    ///an rgba8 color texture and a depth texture
+    pub fn new<F: Facade>(facade: &F, width: u32, height: u32) -> Result<Self, RenderTargetError> {
+        Self::with_format(
+            facade,
+            width,
+            height,
+            UncompressedFloatFormat::U8U8U8U8,
+            true,
+        )
+    }
+
+    ///a floating point color texture, for the HDR rendering
+    pub fn hdr<F: Facade>(facade: &F, width: u32, height: u32) -> Result<Self, RenderTargetError> {
+        Self::with_format(
+            facade,
+            width,
+            height,
+            UncompressedFloatFormat::F16F16F16F16,
+            true,
+        )
+    }
+
+    pub fn with_format<F: Facade>(
+        facade: &F,
+        width: u32,
+        height: u32,
+        format: UncompressedFloatFormat,
+        depth: bool,
+    ) -> Result<Self, RenderTargetError> {
+        //a texture can't be empty
+        let (width, height) = (width.max(1), height.max(1));
+
+        let color =
+            Texture2d::empty_with_format(facade, format, MipmapsOption::NoMipmap, width, height)
+                .map_err(RenderTargetError::TextureCreationError)?;
+        let depth = match depth {
+            true => Some(
+                DepthTexture2d::empty(facade, width, height)
+                    .map_err(RenderTargetError::TextureCreationError)?,
+            ),
+            false => None,
+        };
+
+        Ok(Self {
+            color,
+            depth,
+            format,
+        })
+    }
+
+    pub fn dimensions(&self) -> (u32, u32) {
+        self.color.dimensions()
+    }
+
+    pub fn color(&self) -> &Texture2d {
+        &self.color
+    }
+
+    pub fn depth(&self) -> Option<&DepthTexture2d> {
+        self.depth.as_ref()
+    }
+
+    ///re-create the textures if the dimensions changed, their content is lost.
+    ///Returns true if they were re-created
+    pub fn resize<F: Facade>(
+        &mut self,
+        facade: &F,
+        width: u32,
+        height: u32,
+    ) -> Result<bool, RenderTargetError> {
+        if self.dimensions() == (width.max(1), height.max(1)) {
+            return Ok(false);
+        }
+
+        *self = Self::with_format(facade, width, height, self.format, self.depth.is_some())?;
+        Ok(true)
+    }
+
+    ///the surface to draw into, it borrows the textures
+    pub fn framebuffer<F: Facade>(
+        &self,
+        facade: &F,
+    ) -> Result<SimpleFrameBuffer<'_>, RenderTargetError> {
+        match &self.depth {
+            Some(depth) => SimpleFrameBuffer::with_depth_buffer(facade, &self.color, depth),
+            None => SimpleFrameBuffer::new(facade, &self.color),
+        }
+        .map_err(RenderTargetError::FrameBufferValidationError)
+    }
+
+    ///the pixels of the color texture, from the bottom left corner, for the screenshots
+    pub fn read_pixels(&self) -> RawImage2d<'static, u8> {
+        self.color.read()
+    }
+}
+
+#[derive(Debug)]
+pub enum RenderTargetError {
+    TextureCreationError(TextureCreationError),
+    FrameBufferValidationError(ValidationError),
+    MaterialError(MaterialError),
+    DrawError(DrawError),
+}
+
+impl Display for RenderTargetError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RenderTargetError::TextureCreationError(e) => {
+                write!(f, "texture creation error :{}", e)
+            }
+            RenderTargetError::FrameBufferValidationError(e) => {
+                write!(f, "frame buffer validation error :{}", e)
+            }
+            RenderTargetError::MaterialError(e) => write!(f, "material error :{}", e),
+            RenderTargetError::DrawError(e) => write!(f, "draw error :{}", e),
+        }
+    }
+}
+
+impl std::error::Error for RenderTargetError {}
@@ -0,0 +1,682 @@
+mod test_render_uniforms {
+    use glium::uniforms::{UniformValue, Uniforms};
+
+    use crate::{
+        camera::OrthographicCam,
+        render::{ModelTransform, RenderUniforms},
+    };
+
+    fn names(uniforms: &RenderUniforms) -> Vec<String> {
+        let mut names = vec![];
+        uniforms.visit_values(|name, _| names.push(name.to_string()));
+        names
+    }
+
+    #[test]
+    fn test_merge_sources() {
+        let camera = OrthographicCam::new([0., 0., 0.].into(), 2., 2., 0.1, 10.);
+        let model = ModelTransform {
+            model: [
+                [1., 0., 0., 0.],
+                [0., 1., 0., 0.],
+                [0., 0., 1., 0.],
+                [0., 0., 0., 1.],
+            ],
+        };
+        let user = glium::uniform! { time: 1.5f32 };
+
+        let uniforms = RenderUniforms::new()
+            .merge(&user)
+            .with(&camera)
+            .with(&model);
+
+        assert_eq!(names(&uniforms), vec!["time", "view_projection", "model"]);
+        assert!(matches!(uniforms.get("time"), Some(UniformValue::Float(t)) if *t == 1.5));
+    }
+
+    #[test]
+    fn test_later_uniforms_replace() {
+        let first = 1f32;
+        let second = 2f32;
+        let uniforms = RenderUniforms::new()
+            .add("value", &first)
+            .add("value", &second);
+
+        assert_eq!(uniforms.len(), 1);
+        assert!(matches!(uniforms.get("value"), Some(UniformValue::Float(v)) if *v == 2.));
+    }
+}
+
+mod test_material_param {
+    use glium::uniforms::{AsUniformValue, UniformValue};
+
+    use crate::render::MaterialParam;
+
+    #[test]
+    fn test_param_conversions() {
+        assert!(matches!(MaterialParam::from(2.), MaterialParam::Float(v) if v == 2.));
+        assert!(matches!(
+            MaterialParam::from([1., 0., 0.]),
+            MaterialParam::Vec3([1., 0., 0.])
+        ));
+
+        let color = MaterialParam::Color([1., 0.5, 0., 1.]);
+        assert!(matches!(
+            color.as_uniform_value(),
+            UniformValue::Vec4([1., 0.5, 0., 1.])
+        ));
+    }
+}
+
+mod test_lights {
+    use crate::render::{DirectionalLight, Light, LightList, PointLight, SpotLight, MAX_LIGHTS};
+
+    fn assert_close(a: [f32; 3], b: [f32; 3]) {
+        for (a, b) in a.iter().zip(b) {
+            assert!((a - b).abs() < 1e-5, "{:?} != {:?}", a, b);
+        }
+    }
+
+    #[test]
+    fn test_light_direction() {
+        let sun = DirectionalLight::new([0., -2., 0.], [1.; 3], 1.);
+        assert_close(sun.direction(), [0., -1., 0.]);
+
+        let backward = SpotLight::new([0., 0., 0.].into(), [0., 0., 1.], [1.; 3], 1.);
+        assert_close(backward.direction(), [0., 0., 1.]);
+
+        let spot = SpotLight::new([1., 2., 3.].into(), [1., 0., 0.], [1., 0.5, 0.], 2.);
+        let gpu_light = spot.to_gpu_light();
+        assert_eq!(gpu_light.position, [1., 2., 3., 2.]);
+        assert_eq!(gpu_light.color, [2., 1., 0., 1.]);
+        assert_close(
+            [
+                gpu_light.direction[0],
+                gpu_light.direction[1],
+                gpu_light.direction[2],
+            ],
+            [1., 0., 0.],
+        );
+    }
+
+    #[test]
+    fn test_light_block_is_capped() {
+        let mut lights = LightList::new().with_ambient_light([0.1, 0.2, 0.3]);
+        for i in 0..MAX_LIGHTS + 4 {
+            lights.push(&PointLight::new([i as f32, 0., 0.].into(), [1.; 3], 1.));
+        }
+
+        let block = lights.to_block();
+        assert_eq!(lights.len(), MAX_LIGHTS + 4);
+        assert_eq!(block.light_count, MAX_LIGHTS as i32);
+        assert_eq!(block.ambient_light, [0.1, 0.2, 0.3, 1.]);
+        assert_eq!(block.lights[3].position, [3., 0., 0., 1.]);
+    }
+}
+
+mod test_shadow {
+    use crate::{
+        render::{
+            shadow::cascade_spheres, BuiltinShader, DirectionalLight, LightCamera, ProgramCache,
+            ShadowError, ShadowMap, ShadowSettings, SpotLight, ViewFrustum,
+        },
+        utils::{
+            matrix_util::{look_at, mul, orthographic},
+            test_util::headless_facade,
+        },
+    };
+
+    fn transform(matrix: [[f32; 4]; 4], point: [f32; 3]) -> [f32; 4] {
+        let mut result = [0.; 4];
+        for (row, value) in result.iter_mut().enumerate() {
+            *value = (0..3).map(|k| matrix[k][row] * point[k]).sum::<f32>() + matrix[3][row];
+        }
+        result
+    }
+
+    fn assert_close(a: [f32; 4], b: [f32; 4]) {
+        for (a, b) in a.iter().zip(b) {
+            assert!((a - b).abs() < 1e-4, "{:?} != {:?}", a, b);
+        }
+    }
+
+    #[test]
+    fn test_look_at() {
+        let view = look_at([0., 0., 5.], [0., 0., -1.]);
+        assert_close(transform(view, [0., 0., 0.]), [0., 0., -5., 1.]);
+        assert_close(transform(view, [1., 2., 5.]), [1., 2., 0., 1.]);
+
+        let identity = [
+            [1., 0., 0., 0.],
+            [0., 1., 0., 0.],
+            [0., 0., 1., 0.],
+            [0., 0., 0., 1.],
+        ];
+        let projection = orthographic(2., 2., 1., 10.);
+        assert_eq!(mul(projection, identity), projection);
+        assert_eq!(mul(identity, projection), projection);
+    }
+
+    #[test]
+    fn test_directional_camera_contains_sphere() {
+        let sun = DirectionalLight::new([0., -1., 0.], [1.; 3], 1.);
+        let camera = LightCamera::directional(&sun, [3., 0., 0.], 2.);
+        let view_projection = camera.view_projection();
+
+        for point in [
+            [3., 0., 0.],
+            [5., 0., 0.],
+            [3., 2., 0.],
+            [3., -2., 0.],
+            [3., 0., 2.],
+        ] {
+            let clip = transform(view_projection, point);
+            for c in &clip[..3] {
+                assert!(c.abs() <= 1. + 1e-4, "{:?} is outside of {:?}", point, clip);
+            }
+        }
+        assert_close(transform(view_projection, [3., 0., 0.]), [0., 0., 0., 1.]);
+    }
+
+    #[test]
+    fn test_spot_camera_looks_along_the_spot() {
+        let spot = SpotLight::new([0., 0., 0.].into(), [1., 0., 0.], [1.; 3], 1.);
+        let camera = LightCamera::spot(&spot, 0.1, 10.);
+        let clip = transform(camera.view_projection(), [5., 0., 0.]);
+
+        assert!(clip[3] > 0.);
+        assert!(clip[0].abs() < 1e-4 && clip[1].abs() < 1e-4);
+        assert!((clip[2] / clip[3]).abs() < 1.);
+    }
+
+    #[test]
+    fn test_cascade_spheres() {
+        let view = ViewFrustum {
+            eye: [0., 0., 0.],
+            direction: [0., 0., -2.],
+            fov_y: std::f32::consts::FRAC_PI_2,
+            aspect: 1.,
+        };
+        let spheres: Vec<_> = cascade_spheres(&[10., 30.], &view).collect();
+
+        assert_eq!(spheres.len(), 2);
+        assert_eq!(spheres[0].0, [0., 0., -5.]);
+        assert_eq!(spheres[1].0, [0., 0., -20.]);
+        assert!((spheres[0].1 - (25f32 + 200.).sqrt()).abs() < 1e-3);
+        assert!((spheres[1].1 - (100f32 + 1800.).sqrt()).abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_empty_cascade_splits() {
+        let settings = ShadowSettings {
+            cascade_splits: vec![],
+            ..Default::default()
+        };
+        assert!(matches!(
+            settings.validate(),
+            Err(ShadowError::NoCascadeSplit)
+        ));
+        assert!(ShadowSettings::default().validate().is_ok());
+
+        let Some(facade) = headless_facade() else {
+            return;
+        };
+        assert!(matches!(
+            ShadowMap::new(&facade, settings),
+            Err(ShadowError::NoCascadeSplit)
+        ));
+    }
+
+    #[test]
+    fn test_shadowed_shaders_compile() {
+        let Some(facade) = headless_facade() else {
+            return;
+        };
+        let mut programs = ProgramCache::new();
+        for shader in [
+            BuiltinShader::ShadowedLambert,
+            BuiltinShader::ShadowedBlinnPhong,
+        ] {
+            programs.program(&facade, shader).unwrap();
+            programs.instanced_program(&facade, shader).unwrap();
+        }
+        assert_eq!(programs.len(), 4);
+    }
+}
+
+mod test_render_queue {
+    use crate::render::queue::{plan_draws, view_depth, DrawKey};
+
+    fn key(material: usize, buffer: usize, transparent: bool, depth: f32) -> DrawKey {
+        DrawKey {
+            program: material / 10,
+            material,
+            buffer,
+            transparent,
+            batchable: !transparent,
+            depth,
+        }
+    }
+
+    #[test]
+    fn test_opaque_draws_are_sorted_and_batched() {
+        let keys = [
+            key(21, 1, false, 0.),
+            key(10, 1, false, 0.),
+            key(21, 2, false, 0.),
+            key(10, 1, false, 0.),
+            key(21, 1, false, 0.),
+        ];
+
+        assert_eq!(plan_draws(&keys), vec![vec![1, 3], vec![0, 4], vec![2]]);
+    }
+
+    #[test]
+    fn test_unbatchable_draws_are_not_merged() {
+        let mut keys = [key(10, 1, false, 0.), key(10, 1, false, 0.)];
+        keys[1].batchable = false;
+
+        assert_eq!(plan_draws(&keys), vec![vec![0], vec![1]]);
+    }
+
+    #[test]
+    fn test_transparent_draws_are_drawn_back_to_front() {
+        let keys = [
+            key(10, 1, true, -2.),
+            key(10, 1, false, -1.),
+            key(10, 1, true, -8.),
+            key(10, 1, true, -5.),
+        ];
+
+        assert_eq!(plan_draws(&keys), vec![vec![1], vec![2], vec![3], vec![0]]);
+    }
+
+    #[test]
+    fn test_view_depth() {
+        let view = [
+            [1., 0., 0., 0.],
+            [0., 1., 0., 0.],
+            [0., 0., 1., 0.],
+            [0., 0., -5., 1.],
+        ];
+
+        assert_eq!(view_depth(&view, [3., 4., 1.]), -4.);
+    }
+}
+
+mod test_post_process {
+    use crate::render::post_process::{pass_outputs, PassOutput};
+
+    #[test]
+    fn test_pass_outputs() {
+        assert_eq!(pass_outputs(0).count(), 0);
+        assert_eq!(pass_outputs(1).collect::<Vec<_>>(), vec![PassOutput::Frame]);
+        assert_eq!(
+            pass_outputs(4).collect::<Vec<_>>(),
+            vec![
+                PassOutput::Ping,
+                PassOutput::Pong,
+                PassOutput::Ping,
+                PassOutput::Frame
+            ]
+        );
+    }
+}
+
+mod test_debug_draw {
+    use crate::{
+        mesh::{vertex::Vertex, Mesh},
+        object_traits::{Scale, SceneObject},
+        render::{debug_draw::triangle_tangent, DebugDraw, DEBUG_CIRCLE_SEGMENTS},
+        utils::matrix_util::{invert, normalize, sub, transform_point},
+    };
+
+    fn assert_close(a: [f32; 3], b: [f32; 3]) {
+        for (a, b) in a.iter().zip(b) {
+            assert!((a - b).abs() < 1e-4, "{:?} != {:?}", a, b);
+        }
+    }
+
+    #[test]
+    fn test_shapes_line_count() {
+        let mut debug = DebugDraw::new();
+        debug.line([0.; 3], [1.; 3], [1.; 4]);
+        assert_eq!(debug.len(), 1);
+
+        debug.aabb([0.; 3], [1.; 3], [1.; 4]);
+        assert_eq!(debug.len(), 13);
+
+        debug.clear();
+        debug.sphere([0.; 3], 2., [1.; 4]);
+        assert_eq!(debug.len(), 3 * DEBUG_CIRCLE_SEGMENTS);
+        assert!(debug.vertices().iter().all(|v| (v
+            .position
+            .iter()
+            .map(|c| c * c)
+            .sum::<f32>()
+            .sqrt()
+            - 2.)
+            .abs()
+            < 1e-4));
+    }
+
+    #[test]
+    fn test_frustum_corners() {
+        let projection = [
+            [0.5, 0., 0., 0.],
+            [0., 0.25, 0., 0.],
+            [0., 0., -1., 0.],
+            [1., 0., 0., 1.],
+        ];
+        let inverse = invert(&projection).unwrap();
+        assert_close(transform_point(&inverse, [1., 1., 1.]), [0., 4., -1.]);
+        assert_close(transform_point(&inverse, [-1., -1., -1.]), [-4., -4., 1.]);
+        assert!(invert(&[[0.; 4]; 4]).is_none());
+
+        let mut debug = DebugDraw::new();
+        debug.frustum(projection, [1.; 4]);
+        assert_eq!(debug.len(), 12);
+    }
+
+    #[test]
+    fn test_mesh_normals_non_uniform_scale() {
+        //a triangle of the plane x + y = 1
+        let vertices = [[1., 0., 0.], [0., 1., 0.], [1., 0., 1.]].map(|position| {
+            let mut vertex = Vertex::from(position);
+            vertex.normal = normalize([1., 1., 0.]);
+            vertex
+        });
+        let mut mesh = Mesh::from_buffers_data(vertices.to_vec(), &[0, 1, 2]).unwrap();
+        mesh.set_scale(Scale::from([2., 1., 1.]));
+
+        let mut debug = DebugDraw::new();
+        debug.mesh_normals(&mesh, 1., [1.; 4]);
+        assert_eq!(debug.len(), 3);
+
+        //the scaled plane is x / 2 + y = 1, the normals stay perpendicular to it
+        let lines = debug.vertices();
+        assert_close(lines[0].position, [2., 0., 0.]);
+        let normal = sub(lines[1].position, lines[0].position);
+        assert_close(normal, normalize([0.5, 1., 0.]));
+    }
+
+    #[test]
+    fn test_triangle_tangent() {
+        let tangent = triangle_tangent(
+            [[0., 0., 0.], [0., 2., 0.], [0., 0., 2.]],
+            [[0., 0., 0.], [1., 0., 0.], [0., 1., 0.]],
+        );
+        assert_close(tangent.unwrap(), [0., 1., 0.]);
+
+        assert!(triangle_tangent([[0.; 3]; 3], [[0.; 3]; 3]).is_none());
+    }
+}
+
+mod test_texture {
+    use std::{io::Cursor, path::PathBuf};
+
+    use glium::uniforms::{MagnifySamplerFilter, SamplerWrapFunction};
+
+    use crate::render::{resolve_map_path, ImageData, ImageFormat, TextureSampler};
+
+    fn encode(format: image::ImageFormat) -> Vec<u8> {
+        let image = image::RgbaImage::from_raw(2, 1, vec![255, 0, 0, 255, 0, 0, 255, 128]).unwrap();
+        let mut bytes = Cursor::new(vec![]);
+        image.write_to(&mut bytes, format).unwrap();
+        bytes.into_inner()
+    }
+
+    #[test]
+    fn test_decode_image() {
+        let png = encode(image::ImageFormat::Png);
+        assert_eq!(ImageFormat::guess(&png), ImageFormat::Png);
+
+        let image = ImageData::from_bytes(&png).unwrap();
+        assert_eq!(image.dimensions(), (2, 1));
+        assert_eq!(image.pixels, vec![255, 0, 0, 255, 0, 0, 255, 128]);
+
+        let tga = encode(image::ImageFormat::Tga);
+        let image = ImageData::from_bytes_with_format(&tga, ImageFormat::Tga).unwrap();
+        assert_eq!(image.pixels, vec![255, 0, 0, 255, 0, 0, 255, 128]);
+
+        assert!(ImageData::from_bytes(b"not an image").is_err());
+    }
+
+    #[test]
+    fn test_image_format_from_path() {
+        assert_eq!(ImageFormat::from_path("a/b.PNG"), Some(ImageFormat::Png));
+        assert_eq!(ImageFormat::from_path("b.jpeg"), Some(ImageFormat::Jpeg));
+        assert_eq!(ImageFormat::from_path("b.tga"), Some(ImageFormat::Tga));
+        assert_eq!(ImageFormat::from_path("b.bmp"), None);
+    }
+
+    #[test]
+    fn test_resolve_map_path() {
+        assert_eq!(
+            resolve_map_path("tests/obj", "textures\\wood.png"),
+            PathBuf::from("tests/obj/textures/wood.png")
+        );
+        assert_eq!(
+            resolve_map_path("tests/obj", "/textures/wood.png"),
+            PathBuf::from("/textures/wood.png")
+        );
+    }
+
+    #[test]
+    fn test_sampler() {
+        let sampler = TextureSampler::nearest()
+            .with_wrap(SamplerWrapFunction::Clamp)
+            .with_anisotropy(0)
+            .to_behavior();
+
+        assert_eq!(sampler.magnify_filter, MagnifySamplerFilter::Nearest);
+        assert_eq!(sampler.wrap_function.0, SamplerWrapFunction::Clamp);
+        assert_eq!(sampler.max_anisotropy, 1);
+    }
+}
+
+mod test_skybox {
+    use crate::render::{
+        ambient_cube, cube_direction, equirectangular_to_faces, view_rotation, ImageData,
+    };
+
+    fn filled(width: u32, height: u32, color: [u8; 4]) -> ImageData {
+        ImageData {
+            width,
+            height,
+            pixels: color.repeat((width * height) as usize),
+        }
+    }
+
+    #[test]
+    fn test_view_rotation() {
+        let view = [
+            [0., 0., -1., 0.],
+            [0., 1., 0., 0.],
+            [1., 0., 0., 0.],
+            [5., -2., 3., 1.],
+        ];
+
+        let rotation = view_rotation(&view);
+        assert_eq!(rotation[..3], view[..3]);
+        assert_eq!(rotation[3], [0., 0., 0., 1.]);
+    }
+
+    #[test]
+    fn test_cube_direction() {
+        assert_eq!(cube_direction(0, 0., 0.), [1., 0., 0.]);
+        assert_eq!(cube_direction(3, 0., 0.), [0., -1., 0.]);
+        assert_eq!(cube_direction(5, 0., 0.), [0., 0., -1.]);
+        //the top of the side faces is up
+        assert_eq!(cube_direction(4, 0., -1.), [0., 1., 1.]);
+        assert_eq!(cube_direction(1, 0., -1.), [-1., 1., 0.]);
+    }
+
+    #[test]
+    fn test_equirectangular_to_faces() {
+        //the top half is red and the bottom half is blue
+        let mut panorama = filled(16, 8, [255, 0, 0, 255]);
+        panorama.pixels[16 * 4 * 4..].copy_from_slice(&[0, 0, 255, 255].repeat(16 * 4));
+
+        let faces = equirectangular_to_faces(&panorama, 4);
+        assert!(faces.iter().all(|face| face.dimensions() == (4, 4)));
+        assert!(faces[2].pixels.chunks(4).all(|p| p == [255, 0, 0, 255]));
+        assert!(faces[3].pixels.chunks(4).all(|p| p == [0, 0, 255, 255]));
+        //the first row of a side face is up, and its last row is down
+        assert_eq!(faces[4].pixels[..4], [255, 0, 0, 255]);
+        assert_eq!(faces[4].pixels[15 * 4..], [0, 0, 255, 255]);
+    }
+
+    #[test]
+    fn test_ambient_cube() {
+        let faces = std::array::from_fn(|_| filled(8, 8, [255, 51, 0, 255]));
+        for light in ambient_cube(&faces) {
+            assert!((light[0] - 1.).abs() < 1e-4);
+            assert!((light[1] - 0.2).abs() < 1e-4);
+            assert!(light[2].abs() < 1e-4);
+        }
+
+        let mut faces: [ImageData; 6] = std::array::from_fn(|_| filled(8, 8, [0, 0, 0, 255]));
+        faces[2] = filled(8, 8, [255, 255, 255, 255]);
+        let ambient = ambient_cube(&faces);
+        assert!(ambient[2][0] > 0.5);
+        assert_eq!(ambient[3], [0.; 3]);
+        assert!(ambient[0][0] > 0. && ambient[0][0] < ambient[2][0]);
+    }
+}
+
+mod test_text {
+    use std::collections::HashMap;
+
+    use crate::render::{
+        pack_shelves, signed_distance_field, FontAtlas, FontSettings, GlyphBitmap, TextAlign,
+        TextLayout,
+    };
+
+    ///every glyph is a 6x10 box advancing by 8 pixels, 'A' 'V' are kerned
+    fn atlas() -> FontAtlas {
+        let bitmaps = "ABCV?"
+            .chars()
+            .map(|c| {
+                let bitmap = GlyphBitmap {
+                    width: 6,
+                    height: 10,
+                    pixels: vec![255; 60],
+                    advance: 8.,
+                    offset: [1., 0.],
+                };
+                (c, bitmap)
+            })
+            .chain([(
+                ' ',
+                GlyphBitmap {
+                    width: 0,
+                    height: 0,
+                    pixels: vec![],
+                    advance: 4.,
+                    offset: [0., 0.],
+                },
+            )])
+            .collect();
+        let kerning = HashMap::from([(('A', 'V'), -2.)]);
+
+        FontAtlas::from_bitmaps(
+            &FontSettings {
+                size: 16.,
+                ..Default::default()
+            },
+            bitmaps,
+            kerning,
+            12.,
+            20.,
+        )
+    }
+
+    #[test]
+    fn test_pack_shelves() {
+        let sizes = [(10, 20), (30, 5), (8, 8), (16, 16), (1, 1)];
+        let (width, height, positions) = pack_shelves(&sizes, 1);
+        assert!(width.is_power_of_two() && height.is_power_of_two());
+
+        let rects: Vec<_> = positions
+            .iter()
+            .zip(&sizes)
+            .map(|((x, y), (w, h))| (*x, *y, x + w, y + h))
+            .collect();
+        for (i, a) in rects.iter().enumerate() {
+            assert!(a.2 <= width && a.3 <= height);
+            for b in &rects[i + 1..] {
+                assert!(a.2 <= b.0 || b.2 <= a.0 || a.3 <= b.1 || b.3 <= a.1);
+            }
+        }
+    }
+
+    #[test]
+    fn test_signed_distance_field() {
+        let mut pixels = vec![0; 25];
+        pixels[12] = 255;
+        let bitmap = GlyphBitmap {
+            width: 5,
+            height: 5,
+            pixels,
+            advance: 5.,
+            offset: [0., 0.],
+        };
+
+        let sdf = signed_distance_field(&bitmap, 4);
+        assert_eq!((sdf.width, sdf.height), (13, 13));
+        assert_eq!(sdf.offset, [-4., -4.]);
+
+        let value = |x: u32, y: u32| sdf.pixels[(y * 13 + x) as usize];
+        assert!(value(6, 6) > 128);
+        assert!(value(7, 6) < 128);
+        assert!(value(7, 6) > value(9, 6));
+        assert_eq!(value(0, 0), 0);
+    }
+
+    #[test]
+    fn test_layout() {
+        let atlas = atlas();
+        assert_eq!(atlas.glyph('Z'), atlas.glyph('?'));
+
+        let layout = TextLayout::default().with_size(16.);
+        let glyphs = atlas.layout("AV B", &layout);
+        let chars: String = glyphs.iter().map(|glyph| glyph.character).collect();
+        assert_eq!(chars, "AVB");
+        //the baseline is at the ascent, the glyphs stand on it
+        assert_eq!(glyphs[0].min, [1., 2.]);
+        assert_eq!(glyphs[0].max, [7., 12.]);
+        //kerned
+        assert_eq!(glyphs[1].min[0], 7.);
+        assert_eq!(glyphs[2].min[0], 19.);
+        assert_eq!(atlas.measure("AV B", &layout), [26., 20.]);
+
+        //twice the size of the atlas
+        let glyphs = atlas.layout("B", &layout.with_size(32.));
+        assert_eq!(glyphs[0].max, [14., 24.]);
+    }
+
+    #[test]
+    fn test_line_breaks() {
+        let atlas = atlas();
+        let layout = TextLayout::default().with_size(16.);
+
+        let glyphs = atlas.layout("A\nBC", &layout);
+        assert_eq!(glyphs[1].min, [1., 22.]);
+        assert_eq!(atlas.measure("A\nBC", &layout), [16., 40.]);
+
+        //"AB C" is 28 pixels wide
+        let wrapped = layout.with_max_width(20.);
+        assert_eq!(atlas.measure("AB C BA", &wrapped), [16., 60.]);
+        let glyphs = atlas.layout("AB C", &wrapped);
+        assert_eq!(glyphs[2].min, [1., 22.]);
+
+        //a word wider than the line isn't broken
+        assert_eq!(atlas.measure("ABCABC", &wrapped), [48., 20.]);
+
+        let glyphs = atlas.layout("AB\nC", &layout.with_align(TextAlign::Right));
+        assert_eq!(glyphs[2].min[0], 9.);
+        let glyphs = atlas.layout("AB\nC", &layout.with_align(TextAlign::Center));
+        assert_eq!(glyphs[2].min[0], 5.);
+    }
+}
@@ -0,0 +1,825 @@
+use std::{borrow::Cow, collections::HashMap, fmt::Display, path::Path};
+
+use glium::{
+    backend::Facade,
+    draw_parameters::DepthTest,
+    implement_vertex,
+    index::{NoIndices, PrimitiveType},
+    texture::{
+        ClientFormat, MipmapsOption, RawImage2d, TextureCreationError, UncompressedFloatFormat,
+    },
+    uniforms::{
+        MagnifySamplerFilter, MinifySamplerFilter, SamplerBehavior, SamplerWrapFunction,
+        UniformValue,
+    },
+    vertex::BufferCreationError,
+    Blend, Depth, DrawError, DrawParameters, Program, ProgramCreationError, Surface, Texture2d,
+    VertexBuffer,
+};
+
+use crate::{
+    camera::Camera,
+    utils::types_util::{Arr3F32, Arr4F32, Mat4Arr},
+};
+
+use super::RenderUniforms;
+
+/******************\
+*                 *
+*----FontAtlas----*
+*                 *
+\******************/
+
+///how a font is rasterized into a `FontAtlas`
+#[derive(Debug, Clone, PartialEq)]
+pub struct FontSettings {
+    ///the height of the font in the atlas, in pixels
+    pub size: f32,
+    ///when set, the atlas holds a signed distance field spreading on this number of pixels
+    ///around the glyphs, so the text stays sharp when it is scaled up
+    pub sdf_spread: Option<u32>,
+    ///the characters rasterized in the atlas, the others are replaced by '?'
+    pub characters: Vec<char>,
+    ///the empty pixels between the glyphs of the atlas
+    pub padding: u32,
+}
+
+impl Default for FontSettings {
+    ///the printable ASCII characters, at 32 pixels, without SDF
+    fn default() -> Self {
+        Self {
+            size: 32.,
+            sdf_spread: None,
+            characters: (' '..='~').collect(),
+            padding: 1,
+        }
+    }
+}
+
+///a glyph rasterized by the font, its bitmap starts from the top left corner
+#[derive(Debug, Clone, PartialEq)]
+pub struct GlyphBitmap {
+    pub width: u32,
+    pub height: u32,
+    pub pixels: Vec<u8>,
+    ///the distance to the next glyph
+    pub advance: f32,
+    ///the bottom left corner of the bitmap from the pen position, the y axis going up
+    pub offset: [f32; 2],
+}
+
+///a glyph of a `FontAtlas`, in pixels of the atlas font size
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Glyph {
+    pub advance: f32,
+    ///the bottom left corner of the quad from the pen position, the y axis going up
+    pub offset: [f32; 2],
+    pub size: [f32; 2],
+    ///the texture coordinates of the top left and bottom right corners
+    pub uv_min: [f32; 2],
+    pub uv_max: [f32; 2],
+}
+
+///the glyphs of a font packed in a single channel texture, with their metrics and kerning
+#[derive(Clone)]
+pub struct FontAtlas {
+    size: f32,
+    sdf_spread: Option<u32>,
+    ascent: f32,
+    line_height: f32,
+    glyphs: HashMap<char, Glyph>,
+    kerning: HashMap<(char, char), f32>,
+    width: u32,
+    height: u32,
+    ///one byte per pixel, from the top left corner
+    pixels: Vec<u8>,
+}
+
+impl std::fmt::Debug for FontAtlas {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("FontAtlas")
+            .field("size", &self.size)
+            .field("sdf_spread", &self.sdf_spread)
+            .field("glyphs", &self.glyphs.len())
+            .field("dimensions", &self.dimensions())
+            .finish()
+    }
+}
+
+impl FontAtlas {
+    ///rasterize a TTF or OTF font
+    pub fn from_bytes(bytes: &[u8], settings: &FontSettings) -> Result<Self, TextError> {
+        let font = fontdue::Font::from_bytes(
+            bytes,
+            fontdue::FontSettings {
+                scale: settings.size,
+                ..Default::default()
+            },
+        )
+        .map_err(|e| TextError::FontError(e.to_string()))?;
+
+        let mut characters = settings.characters.clone();
+        characters.retain(|c| font.lookup_glyph_index(*c) != 0);
+        characters.sort();
+        characters.dedup();
+
+        let bitmaps = characters
+            .iter()
+            .map(|c| {
+                let (metrics, pixels) = font.rasterize(*c, settings.size);
+                let bitmap = GlyphBitmap {
+                    width: metrics.width as u32,
+                    height: metrics.height as u32,
+                    pixels,
+                    advance: metrics.advance_width,
+                    offset: [metrics.xmin as f32, metrics.ymin as f32],
+                };
+                (*c, bitmap)
+            })
+            .collect();
+
+        let mut kerning = HashMap::new();
+        for left in &characters {
+            for right in &characters {
+                match font.horizontal_kern(*left, *right, settings.size) {
+                    Some(kern) if kern != 0. => {
+                        kerning.insert((*left, *right), kern);
+                    }
+                    _ => {}
+                }
+            }
+        }
+
+        let (ascent, line_height) = match font.horizontal_line_metrics(settings.size) {
+            Some(metrics) => (metrics.ascent, metrics.new_line_size),
+            None => (settings.size, settings.size),
+        };
+
+        Ok(Self::from_bitmaps(
+            settings,
+            bitmaps,
+            kerning,
+            ascent,
+            line_height,
+        ))
+    }
+
+    pub fn from_path<P: AsRef<Path>>(path: P, settings: &FontSettings) -> Result<Self, TextError> {
+        let bytes = std::fs::read(path).map_err(TextError::IOError)?;
+        Self::from_bytes(&bytes, settings)
+    }
+
+    ///pack already rasterized glyphs, the signed distance fields are computed here
+    pub fn from_bitmaps(
+        settings: &FontSettings,
+        bitmaps: Vec<(char, GlyphBitmap)>,
+        kerning: HashMap<(char, char), f32>,
+        ascent: f32,
+        line_height: f32,
+    ) -> Self {
+        let bitmaps: Vec<_> = match settings.sdf_spread {
+            Some(spread) => bitmaps
+                .into_iter()
+                .map(|(c, bitmap)| (c, signed_distance_field(&bitmap, spread)))
+                .collect(),
+            None => bitmaps,
+        };
+
+        let sizes: Vec<_> = bitmaps
+            .iter()
+            .map(|(_, bitmap)| (bitmap.width, bitmap.height))
+            .collect();
+        let (width, height, positions) = pack_shelves(&sizes, settings.padding);
+
+        let mut pixels = vec![0; (width * height) as usize];
+        let mut glyphs = HashMap::new();
+        for ((c, bitmap), (x, y)) in bitmaps.into_iter().zip(positions) {
+            for row in 0..bitmap.height {
+                let source = (row * bitmap.width) as usize;
+                let destination = ((y + row) * width + x) as usize;
+                pixels[destination..destination + bitmap.width as usize]
+                    .copy_from_slice(&bitmap.pixels[source..source + bitmap.width as usize]);
+            }
+
+            glyphs.insert(
+                c,
+                Glyph {
+                    advance: bitmap.advance,
+                    offset: bitmap.offset,
+                    size: [bitmap.width as f32, bitmap.height as f32],
+                    uv_min: [x as f32 / width as f32, y as f32 / height as f32],
+                    uv_max: [
+                        (x + bitmap.width) as f32 / width as f32,
+                        (y + bitmap.height) as f32 / height as f32,
+                    ],
+                },
+            );
+        }
+
+        Self {
+            size: settings.size,
+            sdf_spread: settings.sdf_spread,
+            ascent,
+            line_height,
+            glyphs,
+            kerning,
+            width,
+            height,
+            pixels,
+        }
+    }
+
+    pub fn size(&self) -> f32 {
+        self.size
+    }
+
+    pub fn is_sdf(&self) -> bool {
+        self.sdf_spread.is_some()
+    }
+
+    pub fn ascent(&self) -> f32 {
+        self.ascent
+    }
+
+    pub fn line_height(&self) -> f32 {
+        self.line_height
+    }
+
+    pub fn dimensions(&self) -> (u32, u32) {
+        (self.width, self.height)
+    }
+
+    pub fn pixels(&self) -> &[u8] {
+        &self.pixels
+    }
+
+    ///the glyph of `c`, or the one of '?' if `c` is not in the atlas
+    pub fn glyph(&self, c: char) -> Option<&Glyph> {
+        self.glyphs.get(&c).or_else(|| self.glyphs.get(&'?'))
+    }
+
+    pub fn kerning(&self, left: char, right: char) -> f32 {
+        self.kerning.get(&(left, right)).copied().unwrap_or(0.)
+    }
+}
+
+/******************\
+*                 *
+*-----Layout------*
+*                 *
+\******************/
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TextAlign {
+    #[default]
+    Left,
+    Center,
+    Right,
+}
+
+///how a text is laid out, the lengths are in the units of the output (pixels for the screen
+///texts, world units for the labels)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TextLayout {
+    ///the height of the font
+    pub size: f32,
+    ///the lines are broken between the words to fit in this width
+    pub max_width: Option<f32>,
+    ///multiplies the line height of the font
+    pub line_spacing: f32,
+    pub align: TextAlign,
+}
+
+impl Default for TextLayout {
+    fn default() -> Self {
+        Self {
+            size: 16.,
+            max_width: None,
+            line_spacing: 1.,
+            align: TextAlign::Left,
+        }
+    }
+}
+
+impl TextLayout {
+    pub fn with_size(mut self, size: f32) -> Self {
+        self.size = size;
+        self
+    }
+
+    pub fn with_max_width(mut self, max_width: f32) -> Self {
+        self.max_width = Some(max_width);
+        self
+    }
+
+    pub fn with_align(mut self, align: TextAlign) -> Self {
+        self.align = align;
+        self
+    }
+}
+
+///a glyph quad, from the top left corner of the text with the y axis going down
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PositionedGlyph {
+    pub character: char,
+    pub min: [f32; 2],
+    pub max: [f32; 2],
+    pub uv_min: [f32; 2],
+    pub uv_max: [f32; 2],
+}
+
+impl FontAtlas {
+    ///the quads of the glyphs, the '\n' break the lines.
+    ///A word wider than `max_width` stays on its own line
+    pub fn layout(&self, text: &str, layout: &TextLayout) -> Vec<PositionedGlyph> {
+        let scale = layout.size / self.size;
+        let line_height = self.line_height * layout.line_spacing;
+        let max_width = layout.max_width.map(|width| width / scale);
+
+        let lines = self.break_lines(text, max_width);
+        let block_width = lines
+            .iter()
+            .map(|line| self.line_width(line))
+            .fold(0., f32::max);
+
+        let mut result = vec![];
+        for (i, line) in lines.iter().enumerate() {
+            let mut pen = match layout.align {
+                TextAlign::Left => 0.,
+                TextAlign::Center => (block_width - self.line_width(line)) / 2.,
+                TextAlign::Right => block_width - self.line_width(line),
+            };
+            let baseline = self.ascent + i as f32 * line_height;
+
+            let mut previous = None;
+            for c in line.chars() {
+                let Some(glyph) = self.glyph(c) else {
+                    continue;
+                };
+                if let Some(previous) = previous {
+                    pen += self.kerning(previous, c);
+                }
+                previous = Some(c);
+
+                if glyph.size[0] > 0. && glyph.size[1] > 0. {
+                    let left = pen + glyph.offset[0];
+                    let bottom = baseline - glyph.offset[1];
+                    result.push(PositionedGlyph {
+                        character: c,
+                        min: [left * scale, (bottom - glyph.size[1]) * scale],
+                        max: [(left + glyph.size[0]) * scale, bottom * scale],
+                        uv_min: glyph.uv_min,
+                        uv_max: glyph.uv_max,
+                    });
+                }
+                pen += glyph.advance;
+            }
+        }
+        result
+    }
+
+    ///the width and the height of the laid out text
+    pub fn measure(&self, text: &str, layout: &TextLayout) -> [f32; 2] {
+        let scale = layout.size / self.size;
+        let lines = self.break_lines(text, layout.max_width.map(|width| width / scale));
+        let width = lines
+            .iter()
+            .map(|line| self.line_width(line))
+            .fold(0., f32::max);
+        let height = match lines.len() {
+            0 => 0.,
+            n => (n - 1) as f32 * self.line_height * layout.line_spacing + self.line_height,
+        };
+        [width * scale, height * scale]
+    }
+
+    ///the advances and the kerning of the characters, in pixels of the atlas
+    fn line_width(&self, line: &str) -> f32 {
+        let mut width = 0.;
+        let mut previous = None;
+        for c in line.chars() {
+            let Some(glyph) = self.glyph(c) else {
+                continue;
+            };
+            if let Some(previous) = previous {
+                width += self.kerning(previous, c);
+            }
+            previous = Some(c);
+            width += glyph.advance;
+        }
+        width
+    }
+
+    fn break_lines(&self, text: &str, max_width: Option<f32>) -> Vec<String> {
+        let mut lines = vec![];
+        for paragraph in text.split('\n') {
+            let Some(max_width) = max_width else {
+                lines.push(paragraph.to_string());
+                continue;
+            };
+
+            let mut line = String::new();
+            for word in paragraph.split(' ') {
+                let candidate = match line.is_empty() {
+                    true => word.to_string(),
+                    false => format!("{} {}", line, word),
+                };
+                if !line.is_empty() && self.line_width(&candidate) > max_width {
+                    lines.push(std::mem::replace(&mut line, word.to_string()));
+                } else {
+                    line = candidate;
+                }
+            }
+            lines.push(line);
+        }
+        lines
+    }
+}
+
+/******************\
+*                 *
+*---TextRenderer--*
+*                 *
+\******************/
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct TextVertex {
+    ///the anchor of the world labels, unused by the screen texts
+    position: Arr3F32,
+    offset: [f32; 2],
+    tex_coords: [f32; 2],
+    color: Arr4F32,
+}
+
+implement_vertex!(TextVertex, position, offset, tex_coords, color);
+
+///texts queued during a frame and drawn by `TextRenderer::flush`, as screen overlays
+///or as labels in the world facing the camera
+pub struct TextRenderer {
+    atlas: FontAtlas,
+    texture: Texture2d,
+    program: Program,
+    screen: Vec<TextVertex>,
+    world: Vec<TextVertex>,
+}
+
+impl std::fmt::Debug for TextRenderer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("TextRenderer")
+            .field("atlas", &self.atlas)
+            .field("screen_glyphs", &(self.screen.len() / 6))
+            .field("world_glyphs", &(self.world.len() / 6))
+            .finish()
+    }
+}
+
+impl TextRenderer {
+    pub fn new<F: Facade>(facade: &F, atlas: FontAtlas) -> Result<Self, TextError> {
+        let image = RawImage2d {
+            data: Cow::Borrowed(atlas.pixels()),
+            width: atlas.width,
+            height: atlas.height,
+            format: ClientFormat::U8,
+        };
+        let texture = Texture2d::with_format(
+            facade,
+            image,
+            UncompressedFloatFormat::U8,
+            MipmapsOption::NoMipmap,
+        )
+        .map_err(TextError::TextureCreationError)?;
+        let program = Program::from_source(facade, TEXT_VERTEX_SHADER, TEXT_FRAGMENT_SHADER, None)
+            .map_err(TextError::ProgramCreationError)?;
+
+        Ok(Self {
+            atlas,
+            texture,
+            program,
+            screen: vec![],
+            world: vec![],
+        })
+    }
+
+    pub fn atlas(&self) -> &FontAtlas {
+        &self.atlas
+    }
+
+    pub fn clear(&mut self) {
+        self.screen.clear();
+        self.world.clear();
+    }
+
+    ///`position` is the top left corner of the text, in pixels from the top left corner of the
+    ///target
+    pub fn queue_screen(
+        &mut self,
+        text: &str,
+        position: [f32; 2],
+        color: Arr4F32,
+        layout: &TextLayout,
+    ) {
+        for glyph in self.atlas.layout(text, layout) {
+            push_quad(&mut self.screen, [0.; 3], color, glyph, |[x, y]| {
+                [position[0] + x, position[1] + y]
+            });
+        }
+    }
+
+    ///the text is centered on `position` and faces the camera, its size is in world units
+    pub fn queue_world(
+        &mut self,
+        text: &str,
+        position: Arr3F32,
+        color: Arr4F32,
+        layout: &TextLayout,
+    ) {
+        let [width, height] = self.atlas.measure(text, layout);
+        for glyph in self.atlas.layout(text, layout) {
+            push_quad(&mut self.world, position, color, glyph, |[x, y]| {
+                [x - width / 2., height / 2. - y]
+            });
+        }
+    }
+
+    ///draw the queued texts and empty the queues, the screen texts are drawn over everything
+    pub fn flush<F, S, C>(
+        &mut self,
+        facade: &F,
+        target: &mut S,
+        camera: &C,
+    ) -> Result<(), TextError>
+    where
+        F: Facade,
+        S: Surface,
+        C: Camera + ?Sized,
+    {
+        let (width, height) = target.get_dimensions();
+        let view: Mat4Arr = camera.view_matrix().into();
+        let projection: Mat4Arr = camera.projection_matrix().into();
+
+        let sampler = SamplerBehavior {
+            wrap_function: (
+                SamplerWrapFunction::Clamp,
+                SamplerWrapFunction::Clamp,
+                SamplerWrapFunction::Clamp,
+            ),
+            minify_filter: MinifySamplerFilter::Linear,
+            magnify_filter: MagnifySamplerFilter::Linear,
+            ..Default::default()
+        };
+
+        let mut uniforms = RenderUniforms::new();
+        uniforms.set(
+            "atlas",
+            UniformValue::Texture2d(&self.texture, Some(sampler)),
+        );
+        uniforms.set("sdf", UniformValue::Bool(self.atlas.is_sdf()));
+        uniforms.set("view", UniformValue::Mat4(view));
+        uniforms.set("projection", UniformValue::Mat4(projection));
+        uniforms.set(
+            "screen_size",
+            UniformValue::Vec2([width as f32, height as f32]),
+        );
+
+        let world = DrawParameters {
+            blend: Blend::alpha_blending(),
+            depth: Depth {
+                test: DepthTest::IfLess,
+                write: false,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let screen = DrawParameters {
+            blend: Blend::alpha_blending(),
+            ..Default::default()
+        };
+
+        let world_vertices = std::mem::take(&mut self.world);
+        let screen_vertices = std::mem::take(&mut self.screen);
+        for (vertices, billboard, draw_parameters) in [
+            (world_vertices, true, world),
+            (screen_vertices, false, screen),
+        ] {
+            if vertices.is_empty() {
+                continue;
+            }
+
+            let buffer = VertexBuffer::new(facade, &vertices)
+                .map_err(TextError::VertexBufferCreationError)?;
+            uniforms.set("billboard", UniformValue::Bool(billboard));
+            target
+                .draw(
+                    &buffer,
+                    NoIndices(PrimitiveType::TrianglesList),
+                    &self.program,
+                    &uniforms,
+                    &draw_parameters,
+                )
+                .map_err(TextError::DrawError)?;
+        }
+        Ok(())
+    }
+}
+
+///two triangles, `place` gives the offset of a corner of the glyph
+fn push_quad(
+    vertices: &mut Vec<TextVertex>,
+    position: Arr3F32,
+    color: Arr4F32,
+    glyph: PositionedGlyph,
+    place: impl Fn([f32; 2]) -> [f32; 2],
+) {
+    let corner = |x: usize, y: usize| TextVertex {
+        position,
+        offset: place([[glyph.min, glyph.max][x][0], [glyph.min, glyph.max][y][1]]),
+        tex_coords: [
+            [glyph.uv_min, glyph.uv_max][x][0],
+            [glyph.uv_min, glyph.uv_max][y][1],
+        ],
+        color,
+    };
+
+    vertices.extend([
+        corner(0, 0),
+        corner(0, 1),
+        corner(1, 1),
+        corner(0, 0),
+        corner(1, 1),
+        corner(1, 0),
+    ]);
+}
+
+const TEXT_VERTEX_SHADER: &str = r#"
+#version 140
+
+in vec3 position;
+in vec2 offset;
+in vec2 tex_coords;
+in vec4 color;
+
+uniform bool billboard;
+uniform mat4 view;
+uniform mat4 projection;
+uniform vec2 screen_size;
+
+out vec2 v_tex_coords;
+out vec4 v_color;
+
+void main() {
+    v_tex_coords = tex_coords;
+    v_color = color;
+
+    if (billboard) {
+        vec4 view_position = view * vec4(position, 1.0);
+        view_position.xy += offset;
+        gl_Position = projection * view_position;
+    } else {
+        vec2 ndc = offset / screen_size * 2.0 - 1.0;
+        gl_Position = vec4(ndc.x, -ndc.y, 0.0, 1.0);
+    }
+}
+"#;
+
+const TEXT_FRAGMENT_SHADER: &str = r#"
+#version 140
+
+in vec2 v_tex_coords;
+in vec4 v_color;
+
+uniform sampler2D atlas;
+uniform bool sdf;
+
+out vec4 color;
+
+void main() {
+    float value = texture(atlas, v_tex_coords).r;
+    float alpha = value;
+    if (sdf) {
+        float width = max(fwidth(value), 0.0001);
+        alpha = smoothstep(0.5 - width, 0.5 + width, value);
+    }
+    color = vec4(v_color.rgb, v_color.a * alpha);
+}
+"#;
+
+/******************\
+*                 *
+*------Utils------*
+*                 *
+\******************/
+
+///the glyph grows by `spread` pixels on every side, 128 is the edge of the glyph and the values
+///go up inside it
+pub(crate) fn signed_distance_field(bitmap: &GlyphBitmap, spread: u32) -> GlyphBitmap {
+    let spread = spread.max(1) as i64;
+    let (width, height) = (
+        bitmap.width as i64 + 2 * spread,
+        bitmap.height as i64 + 2 * spread,
+    );
+    let inside = |x: i64, y: i64| {
+        let (x, y) = (x - spread, y - spread);
+        x >= 0
+            && y >= 0
+            && x < bitmap.width as i64
+            && y < bitmap.height as i64
+            && bitmap.pixels[(y * bitmap.width as i64 + x) as usize] >= 128
+    };
+
+    let mut pixels = Vec::with_capacity((width * height) as usize);
+    for y in 0..height {
+        for x in 0..width {
+            let state = inside(x, y);
+            let mut nearest = (spread * spread) as f32;
+            for dy in -spread..=spread {
+                for dx in -spread..=spread {
+                    let distance = (dx * dx + dy * dy) as f32;
+                    if distance < nearest && inside(x + dx, y + dy) != state {
+                        nearest = distance;
+                    }
+                }
+            }
+
+            let distance = nearest.sqrt().min(spread as f32);
+            let signed = if state { distance } else { -distance };
+            let value = 0.5 + signed / (2. * spread as f32);
+            pixels.push((value.clamp(0., 1.) * 255.).round() as u8);
+        }
+    }
+
+    GlyphBitmap {
+        width: width as u32,
+        height: height as u32,
+        pixels,
+        advance: bitmap.advance,
+        offset: [
+            bitmap.offset[0] - spread as f32,
+            bitmap.offset[1] - spread as f32,
+        ],
+    }
+}
+
+///place the rectangles in rows, from the tallest. Returns the dimensions of the atlas, powers of
+///two, and the top left corners of the rectangles
+pub(crate) fn pack_shelves(sizes: &[(u32, u32)], padding: u32) -> (u32, u32, Vec<(u32, u32)>) {
+    let area: u32 = sizes
+        .iter()
+        .map(|(w, h)| (w + padding) * (h + padding))
+        .sum();
+    let widest = sizes
+        .iter()
+        .map(|(w, _)| w + 2 * padding)
+        .max()
+        .unwrap_or(1);
+    let width = ((area as f32).sqrt().ceil() as u32)
+        .max(widest)
+        .max(1)
+        .next_power_of_two();
+
+    let mut order: Vec<_> = (0..sizes.len()).collect();
+    order.sort_by_key(|i| std::cmp::Reverse(sizes[*i].1));
+
+    let mut positions = vec![(0, 0); sizes.len()];
+    let (mut x, mut y, mut shelf_height) = (padding, padding, 0);
+    for i in order {
+        let (w, h) = sizes[i];
+        if x + w + padding > width {
+            x = padding;
+            y += shelf_height + padding;
+            shelf_height = 0;
+        }
+        positions[i] = (x, y);
+        x += w + padding;
+        shelf_height = shelf_height.max(h);
+    }
+
+    let height = (y + shelf_height + padding).max(1).next_power_of_two();
+    (width, height, positions)
+}
+
+#[derive(Debug)]
+pub enum TextError {
+    IOError(std::io::Error),
+    FontError(String),
+    TextureCreationError(TextureCreationError),
+    ProgramCreationError(ProgramCreationError),
+    VertexBufferCreationError(BufferCreationError),
+    DrawError(DrawError),
+}
+
+impl Display for TextError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TextError::IOError(e) => write!(f, "io error :{}", e),
+            TextError::FontError(e) => write!(f, "font error :{}", e),
+            TextError::TextureCreationError(e) => write!(f, "texture creation error :{}", e),
+            TextError::ProgramCreationError(e) => write!(f, "program creation error :{}", e),
+            TextError::VertexBufferCreationError(e) => {
+                write!(f, "vertex buffer creation error :{}", e)
+            }
+            TextError::DrawError(e) => write!(f, "draw error :{}", e),
+        }
+    }
+}
+
+impl std::error::Error for TextError {}
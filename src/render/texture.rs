@@ -0,0 +1,396 @@
+use std::{
+    collections::HashMap,
+    fmt::Display,
+    path::{Path, PathBuf},
+    rc::Rc,
+};
+
+use glium::{
+    backend::Facade,
+    framebuffer::{SimpleFrameBuffer, ValidationError},
+    texture::{
+        CubeLayer, Cubemap, MipmapsOption, RawImage2d, SrgbTexture2d, SrgbTexture2dArray,
+        TextureCreationError,
+    },
+    uniforms::{MagnifySamplerFilter, MinifySamplerFilter, SamplerBehavior, SamplerWrapFunction},
+    BlitTarget, Surface, Texture2d,
+};
+
+/******************\
+*                 *
+*----ImageData----*
+*                 *
+\******************/
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImageFormat {
+    Png,
+    Jpeg,
+    Tga,
+}
+
+impl ImageFormat {
+    pub fn from_extension(extension: &str) -> Option<Self> {
+        match extension.to_ascii_lowercase().as_str() {
+            "png" => Some(ImageFormat::Png),
+            "jpg" | "jpeg" => Some(ImageFormat::Jpeg),
+            "tga" => Some(ImageFormat::Tga),
+            _ => None,
+        }
+    }
+
+    pub fn from_path<P: AsRef<Path>>(path: P) -> Option<Self> {
+        path.as_ref()
+            .extension()
+            .and_then(|extension| extension.to_str())
+            .and_then(Self::from_extension)
+    }
+
+    ///from the magic number of the file, the TGA files have none so it is the fallback
+    pub fn guess(bytes: &[u8]) -> Self {
+        match bytes {
+            [0x89, b'P', b'N', b'G', ..] => ImageFormat::Png,
+            [0xFF, 0xD8, 0xFF, ..] => ImageFormat::Jpeg,
+            _ => ImageFormat::Tga,
+        }
+    }
+
+    fn to_image_format(self) -> image::ImageFormat {
+        match self {
+            ImageFormat::Png => image::ImageFormat::Png,
+            ImageFormat::Jpeg => image::ImageFormat::Jpeg,
+            ImageFormat::Tga => image::ImageFormat::Tga,
+        }
+    }
+}
+
+///a decoded image, in rgba8 from the top left corner
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ImageData {
+    pub width: u32,
+    pub height: u32,
+    pub pixels: Vec<u8>,
+}
+
+impl ImageData {
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, TextureError> {
+        Self::from_bytes_with_format(bytes, ImageFormat::guess(bytes))
+    }
+
+    pub fn from_bytes_with_format(bytes: &[u8], format: ImageFormat) -> Result<Self, TextureError> {
+        let image = image::load_from_memory_with_format(bytes, format.to_image_format())
+            .map_err(TextureError::ImageError)?
+            .to_rgba8();
+
+        Ok(Self {
+            width: image.width(),
+            height: image.height(),
+            pixels: image.into_raw(),
+        })
+    }
+
+    ///the format comes from the extension, or from the content without known extension
+    pub fn from_path<P: AsRef<Path>>(path: P) -> Result<Self, TextureError> {
+        let bytes = std::fs::read(path.as_ref()).map_err(TextureError::IOError)?;
+        match ImageFormat::from_path(path) {
+            Some(format) => Self::from_bytes_with_format(&bytes, format),
+            None => Self::from_bytes(&bytes),
+        }
+    }
+
+    pub fn dimensions(&self) -> (u32, u32) {
+        (self.width, self.height)
+    }
+
+    ///OpenGL textures start from the bottom left corner, so the rows are reversed
+    pub fn to_raw_image(&self) -> RawImage2d<'_, u8> {
+        RawImage2d::from_raw_rgba_reversed(&self.pixels, self.dimensions())
+    }
+}
+
+/******************\
+*                 *
+*--TextureSampler-*
+*                 *
+\******************/
+
+///how a texture is sampled, given with the texture to the materials
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TextureSampler {
+    pub wrap: SamplerWrapFunction,
+    pub minify: MinifySamplerFilter,
+    pub magnify: MagnifySamplerFilter,
+    ///1 disables the anisotropic filtering
+    pub anisotropy: u16,
+}
+
+impl Default for TextureSampler {
+    ///repeated, trilinear filtering
+    fn default() -> Self {
+        Self {
+            wrap: SamplerWrapFunction::Repeat,
+            minify: MinifySamplerFilter::LinearMipmapLinear,
+            magnify: MagnifySamplerFilter::Linear,
+            anisotropy: 1,
+        }
+    }
+}
+
+impl TextureSampler {
+    ///for the pixel art, without mipmaps
+    pub fn nearest() -> Self {
+        Self {
+            minify: MinifySamplerFilter::Nearest,
+            magnify: MagnifySamplerFilter::Nearest,
+            ..Default::default()
+        }
+    }
+
+    pub fn with_wrap(mut self, wrap: SamplerWrapFunction) -> Self {
+        self.wrap = wrap;
+        self
+    }
+
+    pub fn with_filter(
+        mut self,
+        minify: MinifySamplerFilter,
+        magnify: MagnifySamplerFilter,
+    ) -> Self {
+        self.minify = minify;
+        self.magnify = magnify;
+        self
+    }
+
+    pub fn with_anisotropy(mut self, anisotropy: u16) -> Self {
+        self.anisotropy = anisotropy.max(1);
+        self
+    }
+
+    pub fn to_behavior(&self) -> SamplerBehavior {
+        SamplerBehavior {
+            wrap_function: (self.wrap, self.wrap, self.wrap),
+            minify_filter: self.minify,
+            magnify_filter: self.magnify,
+            max_anisotropy: self.anisotropy,
+            ..Default::default()
+        }
+    }
+}
+
+impl From<TextureSampler> for SamplerBehavior {
+    fn from(value: TextureSampler) -> Self {
+        value.to_behavior()
+    }
+}
+
+/******************\
+*                 *
+*-----Loading-----*
+*                 *
+\******************/
+
+fn mipmaps_option(mipmaps: bool) -> MipmapsOption {
+    match mipmaps {
+        true => MipmapsOption::AutoGeneratedMipmaps,
+        false => MipmapsOption::NoMipmap,
+    }
+}
+
+///a color texture, the pixels are in the sRGB color space
+pub fn srgb_texture<F: Facade>(
+    facade: &F,
+    image: &ImageData,
+    mipmaps: bool,
+) -> Result<SrgbTexture2d, TextureError> {
+    SrgbTexture2d::with_mipmaps(facade, image.to_raw_image(), mipmaps_option(mipmaps))
+        .map_err(TextureError::TextureCreationError)
+}
+
+///a texture holding data instead of colors, like a normal map
+pub fn linear_texture<F: Facade>(
+    facade: &F,
+    image: &ImageData,
+    mipmaps: bool,
+) -> Result<Texture2d, TextureError> {
+    Texture2d::with_mipmaps(facade, image.to_raw_image(), mipmaps_option(mipmaps))
+        .map_err(TextureError::TextureCreationError)
+}
+
+pub fn load_srgb_texture<F: Facade, P: AsRef<Path>>(
+    facade: &F,
+    path: P,
+) -> Result<SrgbTexture2d, TextureError> {
+    srgb_texture(facade, &ImageData::from_path(path)?, true)
+}
+
+///every layer must have the same dimensions
+pub fn srgb_texture_array<F: Facade>(
+    facade: &F,
+    layers: &[ImageData],
+    mipmaps: bool,
+) -> Result<SrgbTexture2dArray, TextureError> {
+    check_dimensions(layers)?;
+    let layers = layers.iter().map(ImageData::to_raw_image).collect();
+    SrgbTexture2dArray::with_mipmaps(facade, layers, mipmaps_option(mipmaps))
+        .map_err(TextureError::TextureCreationError)
+}
+
+///the order of the faces given to `cubemap`
+pub const CUBE_LAYERS: [CubeLayer; 6] = [
+    CubeLayer::PositiveX,
+    CubeLayer::NegativeX,
+    CubeLayer::PositiveY,
+    CubeLayer::NegativeY,
+    CubeLayer::PositiveZ,
+    CubeLayer::NegativeZ,
+];
+
+///the faces are given in the order of `CUBE_LAYERS`, they must be squares of the same size
+pub fn cubemap<F: Facade>(facade: &F, faces: &[ImageData; 6]) -> Result<Cubemap, TextureError> {
+    check_dimensions(faces)?;
+    let (size, height) = faces[0].dimensions();
+    if size != height {
+        return Err(TextureError::InvalidDimensions(faces[0].dimensions()));
+    }
+
+    let cubemap = Cubemap::empty(facade, size).map_err(TextureError::TextureCreationError)?;
+    let target = BlitTarget {
+        left: 0,
+        bottom: 0,
+        width: size as i32,
+        height: size as i32,
+    };
+
+    //unlike the other textures, the faces of a cubemap start from their top left corner
+    for (face, layer) in faces.iter().zip(CUBE_LAYERS) {
+        let image = RawImage2d::from_raw_rgba(face.pixels.clone(), face.dimensions());
+        let texture = Texture2d::new(facade, image).map_err(TextureError::TextureCreationError)?;
+        let framebuffer = SimpleFrameBuffer::new(facade, cubemap.main_level().image(layer))
+            .map_err(TextureError::FrameBufferValidationError)?;
+        texture.as_surface().blit_whole_color_to(
+            &framebuffer,
+            &target,
+            MagnifySamplerFilter::Linear,
+        );
+    }
+    Ok(cubemap)
+}
+
+pub fn load_cubemap<F: Facade, P: AsRef<Path>>(
+    facade: &F,
+    paths: [P; 6],
+) -> Result<Cubemap, TextureError> {
+    let [a, b, c, d, e, f] = paths;
+    let faces = [
+        ImageData::from_path(a)?,
+        ImageData::from_path(b)?,
+        ImageData::from_path(c)?,
+        ImageData::from_path(d)?,
+        ImageData::from_path(e)?,
+        ImageData::from_path(f)?,
+    ];
+    cubemap(facade, &faces)
+}
+
+fn check_dimensions(images: &[ImageData]) -> Result<(), TextureError> {
+    let Some(first) = images.first() else {
+        return Err(TextureError::InvalidDimensions((0, 0)));
+    };
+    match images
+        .iter()
+        .find(|image| image.dimensions() != first.dimensions())
+    {
+        Some(image) => Err(TextureError::InvalidDimensions(image.dimensions())),
+        None => Ok(()),
+    }
+}
+
+/******************\
+*                 *
+*---TextureCache--*
+*                 *
+\******************/
+
+///a `map_Kd` (or other map) of a mtl file, relative to the directory of the mtl file.
+///The windows separators are replaced
+pub fn resolve_map_path<P: AsRef<Path>>(mtl_directory: P, map: &str) -> PathBuf {
+    let map = map.replace('\\', "/");
+    let map = Path::new(&map);
+    match map.is_absolute() {
+        true => map.to_path_buf(),
+        false => mtl_directory.as_ref().join(map),
+    }
+}
+
+///the sRGB textures loaded from files, so the materials sharing a file share its texture
+#[derive(Default)]
+pub struct TextureCache {
+    textures: HashMap<PathBuf, Rc<SrgbTexture2d>>,
+}
+
+impl TextureCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn load<F: Facade, P: AsRef<Path>>(
+        &mut self,
+        facade: &F,
+        path: P,
+    ) -> Result<Rc<SrgbTexture2d>, TextureError> {
+        let path = path.as_ref();
+        if let Some(texture) = self.textures.get(path) {
+            return Ok(texture.clone());
+        }
+
+        let texture = Rc::new(load_srgb_texture(facade, path)?);
+        self.textures.insert(path.to_path_buf(), texture.clone());
+        Ok(texture)
+    }
+
+    pub fn get<P: AsRef<Path>>(&self, path: P) -> Option<&Rc<SrgbTexture2d>> {
+        self.textures.get(path.as_ref())
+    }
+
+    pub fn len(&self) -> usize {
+        self.textures.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.textures.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.textures.clear();
+    }
+}
+
+#[derive(Debug)]
+pub enum TextureError {
+    IOError(std::io::Error),
+    ImageError(image::ImageError),
+    TextureCreationError(TextureCreationError),
+    FrameBufferValidationError(ValidationError),
+    ///the images of an array or a cubemap don't have the same dimensions
+    InvalidDimensions((u32, u32)),
+    MaterialError(super::MaterialError),
+}
+
+impl Display for TextureError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TextureError::IOError(e) => write!(f, "IO error :{}", e),
+            TextureError::ImageError(e) => write!(f, "image error :{}", e),
+            TextureError::TextureCreationError(e) => write!(f, "texture creation error :{}", e),
+            TextureError::FrameBufferValidationError(e) => {
+                write!(f, "frame buffer validation error :{}", e)
+            }
+            TextureError::InvalidDimensions((width, height)) => {
+                write!(f, "invalid dimensions :{}x{}", width, height)
+            }
+            TextureError::MaterialError(e) => write!(f, "material error :{}", e),
+        }
+    }
+}
+
+impl std::error::Error for TextureError {}
@@ -0,0 +1,92 @@
+use glium::uniforms::{AsUniformValue, UniformValue, Uniforms};
+
+use crate::{camera::OrthographicCam, object_traits::GetableSceneObject};
+
+///something giving uniforms to a `RenderUniforms`, like a camera, a material or the lights
+pub trait UniformProvider {
+    fn provide_uniforms<'a>(&'a self, uniforms: &mut RenderUniforms<'a>);
+}
+
+///uniforms merged from several sources, a uniform replaces the previous one with the same name
+#[derive(Clone, Default)]
+pub struct RenderUniforms<'a> {
+    values: Vec<(String, UniformValue<'a>)>,
+}
+
+impl<'a> RenderUniforms<'a> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn set(&mut self, name: &str, value: UniformValue<'a>) {
+        match self.values.iter_mut().find(|(n, _)| n == name) {
+            Some((_, old)) => *old = value,
+            None => self.values.push((name.to_string(), value)),
+        }
+    }
+
+    pub fn get(&self, name: &str) -> Option<&UniformValue<'a>> {
+        self.values
+            .iter()
+            .find(|(n, _)| n == name)
+            .map(|(_, value)| value)
+    }
+
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    pub fn add<T: AsUniformValue>(mut self, name: &str, value: &'a T) -> Self {
+        self.set(name, value.as_uniform_value());
+        self
+    }
+
+    pub fn with<P: UniformProvider + ?Sized>(mut self, provider: &'a P) -> Self {
+        provider.provide_uniforms(&mut self);
+        self
+    }
+
+    ///add glium uniforms, like the ones built with `glium::uniform!`
+    pub fn merge<U: Uniforms>(mut self, uniforms: &'a U) -> Self {
+        uniforms.visit_values(|name, value| self.set(name, value));
+        self
+    }
+}
+
+impl Uniforms for RenderUniforms<'_> {
+    fn visit_values<'b, F: FnMut(&str, UniformValue<'b>)>(&'b self, mut output: F) {
+        for (name, value) in &self.values {
+            output(name, *value);
+        }
+    }
+}
+
+///`view_projection`, the uniform value of the camera
+impl UniformProvider for OrthographicCam {
+    fn provide_uniforms<'a>(&'a self, uniforms: &mut RenderUniforms<'a>) {
+        uniforms.set("view_projection", self.as_uniform_value());
+    }
+}
+
+///the transform of an object, as `model`
+pub struct ModelTransform {
+    pub model: [[f32; 4]; 4],
+}
+
+impl ModelTransform {
+    pub fn of<O: GetableSceneObject + ?Sized>(object: &O) -> Self {
+        Self {
+            model: object.get_model_matrix().into(),
+        }
+    }
+}
+
+impl UniformProvider for ModelTransform {
+    fn provide_uniforms<'a>(&'a self, uniforms: &mut RenderUniforms<'a>) {
+        uniforms.set("model", UniformValue::Mat4(self.model));
+    }
+}
@@ -0,0 +1,156 @@
+//4x4 matrices stored as column major arrays, the layout of the glium uniforms and of
+//`Camera::view_matrix().into()`
+
+use super::types_util::{Arr3F32, Mat4Arr};
+
+pub const IDENTITY: Mat4Arr = [
+    [1., 0., 0., 0.],
+    [0., 1., 0., 0.],
+    [0., 0., 1., 0.],
+    [0., 0., 0., 1.],
+];
+
+///`a * b`
+pub fn mul(a: Mat4Arr, b: Mat4Arr) -> Mat4Arr {
+    core::array::from_fn(|column| {
+        core::array::from_fn(|row| (0..4).map(|k| a[k][row] * b[column][k]).sum())
+    })
+}
+
+pub fn transpose(matrix: &Mat4Arr) -> Mat4Arr {
+    core::array::from_fn(|column| core::array::from_fn(|row| matrix[row][column]))
+}
+
+///the result is divided by `w`
+pub fn transform_point(matrix: &Mat4Arr, point: Arr3F32) -> Arr3F32 {
+    let [x, y, z, w]: [f32; 4] = core::array::from_fn(|row| {
+        matrix[0][row] * point[0]
+            + matrix[1][row] * point[1]
+            + matrix[2][row] * point[2]
+            + matrix[3][row]
+    });
+    if w == 0. {
+        return [x, y, z];
+    }
+    [x / w, y / w, z / w]
+}
+
+///the translation is ignored
+pub fn transform_vector(matrix: &Mat4Arr, vector: Arr3F32) -> Arr3F32 {
+    core::array::from_fn(|row| {
+        matrix[0][row] * vector[0] + matrix[1][row] * vector[1] + matrix[2][row] * vector[2]
+    })
+}
+
+///Gauss-Jordan elimination, `None` if `matrix` can't be inverted
+pub fn invert(matrix: &Mat4Arr) -> Option<Mat4Arr> {
+    //the rows of `matrix`, next to the identity
+    let mut rows: [[f32; 8]; 4] = core::array::from_fn(|row| {
+        core::array::from_fn(|column| match column {
+            0..=3 => matrix[column][row],
+            _ => (column - 4 == row) as u8 as f32,
+        })
+    });
+
+    for column in 0..4 {
+        let pivot =
+            (column..4).max_by(|a, b| rows[*a][column].abs().total_cmp(&rows[*b][column].abs()))?;
+        if rows[pivot][column].abs() < 1e-12 {
+            return None;
+        }
+        rows.swap(column, pivot);
+
+        let pivot_value = rows[column][column];
+        rows[column] = rows[column].map(|value| value / pivot_value);
+        for row in 0..4 {
+            if row != column {
+                let factor = rows[row][column];
+                let pivot_row = rows[column];
+                for (value, pivot) in rows[row].iter_mut().zip(pivot_row) {
+                    *value -= factor * pivot;
+                }
+            }
+        }
+    }
+
+    Some(core::array::from_fn(|column| {
+        core::array::from_fn(|row| rows[row][column + 4])
+    }))
+}
+
+///a view matrix at `eye` looking toward `direction`, the up vector is `y` unless `direction`
+///is vertical
+pub fn look_at(eye: Arr3F32, direction: Arr3F32) -> Mat4Arr {
+    let forward = normalize(direction);
+    let up = if forward[1].abs() > 0.999 {
+        [0., 0., 1.]
+    } else {
+        [0., 1., 0.]
+    };
+    let side = normalize(cross(forward, up));
+    let up = cross(side, forward);
+
+    [
+        [side[0], up[0], -forward[0], 0.],
+        [side[1], up[1], -forward[1], 0.],
+        [side[2], up[2], -forward[2], 0.],
+        [-dot(side, eye), -dot(up, eye), dot(forward, eye), 1.],
+    ]
+}
+
+pub fn orthographic(half_width: f32, half_height: f32, near: f32, far: f32) -> Mat4Arr {
+    [
+        [1. / half_width, 0., 0., 0.],
+        [0., 1. / half_height, 0., 0.],
+        [0., 0., -2. / (far - near), 0.],
+        [0., 0., -(far + near) / (far - near), 1.],
+    ]
+}
+
+///`fov_y` is in radians
+pub fn perspective(fov_y: f32, aspect: f32, near: f32, far: f32) -> Mat4Arr {
+    let f = 1. / (fov_y / 2.).tan();
+    [
+        [f / aspect, 0., 0., 0.],
+        [0., f, 0., 0.],
+        [0., 0., (far + near) / (near - far), -1.],
+        [0., 0., 2. * far * near / (near - far), 0.],
+    ]
+}
+
+pub(crate) fn add(a: Arr3F32, b: Arr3F32) -> Arr3F32 {
+    [a[0] + b[0], a[1] + b[1], a[2] + b[2]]
+}
+
+pub(crate) fn sub(a: Arr3F32, b: Arr3F32) -> Arr3F32 {
+    [a[0] - b[0], a[1] - b[1], a[2] - b[2]]
+}
+
+pub(crate) fn scale(v: Arr3F32, factor: f32) -> Arr3F32 {
+    v.map(|c| c * factor)
+}
+
+pub(crate) fn length(v: Arr3F32) -> f32 {
+    dot(v, v).sqrt()
+}
+
+///a zero vector is returned as is
+pub(crate) fn normalize(v: Arr3F32) -> Arr3F32 {
+    let length = length(v);
+    if length == 0. {
+        return v;
+    }
+    scale(v, 1. / length)
+}
+
+pub(crate) fn cross(a: Arr3F32, b: Arr3F32) -> Arr3F32 {
+    [
+        a[1] * b[2] - a[2] * b[1],
+        a[2] * b[0] - a[0] * b[2],
+        a[0] * b[1] - a[1] * b[0],
+    ]
+}
+
+pub(crate) fn dot(a: Arr3F32, b: Arr3F32) -> f32 {
+    a[0] * b[0] + a[1] * b[1] + a[2] * b[2]
+}
@@ -1,3 +1,7 @@
 pub(crate) mod macro_util;
 pub mod math_util;
+pub mod matrix_util;
 pub mod types_util;
+
+#[cfg(test)]
+pub(crate) mod test_util;
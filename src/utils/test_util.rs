@@ -0,0 +1,40 @@
+//an OpenGL context without window for the tests, made with the first EGL device
+
+use glium::backend::glutin::headless::Headless;
+
+///`None` if the machine has no EGL device or if it can't give an OpenGL 3.3 context,
+///the tests needing a GPU are then skipped
+#[cfg(not(target_os = "macos"))]
+pub(crate) fn headless_facade() -> Option<Headless> {
+    use glutin::{
+        api::egl::{device::Device, display::Display as EglDisplay},
+        config::{ConfigSurfaceTypes, ConfigTemplateBuilder},
+        context::{ContextApi, ContextAttributesBuilder, Version},
+        display::Display,
+        prelude::*,
+    };
+
+    let device = Device::query_devices().ok()?.next()?;
+    let display = Display::Egl(unsafe { EglDisplay::with_device(&device, None) }.ok()?);
+
+    let template = ConfigTemplateBuilder::new()
+        .with_surface_type(ConfigSurfaceTypes::empty())
+        .build();
+    let config = unsafe { display.find_configs(template) }.ok()?.next()?;
+
+    let attributes = ContextAttributesBuilder::new()
+        .with_context_api(ContextApi::OpenGl(Some(Version::new(3, 3))))
+        .build(None);
+    let context = unsafe { display.create_context(&config, &attributes) }
+        .ok()?
+        .make_current_surfaceless()
+        .ok()?;
+
+    unsafe { Headless::new(context) }.ok()
+}
+
+///there is no EGL on macOS
+#[cfg(target_os = "macos")]
+pub(crate) fn headless_facade() -> Option<Headless> {
+    None
+}
@@ -10,3 +10,5 @@ pub type Vec3 = VectorMath<f32, 3>;
 pub type Vec4 = VectorMath<f32, 4>;
 pub type Mat4 = Matrix<f32, 4, 4>;
 pub type Mat3 = Matrix<f32, 3, 3>;
+///a column major `Mat4`, like the glium uniforms
+pub type Mat4Arr = [[f32; 4]; 4];